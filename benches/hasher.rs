@@ -0,0 +1,162 @@
+//! Compares the hashers of the outer plonky2 proof: BN254 Poseidon (`HasherChip`), BN254
+//! Poseidon2 (`Poseidon2HasherChip`), Goldilocks Poseidon (`PublicInputsHasherChip`) and Keccak
+//! (`KeccakHasherChip`). The same plonky2 circuit is proven with each config, and its verifier
+//! circuit is checked with the mock prover.
+//!
+//! Run with `cargo bench --bench hasher`.
+
+use std::cell::Cell;
+use std::time::Instant;
+
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{ConstraintSystem, Error},
+};
+use halo2wrong_maingate::AssignedValue;
+use plonky2::{
+    field::{goldilocks_field::GoldilocksField, types::Field},
+    fri::{reduction_strategies::FriReductionStrategy, FriConfig},
+    hash::poseidon::SPONGE_WIDTH,
+    iop::witness::{PartialWitness, WitnessWrite},
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::CircuitConfig,
+        config::{GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig},
+    },
+};
+use stark_verifier::{
+    bn254_poseidon::plonky2_config::{
+        Bn254Poseidon2GoldilocksConfig, Bn254PoseidonGoldilocksConfig,
+    },
+    chip::{
+        goldilocks_chip::GoldilocksChipConfig,
+        hasher_chip::{HasherChip, HasherInstructions},
+        keccak_hasher_chip::KeccakHasherChip,
+        poseidon2_hasher_chip::Poseidon2HasherChip,
+        public_inputs_hasher_chip::PublicInputsHasherChip,
+    },
+    context::RegionCtx,
+    types::{assigned::AssignedHashValues, encoding::VerifierInput},
+    verifier_api::{mock_prove, verifier_with_hasher},
+    verifier_circuit::ProofTuple,
+};
+
+type F = GoldilocksField;
+const D: usize = 2;
+const DEGREE: u32 = 20;
+
+thread_local! {
+    static HASHER_ROWS: Cell<usize> = Cell::new(0);
+    static HASHER_CALLS: Cell<usize> = Cell::new(0);
+}
+
+/// Delegates to `H`, counting the rows it assigns.
+#[derive(Clone, Debug)]
+struct CountingHasher<H>(H);
+
+fn count<T>(
+    ctx: &mut RegionCtx<'_, Fr>,
+    f: impl FnOnce(&mut RegionCtx<'_, Fr>) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let start = ctx.offset();
+    let result = f(ctx)?;
+    HASHER_ROWS.with(|rows| rows.set(rows.get() + ctx.offset() - start));
+    HASHER_CALLS.with(|calls| calls.set(calls.get() + 1));
+    Ok(result)
+}
+
+impl<H: HasherInstructions<Fr>> HasherInstructions<Fr> for CountingHasher<H> {
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> GoldilocksChipConfig<Fr> {
+        H::configure(meta)
+    }
+
+    fn new(
+        ctx: &mut RegionCtx<'_, Fr>,
+        goldilocks_chip_config: &GoldilocksChipConfig<Fr>,
+    ) -> Result<Self, Error> {
+        let start = ctx.offset();
+        let hasher = H::new(ctx, goldilocks_chip_config)?;
+        HASHER_ROWS.with(|rows| rows.set(rows.get() + ctx.offset() - start));
+        Ok(Self(hasher))
+    }
+
+    fn permute_state(
+        &mut self,
+        ctx: &mut RegionCtx<'_, Fr>,
+        state: [AssignedValue<Fr>; SPONGE_WIDTH],
+    ) -> Result<[AssignedValue<Fr>; SPONGE_WIDTH], Error> {
+        count(ctx, |ctx| self.0.permute_state(ctx, state))
+    }
+
+    fn hash_or_noop(
+        &mut self,
+        ctx: &mut RegionCtx<'_, Fr>,
+        inputs: &[AssignedValue<Fr>],
+    ) -> Result<AssignedHashValues<Fr>, Error> {
+        count(ctx, |ctx| self.0.hash_or_noop(ctx, inputs))
+    }
+
+    fn two_to_one(
+        &mut self,
+        ctx: &mut RegionCtx<'_, Fr>,
+        left: &AssignedHashValues<Fr>,
+        right: &AssignedHashValues<Fr>,
+    ) -> Result<AssignedHashValues<Fr>, Error> {
+        count(ctx, |ctx| self.0.two_to_one(ctx, left, right))
+    }
+}
+
+/// A proof of `y = x^2 + x`, with a single FRI query so that the hashing of the challenger and
+/// of the Merkle proofs are both visible.
+fn mul_add_proof_tuple<C: GenericConfig<D, F = F>>() -> ProofTuple<F, C, D> {
+    let config = CircuitConfig {
+        fri_config: FriConfig {
+            rate_bits: 3,
+            cap_height: 0,
+            proof_of_work_bits: 0,
+            reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
+            num_query_rounds: 1,
+        },
+        ..CircuitConfig::standard_recursion_config()
+    };
+    let mut builder = CircuitBuilder::<F, D>::new(config);
+    let x = builder.add_virtual_public_input();
+    let y = builder.mul_add(x, x, x);
+    builder.register_public_input(y);
+    let data = builder.build::<C>();
+
+    let mut pw = PartialWitness::new();
+    pw.set_target(x, F::from_canonical_u64(3));
+    let proof = data.prove(pw).unwrap();
+    (proof, data.verifier_only, data.common)
+}
+
+fn bench_hasher<C, H>(name: &str)
+where
+    C: GenericConfig<D, F = F>,
+    H: HasherInstructions<Fr> + Clone,
+{
+    let input = VerifierInput::try_from(mul_add_proof_tuple::<C>()).unwrap();
+    let circuit = verifier_with_hasher::<D, CountingHasher<H>>(input).unwrap();
+    HASHER_ROWS.with(|rows| rows.set(0));
+    HASHER_CALLS.with(|calls| calls.set(0));
+
+    let now = Instant::now();
+    mock_prove(DEGREE, &circuit, circuit.instances()).unwrap();
+    let elapsed = now.elapsed();
+
+    // `SimpleFloorPlanner` synthesizes the region twice, first to measure its shape.
+    let rows = HASHER_ROWS.with(|rows| rows.get()) / 2;
+    let calls = HASHER_CALLS.with(|calls| calls.get()) / 2;
+    println!(
+        "{name}: {rows} rows in {calls} hasher calls ({} rows per call), mock proving took {elapsed:?}",
+        rows / calls.max(1)
+    );
+}
+
+fn main() {
+    bench_hasher::<Bn254PoseidonGoldilocksConfig, HasherChip<Fr>>("BN254 Poseidon");
+    bench_hasher::<Bn254Poseidon2GoldilocksConfig, Poseidon2HasherChip<Fr>>("BN254 Poseidon2");
+    bench_hasher::<PoseidonGoldilocksConfig, PublicInputsHasherChip<Fr>>("Goldilocks Poseidon");
+    bench_hasher::<KeccakGoldilocksConfig, KeccakHasherChip<Fr>>("Keccak");
+}
@@ -7,8 +7,14 @@ use plonky2::field::{
 
 use crate::chip::native_chip::{arithmetic_chip::GOLDILOCKS_MODULUS, utils::goldilocks_decompose};
 
-use super::constants::{
-    MDS_MATRIX_FR, ROUND_CONSTANTS_FR, R_F_BN254_POSEIDON, R_P_BN254_POSEIDON, T_BN254_POSEIDON,
+use super::{
+    constants::{
+        MDS_MATRIX_FR, ROUND_CONSTANTS_FR, R_F_BN254_POSEIDON, R_P_BN254_POSEIDON, T_BN254_POSEIDON,
+    },
+    poseidon2_constants::{
+        self as poseidon2, MAT_EXTERNAL, MAT_INTERNAL_DIAG_M_1, R_F_BN254_POSEIDON2,
+        R_P_BN254_POSEIDON2, T_BN254_POSEIDON2,
+    },
 };
 
 fn constant_layer(state: &mut [Fr; T_BN254_POSEIDON], counter: &mut usize) {
@@ -57,6 +63,74 @@ pub fn permute_bn254_poseidon_native(state: &mut [Fr; T_BN254_POSEIDON]) {
     }
 }
 
+/// A Poseidon2 instance over BN254 of width `T`, with the S-box `x^5`.
+pub struct Poseidon2Instance<'a, const T: usize> {
+    pub mat_external: [[u64; T]; T],
+    pub mat_internal_diag_m_1: [u64; T],
+    /// `T` constants for each full round and one for each partial round.
+    pub round_constants: &'a [Fr],
+    pub r_f: usize,
+    pub r_p: usize,
+}
+
+impl<const T: usize> Poseidon2Instance<'_, T> {
+    fn external_layer(&self, state: &mut [Fr; T]) {
+        let mut new_state = [Fr::from(0); T];
+        for i in 0..T {
+            for j in 0..T {
+                new_state[i] += state[j] * Fr::from(self.mat_external[i][j]);
+            }
+        }
+        *state = new_state
+    }
+
+    fn internal_layer(&self, state: &mut [Fr; T]) {
+        let sum = state.iter().fold(Fr::from(0), |acc, x| acc + x);
+        for i in 0..T {
+            state[i] = state[i] * Fr::from(self.mat_internal_diag_m_1[i]) + sum;
+        }
+    }
+
+    fn full_round(&self, state: &mut [Fr; T], counter: &mut usize) {
+        for i in 0..T {
+            state[i] = (state[i] + self.round_constants[*counter]).pow(&[5]);
+            *counter += 1;
+        }
+        self.external_layer(state);
+    }
+
+    fn partial_round(&self, state: &mut [Fr; T], counter: &mut usize) {
+        state[0] = (state[0] + self.round_constants[*counter]).pow(&[5]);
+        *counter += 1;
+        self.internal_layer(state);
+    }
+
+    pub fn permute(&self, state: &mut [Fr; T]) {
+        let mut counter = 0;
+        self.external_layer(state);
+        for _ in 0..self.r_f / 2 {
+            self.full_round(state, &mut counter);
+        }
+        for _ in 0..self.r_p {
+            self.partial_round(state, &mut counter);
+        }
+        for _ in 0..self.r_f / 2 {
+            self.full_round(state, &mut counter);
+        }
+    }
+}
+
+pub fn permute_bn254_poseidon2_native(state: &mut [Fr; T_BN254_POSEIDON2]) {
+    Poseidon2Instance {
+        mat_external: MAT_EXTERNAL,
+        mat_internal_diag_m_1: MAT_INTERNAL_DIAG_M_1,
+        round_constants: &poseidon2::ROUND_CONSTANTS_FR[..],
+        r_f: R_F_BN254_POSEIDON2,
+        r_p: R_P_BN254_POSEIDON2,
+    }
+    .permute(state)
+}
+
 pub fn encode_fe(x: [GoldilocksField; 3]) -> Fr {
     let acc = x.iter().enumerate().fold(Fr::from(0u64), |acc, (i, x)| {
         acc + Fr::from(x.to_canonical_u64()) * Fr::from(GOLDILOCKS_MODULUS).pow(&[i as u64])
@@ -73,3 +147,63 @@ pub fn decode_fe(x: Fr) -> [GoldilocksField; 3] {
         .to_vec();
     decomposed.try_into().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use halo2wrong_maingate::big_to_fe;
+    use num_bigint::BigUint;
+
+    use crate::bn254_poseidon::poseidon2_constants::grain_round_constants;
+
+    use super::{permute_bn254_poseidon2_native, Poseidon2Instance};
+
+    fn fe(x: &str) -> Fr {
+        big_to_fe(BigUint::parse_bytes(x.as_bytes(), 16).unwrap())
+    }
+
+    #[test]
+    fn test_poseidon2_reference_instance() {
+        // the test vector of the reference Poseidon2 instance over BN254, of width 3
+        let round_constants = grain_round_constants(3, 8, 56)
+            .into_iter()
+            .map(big_to_fe)
+            .collect::<Vec<Fr>>();
+        let instance = Poseidon2Instance {
+            mat_external: [[2, 1, 1], [1, 2, 1], [1, 1, 2]],
+            mat_internal_diag_m_1: [1, 1, 2],
+            round_constants: &round_constants,
+            r_f: 8,
+            r_p: 56,
+        };
+        let mut state = [0u64, 1, 2].map(Fr::from);
+        instance.permute(&mut state);
+        assert_eq!(
+            state,
+            [
+                "0bb61d24daca55eebcb1929a82650f328134334da98ea4f847f760054f4a3033",
+                "303b6f7c86d043bfcbcc80214f26a30277a15d3f74ca654992defe7ff8d03570",
+                "1ed25194542b12eef8617361c3ba7c52e660b145994427cc86296242cf766ec8",
+            ]
+            .map(fe)
+        );
+    }
+
+    #[test]
+    fn test_poseidon2_known_answer() {
+        // the permutation of this instance, as computed independently of this crate from the
+        // Grain LFSR and the matrices of `poseidon2_constants`
+        let mut state = [0u64, 1, 2, 3].map(Fr::from);
+        permute_bn254_poseidon2_native(&mut state);
+        assert_eq!(
+            state,
+            [
+                "0db08680f459bcb5146decc9597010394c1a7fb01c19b62b262f8a26e1e0c7d8",
+                "01663e5506bcd6042e9e287b6f1a217ac8e7dfa699bc7a20d84c1aea05d32c91",
+                "12808626a8cd8a8df0411b8a547afcb8b7243fd0679ee6296d385e9a6bb8401a",
+                "0b6bd2e1802fcc85cf148b2d17a6f2f69fb7b303a85ceddf1005b0cf7db5ce1a",
+            ]
+            .map(fe)
+        );
+    }
+}
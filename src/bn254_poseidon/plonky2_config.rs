@@ -2,7 +2,10 @@ use std::fmt::Debug;
 
 use halo2_proofs::halo2curves::bn256::Fr;
 use plonky2::{
-    field::{extension::quadratic::QuadraticExtension, goldilocks_field::GoldilocksField},
+    field::{
+        extension::{quadratic::QuadraticExtension, quartic::QuarticExtension},
+        goldilocks_field::GoldilocksField,
+    },
     fri::{reduction_strategies::FriReductionStrategy, FriConfig},
     hash::{
         hash_types::HashOut,
@@ -18,7 +21,8 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     constants::T_BN254_POSEIDON,
-    native::{decode_fe, encode_fe, permute_bn254_poseidon_native},
+    native::{decode_fe, encode_fe, permute_bn254_poseidon2_native, permute_bn254_poseidon_native},
+    poseidon2_constants::T_BN254_POSEIDON2,
 };
 
 #[derive(Copy, Clone, Default, Debug, PartialEq)]
@@ -116,6 +120,119 @@ impl GenericConfig<2> for Bn254PoseidonGoldilocksConfig {
     type InnerHasher = PoseidonHash;
 }
 
+impl GenericConfig<4> for Bn254PoseidonGoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = QuarticExtension<Self::F>;
+    type Hasher = Bn254PoseidonHash;
+    type InnerHasher = PoseidonHash;
+}
+
+/// The Poseidon2 counterpart of `Bn254PoseidonPermutation`. The 12 elements of the state are
+/// packed three per element into the 4 elements of the Poseidon2 state, with no capacity element.
+/// The capacity of the sponge is already in the plonky2 state, its last 4 elements, which are
+/// permuted like the rate. The fifth element of the width 5 Poseidon state of
+/// `Bn254PoseidonPermutation` does not add any: it is zero before each permutation and dropped
+/// after it, so no state is kept in it from one permutation to the next.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct Bn254Poseidon2Permutation<T> {
+    state: [T; SPONGE_WIDTH],
+}
+
+impl<T: Eq> Eq for Bn254Poseidon2Permutation<T> {}
+
+impl<T> AsRef<[T]> for Bn254Poseidon2Permutation<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.state
+    }
+}
+
+trait Poseidon2Permuter: Sized {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH];
+}
+
+impl Poseidon2Permuter for GoldilocksField {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        let encoded_state = input
+            .chunks(3)
+            .map(|x| encode_fe(x.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let mut state: [Fr; T_BN254_POSEIDON2] = encoded_state.try_into().unwrap();
+        permute_bn254_poseidon2_native(&mut state);
+        let decoded_state = state.iter().flat_map(|x| decode_fe(*x)).collect::<Vec<_>>();
+        decoded_state.try_into().unwrap()
+    }
+}
+
+impl<T: Copy + Debug + Default + Eq + Poseidon2Permuter + Send + Sync> PlonkyPermutation<T>
+    for Bn254Poseidon2Permutation<T>
+{
+    const RATE: usize = SPONGE_RATE;
+    const WIDTH: usize = SPONGE_WIDTH;
+
+    fn new<I: IntoIterator<Item = T>>(elts: I) -> Self {
+        let mut perm = Self {
+            state: [T::default(); SPONGE_WIDTH],
+        };
+        perm.set_from_iter(elts, 0);
+        perm
+    }
+
+    fn set_elt(&mut self, elt: T, idx: usize) {
+        self.state[idx] = elt;
+    }
+
+    fn set_from_slice(&mut self, elts: &[T], start_idx: usize) {
+        let begin = start_idx;
+        let end = start_idx + elts.len();
+        self.state[begin..end].copy_from_slice(elts);
+    }
+
+    fn set_from_iter<I: IntoIterator<Item = T>>(&mut self, elts: I, start_idx: usize) {
+        for (s, e) in self.state[start_idx..].iter_mut().zip(elts) {
+            *s = e;
+        }
+    }
+
+    fn permute(&mut self) {
+        self.state = T::permute(self.state);
+    }
+
+    fn squeeze(&self) -> &[T] {
+        &self.state[..Self::RATE]
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Bn254Poseidon2Hash;
+impl Hasher<GoldilocksField> for Bn254Poseidon2Hash {
+    const HASH_SIZE: usize = 4 * 8;
+    type Hash = HashOut<GoldilocksField>;
+    type Permutation = Bn254Poseidon2Permutation<GoldilocksField>;
+
+    fn hash_no_pad(input: &[GoldilocksField]) -> Self::Hash {
+        hash_n_to_hash_no_pad::<GoldilocksField, Self::Permutation>(input)
+    }
+    fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
+        compress::<GoldilocksField, Self::Permutation>(left, right)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Bn254Poseidon2GoldilocksConfig;
+impl GenericConfig<2> for Bn254Poseidon2GoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = QuadraticExtension<Self::F>;
+    type Hasher = Bn254Poseidon2Hash;
+    type InnerHasher = PoseidonHash;
+}
+
+impl GenericConfig<4> for Bn254Poseidon2GoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = QuarticExtension<Self::F>;
+    type Hasher = Bn254Poseidon2Hash;
+    type InnerHasher = PoseidonHash;
+}
+
 // If you use recursive proof in the plonky2's circuit, use this config for the inner circuit.
 pub fn standard_inner_stark_verifier_config() -> CircuitConfig {
     CircuitConfig {
@@ -134,7 +251,6 @@ pub fn standard_inner_stark_verifier_config() -> CircuitConfig {
 pub fn standard_stark_verifier_config() -> CircuitConfig {
     let inner_config = standard_inner_stark_verifier_config();
     CircuitConfig {
-        use_interpolation_gate: false,
         fri_config: FriConfig {
             rate_bits: 3,
             cap_height: 0,
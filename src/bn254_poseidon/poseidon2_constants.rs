@@ -0,0 +1,253 @@
+use std::collections::VecDeque;
+
+use halo2_proofs::halo2curves::{
+    bn256::Fr,
+    ff::{Field, PrimeField},
+};
+use halo2wrong_maingate::{big_to_fe, fe_to_big};
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+
+/// The external matrix `M_4` of Poseidon2, applied in the full rounds and before the first one.
+pub const MAT_EXTERNAL: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+/// The diagonal of the internal matrix minus the identity, i.e. the internal matrix is
+/// `1 + diag(MAT_INTERNAL_DIAG_M_1)` where `1` is the all-ones matrix. Its powers up to `2t` have
+/// irreducible characteristic polynomials, as checked by `test_internal_matrix`, so the partial
+/// rounds have no invariant subspace trails.
+pub const MAT_INTERNAL_DIAG_M_1: [u64; 4] = [1, 3, 5, 7];
+
+/// The Grain LFSR of the Poseidon reference implementation, in self-shrinking mode, from which
+/// the round constants are drawn.
+struct Grain {
+    state: VecDeque<bool>,
+}
+
+impl Grain {
+    /// Initializes the LFSR with the parameters of an instance over a prime field of `n` bits
+    /// with the S-box `x^alpha`, and discards its first 160 bits.
+    fn new(n: usize, t: usize, r_f: usize, r_p: usize) -> Self {
+        let mut state = VecDeque::with_capacity(80);
+        // field type 1 (prime field), S-box type 0 (x^alpha)
+        for (value, num_bits) in [(1, 2), (0, 4), (n, 12), (t, 12), (r_f, 10), (r_p, 10)] {
+            state.extend((0..num_bits).rev().map(|i| (value >> i) & 1 == 1));
+        }
+        state.extend([true; 30]);
+        let mut grain = Self { state };
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let bit = [62, 51, 38, 23, 13, 0]
+            .iter()
+            .fold(false, |acc, &i| acc ^ self.state[i]);
+        self.state.pop_front();
+        self.state.push_back(bit);
+        bit
+    }
+
+    // Bits are drawn in pairs, the second one being kept only if the first one is set.
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+}
+
+/// Generates the round constants of the Poseidon2 instance over BN254 of width `t` with `r_f`
+/// full and `r_p` partial rounds, like `poseidon2_rust_params.sage` of the Poseidon2 reference
+/// implementation: `t` constants for each full round and one for each partial round, in the order
+/// of the rounds. Each constant is drawn from 254 bits of `Grain`, until it is less than the
+/// modulus.
+pub fn grain_round_constants(t: usize, r_f: usize, r_p: usize) -> Vec<BigUint> {
+    let modulus = fe_to_big(-Fr::ONE) + 1u64;
+    let num_bits = Fr::NUM_BITS as usize;
+    let mut grain = Grain::new(num_bits, t, r_f, r_p);
+    (0..r_f * t + r_p)
+        .map(|_| loop {
+            let x = (0..num_bits).fold(BigUint::from(0u64), |acc, _| {
+                (acc << 1) + u64::from(grain.next_bit())
+            });
+            if x < modulus {
+                break x;
+            }
+        })
+        .collect()
+}
+
+lazy_static! {
+    pub static ref ROUND_CONSTANTS_BG: [BigUint; 88] =
+        grain_round_constants(T_BN254_POSEIDON2, R_F_BN254_POSEIDON2, R_P_BN254_POSEIDON2)
+            .try_into()
+            .unwrap();
+    pub static ref ROUND_CONSTANTS_FR: [Fr; 88] = ROUND_CONSTANTS_BG.clone().map(big_to_fe);
+}
+
+/// The width of the instance. The 12 elements of the plonky2 state are packed three per element,
+/// see `Bn254Poseidon2Permutation`.
+pub const T_BN254_POSEIDON2: usize = 4;
+/// The round numbers of Poseidon over BN254 for a width of 4 and 128 bits of security, e.g. in
+/// circomlib, which are also the ones of the reference Poseidon2 instance of width 3.
+pub const R_F_BN254_POSEIDON2: usize = 8;
+pub const R_P_BN254_POSEIDON2: usize = 56;
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::halo2curves::{
+        bn256::Fr,
+        ff::{Field, PrimeField},
+    };
+    use num_bigint::BigUint;
+
+    use super::{grain_round_constants, MAT_INTERNAL_DIAG_M_1, T_BN254_POSEIDON2};
+
+    const T: usize = T_BN254_POSEIDON2;
+
+    // polynomials over Fr, lowest coefficient first
+
+    fn trim(mut a: Vec<Fr>) -> Vec<Fr> {
+        while a.last() == Some(&Fr::ZERO) {
+            a.pop();
+        }
+        a
+    }
+
+    fn sub(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+        let n = a.len().max(b.len());
+        trim(
+            (0..n)
+                .map(|i| {
+                    a.get(i).copied().unwrap_or(Fr::ZERO) - b.get(i).copied().unwrap_or(Fr::ZERO)
+                })
+                .collect(),
+        )
+    }
+
+    fn rem(a: &[Fr], m: &[Fr]) -> Vec<Fr> {
+        let mut a = trim(a.to_vec());
+        let lead_inv = m.last().unwrap().invert().unwrap();
+        while a.len() >= m.len() {
+            let c = *a.last().unwrap() * lead_inv;
+            let shift = a.len() - m.len();
+            for (i, m) in m.iter().enumerate() {
+                a[shift + i] -= c * m;
+            }
+            a = trim(a);
+        }
+        a
+    }
+
+    fn mul_mod(a: &[Fr], b: &[Fr], m: &[Fr]) -> Vec<Fr> {
+        if a.is_empty() || b.is_empty() {
+            return vec![];
+        }
+        let mut c = vec![Fr::ZERO; a.len() + b.len() - 1];
+        for (i, a) in a.iter().enumerate() {
+            for (j, b) in b.iter().enumerate() {
+                c[i + j] += a * b;
+            }
+        }
+        rem(&c, m)
+    }
+
+    fn gcd(a: &[Fr], b: &[Fr]) -> Vec<Fr> {
+        let (mut a, mut b) = (trim(a.to_vec()), trim(b.to_vec()));
+        while !b.is_empty() {
+            (a, b) = (b.clone(), rem(&a, &b));
+        }
+        a
+    }
+
+    // x^(p^k) mod m
+    fn frobenius(m: &[Fr], k: usize) -> Vec<Fr> {
+        // p = (p - 1) + 1, whose bits are read from the little-endian representation of -1
+        let p_minus_1 = (-Fr::ONE).to_repr();
+        let mut x = vec![Fr::ZERO, Fr::ONE];
+        for _ in 0..k {
+            let mut power = vec![Fr::ONE];
+            for byte in p_minus_1.iter().rev() {
+                for i in (0..8).rev() {
+                    power = mul_mod(&power, &power, m);
+                    if (byte >> i) & 1 == 1 {
+                        power = mul_mod(&power, &x, m);
+                    }
+                }
+            }
+            x = mul_mod(&power, &x, m);
+        }
+        x
+    }
+
+    // Rabin's test for a polynomial of degree 4: it divides x^(p^4) - x, and is coprime with
+    // x^(p^2) - x, so it has no factor of degree 1 or 2.
+    fn is_irreducible_quartic(f: &[Fr]) -> bool {
+        let x = [Fr::ZERO, Fr::ONE];
+        sub(&frobenius(f, 4), &x).is_empty() && gcd(f, &sub(&frobenius(f, 2), &x)).len() == 1
+    }
+
+    fn mat_mul(a: &[[Fr; T]; T], b: &[[Fr; T]; T]) -> [[Fr; T]; T] {
+        a.map(|row| std::array::from_fn(|j| (0..T).map(|k| row[k] * b[k][j]).sum()))
+    }
+
+    // the characteristic polynomial with the Faddeev-LeVerrier algorithm
+    fn characteristic_polynomial(m: &[[Fr; T]; T]) -> Vec<Fr> {
+        let mut coeffs = vec![Fr::ZERO; T + 1];
+        coeffs[T] = Fr::ONE;
+        let mut n = [[Fr::ZERO; T]; T];
+        for k in 1..=T {
+            n = mat_mul(m, &n);
+            for (i, row) in n.iter_mut().enumerate() {
+                row[i] += coeffs[T - k + 1];
+            }
+            let product = mat_mul(m, &n);
+            let trace: Fr = (0..T).map(|i| product[i][i]).sum();
+            coeffs[T - k] = -trace * Fr::from(k as u64).invert().unwrap();
+        }
+        coeffs
+    }
+
+    #[test]
+    fn test_grain_round_constants() {
+        // the first round of the reference Poseidon2 instance over BN254, of width 3
+        let constants = grain_round_constants(3, 8, 56);
+        assert_eq!(constants.len(), 8 * 3 + 56);
+        let expected = [
+            "1d066a255517b7fd8bddd3a93f7804ef7f8fcde48bb4c37a59a09a1a97052816",
+            "29daefb55f6f2dc6ac3f089cebcc6120b7c6fef31367b68eb7238547d32c1610",
+            "1f2cb1624a78ee001ecbd88ad959d7012572d76f08ec5c4f9e8b7ad7b0b4e1d1",
+        ]
+        .map(|x| BigUint::parse_bytes(x.as_bytes(), 16).unwrap());
+        assert_eq!(constants[..3], expected);
+    }
+
+    /// The partial rounds have no invariant subspace trails when the powers of the internal
+    /// matrix up to `2t` have irreducible characteristic polynomials, see Section 5.3 of the
+    /// Poseidon2 paper.
+    #[test]
+    fn test_internal_matrix() {
+        let mut m = [[Fr::ONE; T]; T];
+        for (i, d) in MAT_INTERNAL_DIAG_M_1.iter().enumerate() {
+            m[i][i] += Fr::from(*d);
+        }
+        let mut power = m;
+        for _ in 0..2 * T {
+            assert!(is_irreducible_quartic(&characteristic_polynomial(&power)));
+            power = mat_mul(&power, &m);
+        }
+        // the all-ones matrix plus the identity has the eigenvalue 1 with multiplicity 3
+        let mut reducible = [[Fr::ONE; T]; T];
+        for (i, row) in reducible.iter_mut().enumerate() {
+            row[i] += Fr::ONE;
+        }
+        assert!(!is_irreducible_quartic(&characteristic_polynomial(
+            &reducible
+        )));
+    }
+}
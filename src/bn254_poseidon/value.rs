@@ -1,8 +1,14 @@
 use halo2_proofs::{circuit::Value, halo2curves::ff::PrimeField};
 use num_bigint::BigUint;
 
-use super::constants::{
-    MDS_MATRIX_BG, ROUND_CONSTANTS_BG, R_F_BN254_POSEIDON, R_P_BN254_POSEIDON, T_BN254_POSEIDON,
+use super::{
+    constants::{
+        MDS_MATRIX_BG, ROUND_CONSTANTS_BG, R_F_BN254_POSEIDON, R_P_BN254_POSEIDON, T_BN254_POSEIDON,
+    },
+    poseidon2_constants::{
+        self as poseidon2, MAT_EXTERNAL, MAT_INTERNAL_DIAG_M_1, R_F_BN254_POSEIDON2,
+        R_P_BN254_POSEIDON2, T_BN254_POSEIDON2,
+    },
 };
 
 pub fn bg_to_fe<F: PrimeField>(x: &BigUint) -> F {
@@ -68,15 +74,71 @@ pub fn permute_value<F: PrimeField>(state: &mut [Value<F>; T_BN254_POSEIDON]) {
     }
 }
 
+pub fn poseidon2_external_layer_value<F: PrimeField>(state: &mut [Value<F>; T_BN254_POSEIDON2]) {
+    let mut new_state = [Value::known(F::from(0)); T_BN254_POSEIDON2];
+    for i in 0..T_BN254_POSEIDON2 {
+        for j in 0..T_BN254_POSEIDON2 {
+            new_state[i] = new_state[i] + state[j] * Value::known(F::from(MAT_EXTERNAL[i][j]));
+        }
+    }
+    *state = new_state
+}
+
+fn poseidon2_internal_layer_value<F: PrimeField>(state: &mut [Value<F>; T_BN254_POSEIDON2]) {
+    let sum = state
+        .iter()
+        .fold(Value::known(F::from(0)), |acc, x| acc + *x);
+    for i in 0..T_BN254_POSEIDON2 {
+        state[i] = state[i] * Value::known(F::from(MAT_INTERNAL_DIAG_M_1[i])) + sum;
+    }
+}
+
+pub fn poseidon2_full_round_value<F: PrimeField>(
+    state: &mut [Value<F>; T_BN254_POSEIDON2],
+    counter: &mut usize,
+) {
+    for i in 0..T_BN254_POSEIDON2 {
+        let x = state[i] + Value::known(bg_to_fe::<F>(&poseidon2::ROUND_CONSTANTS_BG[*counter]));
+        state[i] = x * x * x * x * x;
+        *counter += 1;
+    }
+    poseidon2_external_layer_value(state);
+}
+
+pub fn poseidon2_partial_round_value<F: PrimeField>(
+    state: &mut [Value<F>; T_BN254_POSEIDON2],
+    counter: &mut usize,
+) {
+    let x = state[0] + Value::known(bg_to_fe::<F>(&poseidon2::ROUND_CONSTANTS_BG[*counter]));
+    state[0] = x * x * x * x * x;
+    *counter += 1;
+    poseidon2_internal_layer_value(state);
+}
+
+pub fn poseidon2_permute_value<F: PrimeField>(state: &mut [Value<F>; T_BN254_POSEIDON2]) {
+    let mut counter = 0;
+    poseidon2_external_layer_value(state);
+    for _ in 0..R_F_BN254_POSEIDON2 / 2 {
+        poseidon2_full_round_value(state, &mut counter);
+    }
+    for _ in 0..R_P_BN254_POSEIDON2 {
+        poseidon2_partial_round_value(state, &mut counter);
+    }
+    for _ in 0..R_F_BN254_POSEIDON2 / 2 {
+        poseidon2_full_round_value(state, &mut counter);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 
     use crate::bn254_poseidon::{
-        constants::T_BN254_POSEIDON, native::permute_bn254_poseidon_native,
+        constants::T_BN254_POSEIDON,
+        native::{permute_bn254_poseidon2_native, permute_bn254_poseidon_native},
     };
 
-    use super::permute_value;
+    use super::{permute_value, poseidon2_permute_value};
 
     #[test]
     fn test_poseidon_correspondence_with_value() {
@@ -91,4 +153,18 @@ mod tests {
                 x.map(|x| assert_eq!(x, *y));
             });
     }
+
+    #[test]
+    fn test_poseidon2_correspondence_with_value() {
+        let mut state_native = [0u64, 1, 2, 3].map(Fr::from);
+        let mut state_value = state_native.map(|x| Value::known(x));
+        poseidon2_permute_value(&mut state_value);
+        permute_bn254_poseidon2_native(&mut state_native);
+        state_value
+            .iter()
+            .zip(state_native.iter())
+            .for_each(|(x, y)| {
+                x.map(|x| assert_eq!(x, *y));
+            });
+    }
 }
@@ -2,12 +2,14 @@ use halo2_proofs::circuit::AssignedCell;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
 use halo2wrong_maingate::AssignedValue;
 use itertools::Itertools;
+use std::marker::PhantomData;
 use plonky2::field::types::{Field, PrimeField64};
 use plonky2::{field::goldilocks_field::GoldilocksField, util::reverse_index_bits_in_place};
 
 use super::{
     goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
     goldilocks_extension_chip::GoldilocksExtensionChip,
+    hasher_chip::{HasherChip, HasherInstructions},
     merkle_proof_chip::MerkleProofChip,
     vector_chip::VectorChip,
 };
@@ -23,15 +25,17 @@ use crate::types::{
     fri::{FriBatchInfo, FriInstanceInfo},
 };
 
-pub struct FriVerifierChip<F: PrimeField> {
+pub struct FriVerifierChip<F: PrimeField, const D: usize, H: HasherInstructions<F> = HasherChip<F>>
+{
     goldilocks_chip_config: GoldilocksChipConfig<F>,
     /// Representative `g` of the coset used in FRI, so that LDEs in FRI are done over `gH`.
     offset: AssignedValue<F>,
     /// The degree of the purported codeword, measured in bits.
     fri_params: FriParams,
+    _marker: PhantomData<H>,
 }
 
-impl<F: PrimeField> FriVerifierChip<F> {
+impl<F: PrimeField, const D: usize, H: HasherInstructions<F>> FriVerifierChip<F, D, H> {
     pub fn construct(
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
 
@@ -42,6 +46,7 @@ impl<F: PrimeField> FriVerifierChip<F> {
             goldilocks_chip_config: goldilocks_chip_config.clone(),
             offset: offset.clone(),
             fri_params,
+            _marker: PhantomData,
         }
     }
 
@@ -49,7 +54,7 @@ impl<F: PrimeField> FriVerifierChip<F> {
         GoldilocksChip::new(&self.goldilocks_chip_config)
     }
 
-    fn goldilocks_extension_chip(&self) -> GoldilocksExtensionChip<F> {
+    fn goldilocks_extension_chip(&self) -> GoldilocksExtensionChip<F, D> {
         GoldilocksExtensionChip::new(&self.goldilocks_chip_config)
     }
 
@@ -58,10 +63,11 @@ impl<F: PrimeField> FriVerifierChip<F> {
     fn compute_reduced_openings(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        fri_alpha: &AssignedExtensionFieldValue<F, 2>,
-        fri_openings: &AssignedFriOpenings<F, 2>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
-        let goldilocks_extension_chip = GoldilocksExtensionChip::new(&self.goldilocks_chip_config);
+        fri_alpha: &AssignedExtensionFieldValue<F, D>,
+        fri_openings: &AssignedFriOpenings<F, D>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
         fri_openings
             .batches
             .iter()
@@ -90,20 +96,25 @@ impl<F: PrimeField> FriVerifierChip<F> {
         initial_merkle_caps: &[AssignedMerkleCapValues<F>],
         initial_trees_proof: &AssignedFriInitialTreeProofValues<F>,
     ) -> Result<(), Error> {
-        let merkle_proof_chip = MerkleProofChip::new(&self.goldilocks_chip_config);
-        for (_, ((evals, merkle_proof), cap)) in initial_trees_proof
+        let merkle_proof_chip = MerkleProofChip::<F, H>::new(&self.goldilocks_chip_config);
+        for (i, ((evals, merkle_proof), cap)) in initial_trees_proof
             .evals_proofs
             .iter()
             .zip(initial_merkle_caps)
             .enumerate()
         {
-            merkle_proof_chip.verify_merkle_proof_to_cap_with_cap_index(
-                ctx,
-                evals,
-                x_index_bits,
-                &cap_index,
-                &cap,
-                merkle_proof,
+            ctx.namespaced(
+                || format!("initial tree {i}"),
+                |ctx| {
+                    merkle_proof_chip.verify_merkle_proof_to_cap_with_cap_index(
+                        ctx,
+                        evals,
+                        x_index_bits,
+                        &cap_index,
+                        &cap,
+                        merkle_proof,
+                    )
+                },
             )?;
         }
         Ok(())
@@ -112,13 +123,13 @@ impl<F: PrimeField> FriVerifierChip<F> {
     fn batch_initial_polynomials(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        fri_instance_info: &FriInstanceInfo<F, 2>,
-        fri_alpha: &AssignedExtensionFieldValue<F, 2>,
+        fri_instance_info: &FriInstanceInfo<F, D>,
+        fri_alpha: &AssignedExtensionFieldValue<F, D>,
         // `x` is the initially selected point in FRI
         x: &AssignedValue<F>,
         initial_trees_proof: &AssignedFriInitialTreeProofValues<F>,
-        reduced_openings: &[AssignedExtensionFieldValue<F, 2>],
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        reduced_openings: &[AssignedExtensionFieldValue<F, D>],
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
         let x = goldilocks_extension_chip.convert_to_extension(ctx, &x)?;
         let mut sum = goldilocks_extension_chip.zero_extension(ctx)?;
@@ -170,10 +181,10 @@ impl<F: PrimeField> FriVerifierChip<F> {
         ctx: &mut RegionCtx<'_, F>,
         x_index_within_coset_bits: &[AssignedValue<F>],
         x: &AssignedValue<F>,
-        evals: &Vec<AssignedExtensionFieldValue<F, 2>>,
+        evals: &Vec<AssignedExtensionFieldValue<F, D>>,
         arity_bits: usize,
-        beta: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        beta: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_chip = self.goldilocks_chip();
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
         // computes `P'(x^arity)` where `arity = 1 << arity_bits` from `P(x*g^i), (i = 0, ..., arity)` where
@@ -208,34 +219,138 @@ impl<F: PrimeField> FriVerifierChip<F> {
             g_power = goldilocks_chip.mul(ctx, &g_power, &g)?;
             points.push((x, eval.clone()));
         }
-        // TODO : For now, only 2-arity is supported. Otherwise, FFT implementation over extension Field is required.
-        // a0 -> a1
-        // b0 -> b1
-        // x  -> a1 + (x-a0)*(b1-a1)/(b0-a0)
-        let (a0, a1) = &points[0];
-        let (b0, b1) = &points[1];
-
-        // a1 + (x - a0) * (b1 - a1) / (b0 - a0)
-        let x_minus_a0 = goldilocks_extension_chip.sub_extension(ctx, beta, a0)?;
-        let b1_minus_a1 = goldilocks_extension_chip.sub_extension(ctx, b1, a1)?;
-        let numerator = goldilocks_extension_chip.mul_extension(ctx, &x_minus_a0, &b1_minus_a1)?;
-        let denominator = goldilocks_extension_chip.sub_extension(ctx, b0, a0)?;
-        let result =
-            goldilocks_extension_chip.div_add_extension(ctx, &numerator, &denominator, a1)?;
-        Ok(result)
+        if arity_bits == 1 {
+            // a0 -> a1
+            // b0 -> b1
+            // x  -> a1 + (x-a0)*(b1-a1)/(b0-a0)
+            let (a0, a1) = &points[0];
+            let (b0, b1) = &points[1];
+
+            // a1 + (x - a0) * (b1 - a1) / (b0 - a0)
+            let x_minus_a0 = goldilocks_extension_chip.sub_extension(ctx, beta, a0)?;
+            let b1_minus_a1 = goldilocks_extension_chip.sub_extension(ctx, b1, a1)?;
+            let numerator =
+                goldilocks_extension_chip.mul_extension(ctx, &x_minus_a0, &b1_minus_a1)?;
+            let denominator = goldilocks_extension_chip.sub_extension(ctx, b0, a0)?;
+            let result =
+                goldilocks_extension_chip.div_add_extension(ctx, &numerator, &denominator, a1)?;
+            return Ok(result);
+        }
+
+        // For larger arities, we use the barycentric formula. The points are `x_i = s * g^i` where
+        // `s` is `coset_start`, so \prod_i (X - x_i) = X^n - s^n and the barycentric weight of `x_i`
+        // is `x_i / (n * s^n)`:
+        // P'(beta) = (beta^n - s^n) / (n * s^n) * \sum_i x_i * P(x_i) / (beta - x_i)
+        let mut sum = goldilocks_extension_chip.zero_extension(ctx)?;
+        for (x_i, eval) in points.iter() {
+            let numerator = goldilocks_extension_chip.mul_extension(ctx, x_i, eval)?;
+            let denominator = goldilocks_extension_chip.sub_extension(ctx, beta, x_i)?;
+            sum =
+                goldilocks_extension_chip.div_add_extension(ctx, &numerator, &denominator, &sum)?;
+        }
+        let coset_start_pow = goldilocks_chip.exp_power_of_2(ctx, &coset_start, arity_bits)?;
+        let coset_start_pow =
+            goldilocks_extension_chip.convert_to_extension(ctx, &coset_start_pow)?;
+        let beta_pow =
+            goldilocks_extension_chip.exp_power_of_2_extension(ctx, beta.clone(), arity_bits)?;
+        let vanishing =
+            goldilocks_extension_chip.sub_extension(ctx, &beta_pow, &coset_start_pow)?;
+        let numerator = goldilocks_extension_chip.mul_extension(ctx, &vanishing, &sum)?;
+        let denominator = goldilocks_extension_chip.scalar_mul(
+            ctx,
+            &coset_start_pow,
+            GoldilocksField::from_canonical_usize(arity),
+        )?;
+        goldilocks_extension_chip.div_extension(ctx, &numerator, &denominator)
+    }
+
+    /// Checks that `prev_eval` is consistent with the evaluations opened in the reduction step
+    /// `step`, and returns the folded evaluation with the next point and its index bits.
+    fn check_reduction_step(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        fri_proof: &AssignedFriProofValues<F, D>,
+        round_proof: &AssignedFriQueryRoundValues<F, D>,
+        step: usize,
+        arity_bits: usize,
+        beta: &AssignedExtensionFieldValue<F, D>,
+        cap_index: &AssignedValue<F>,
+        prev_eval: &AssignedExtensionFieldValue<F, D>,
+        x_from_subgroup: &AssignedValue<F>,
+        x_index_bits: &[AssignedValue<F>],
+    ) -> Result<
+        (
+            AssignedExtensionFieldValue<F, D>,
+            AssignedValue<F>,
+            Vec<AssignedValue<F>>,
+        ),
+        Error,
+    > {
+        let goldilocks_chip = self.goldilocks_chip();
+        let evals = &round_proof.steps[step].evals;
+
+        // Split x_index into the index of the coset x is in, and the index of x within that coset.
+        let coset_index_bits = x_index_bits[arity_bits..].to_vec();
+        let x_index_within_coset_bits = &x_index_bits[..arity_bits];
+        let x_index_within_coset =
+            goldilocks_chip.from_bits(ctx, &x_index_within_coset_bits.to_vec())?;
+
+        // check the consistency of `prev_eval` and `next_eval`
+        ctx.check(
+            || "folded eval mismatch",
+            |ctx| {
+                for i in 0..D {
+                    let vector_chip = VectorChip::new(
+                        &self.goldilocks_chip_config,
+                        evals.iter().map(|eval| eval.0[i].clone()).collect_vec(),
+                    );
+                    let next_eval_i = vector_chip.access(ctx, &x_index_within_coset)?;
+                    goldilocks_chip.assert_equal(ctx, &prev_eval.0[i], &next_eval_i)?;
+                }
+                Ok(())
+            },
+        )?;
+
+        let next_eval = self.next_eval(
+            ctx,
+            x_index_within_coset_bits,
+            x_from_subgroup,
+            evals,
+            arity_bits,
+            beta,
+        )?;
+
+        let merkle_proof_chip = MerkleProofChip::<F, H>::new(&self.goldilocks_chip_config);
+        ctx.namespaced(
+            || "commit phase tree",
+            |ctx| {
+                merkle_proof_chip.verify_merkle_proof_to_cap_with_cap_index(
+                    ctx,
+                    &evals.iter().flat_map(|eval| eval.0.clone()).collect_vec(),
+                    &coset_index_bits,
+                    cap_index,
+                    &fri_proof.commit_phase_merkle_cap_values[step],
+                    &round_proof.steps[step].merkle_proof,
+                )
+            },
+        )?;
+        // Update the point x to x^arity.
+        let x_from_subgroup = goldilocks_chip.exp_power_of_2(ctx, x_from_subgroup, arity_bits)?;
+
+        Ok((next_eval, x_from_subgroup, coset_index_bits))
     }
 
     fn check_consistency(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         initial_merkle_caps: &[AssignedMerkleCapValues<F>],
-        fri_instance_info: &FriInstanceInfo<F, 2>,
-        fri_alpha: &AssignedExtensionFieldValue<F, 2>,
-        fri_betas: &[AssignedExtensionFieldValue<F, 2>],
-        fri_proof: &AssignedFriProofValues<F, 2>,
+        fri_instance_info: &FriInstanceInfo<F, D>,
+        fri_alpha: &AssignedExtensionFieldValue<F, D>,
+        fri_betas: &[AssignedExtensionFieldValue<F, D>],
+        fri_proof: &AssignedFriProofValues<F, D>,
         x_index: &AssignedValue<F>,
-        round_proof: &AssignedFriQueryRoundValues<F, 2>,
-        reduced_openings: &[AssignedExtensionFieldValue<F, 2>],
+        round_proof: &AssignedFriQueryRoundValues<F, D>,
+        reduced_openings: &[AssignedExtensionFieldValue<F, D>],
     ) -> Result<(), Error> {
         let goldilocks_chip = self.goldilocks_chip();
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
@@ -273,89 +388,80 @@ impl<F: PrimeField> FriVerifierChip<F> {
         )?;
 
         for (i, &arity_bits) in self.fri_params.reduction_arity_bits.iter().enumerate() {
-            let evals = &round_proof.steps[i].evals;
-
-            // Split x_index into the index of the coset x is in, and the index of x within that coset.
-            let coset_index_bits = x_index_bits[arity_bits..].to_vec();
-            let x_index_within_coset_bits = &x_index_bits[..arity_bits];
-            let x_index_within_coset =
-                goldilocks_chip.from_bits(ctx, &x_index_within_coset_bits.to_vec())?;
-
-            // check the consistency of `prev_eval` and `next_eval`
-            for i in 0..2 {
-                let vector_chip = VectorChip::new(
-                    &self.goldilocks_chip_config,
-                    evals.iter().map(|eval| eval.0[i].clone()).collect_vec(),
-                );
-                let next_eval_i = vector_chip.access(ctx, &x_index_within_coset)?;
-                goldilocks_chip.assert_equal(ctx, &prev_eval.0[i], &next_eval_i)?;
-            }
-
-            prev_eval = self.next_eval(
-                ctx,
-                x_index_within_coset_bits,
-                &x_from_subgroup,
-                evals,
-                arity_bits,
-                &fri_betas[i],
-            )?;
-
-            let merkle_proof_chip = MerkleProofChip::new(&self.goldilocks_chip_config);
-            merkle_proof_chip.verify_merkle_proof_to_cap_with_cap_index(
-                ctx,
-                &evals.iter().flat_map(|eval| eval.0.clone()).collect_vec(),
-                &coset_index_bits,
-                &cap_index,
-                &fri_proof.commit_phase_merkle_cap_values[i],
-                &round_proof.steps[i].merkle_proof,
+            (prev_eval, x_from_subgroup, x_index_bits) = ctx.namespaced(
+                || format!("reduction step {i}"),
+                |ctx| {
+                    self.check_reduction_step(
+                        ctx,
+                        fri_proof,
+                        round_proof,
+                        i,
+                        arity_bits,
+                        &fri_betas[i],
+                        &cap_index,
+                        &prev_eval,
+                        &x_from_subgroup,
+                        &x_index_bits,
+                    )
+                },
             )?;
-            // Update the point x to x^arity.
-            x_from_subgroup = goldilocks_chip.exp_power_of_2(ctx, &x_from_subgroup, arity_bits)?;
-
-            x_index_bits = coset_index_bits;
         }
         // Final check of FRI. After all the reductions, we check that the final polynomial is equal
         // to the one sent by the prover.
         let final_poly_coeffs = &fri_proof.final_poly.0;
-        let final_poly_eval = goldilocks_extension_chip.reduce_extension_field_terms_base(
-            ctx,
-            &x_from_subgroup,
-            final_poly_coeffs,
-        )?;
-        goldilocks_extension_chip.assert_equal_extension(ctx, &prev_eval, &final_poly_eval)?;
-        Ok(())
+        ctx.check(
+            || "final polynomial mismatch",
+            |ctx| {
+                let final_poly_eval = goldilocks_extension_chip.reduce_extension_field_terms_base(
+                    ctx,
+                    &x_from_subgroup,
+                    final_poly_coeffs,
+                )?;
+                goldilocks_extension_chip.assert_equal_extension(ctx, &prev_eval, &final_poly_eval)
+            },
+        )
     }
 
     pub fn verify_fri_proof(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         initial_merkle_caps: &[AssignedMerkleCapValues<F>],
-        fri_challenges: &AssignedFriChallenges<F, 2>,
-        fri_openings: &AssignedFriOpenings<F, 2>,
-        fri_proof: &AssignedFriProofValues<F, 2>,
-        fri_instance_info: &FriInstanceInfo<F, 2>,
+        fri_challenges: &AssignedFriChallenges<F, D>,
+        fri_openings: &AssignedFriOpenings<F, D>,
+        fri_proof: &AssignedFriProofValues<F, D>,
+        fri_instance_info: &FriInstanceInfo<F, D>,
     ) -> Result<(), Error> {
         // verify proof of work
-        self.fri_verify_proof_of_work(
-            ctx,
-            &fri_challenges.fri_pow_response,
-            &self.fri_params.config,
+        ctx.check(
+            || "insufficient proof of work",
+            |ctx| {
+                self.fri_verify_proof_of_work(
+                    ctx,
+                    &fri_challenges.fri_pow_response,
+                    &self.fri_params.config,
+                )
+            },
         )?;
 
         // this value is the same across all queries
         let reduced_openings =
             self.compute_reduced_openings(ctx, &fri_challenges.fri_alpha, fri_openings)?;
         for (i, round_proof) in fri_proof.query_round_proofs.iter().enumerate() {
-            self.check_consistency(
-                ctx,
-                initial_merkle_caps,
-                fri_instance_info,
-                &fri_challenges.fri_alpha,
-                &fri_challenges.fri_betas,
-                fri_proof,
-                &fri_challenges.fri_query_indices[i],
-                round_proof,
-                &reduced_openings,
+            ctx.namespaced(
+                || format!("FRI query {i}"),
+                |ctx| {
+                    self.check_consistency(
+                        ctx,
+                        initial_merkle_caps,
+                        fri_instance_info,
+                        &fri_challenges.fri_alpha,
+                        &fri_challenges.fri_betas,
+                        fri_proof,
+                        &fri_challenges.fri_query_indices[i],
+                        round_proof,
+                        &reduced_openings,
+                    )
+                },
             )?;
         }
         Ok(())
@@ -1,7 +1,7 @@
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
     halo2curves::ff::PrimeField,
-    plonk::Error,
+    plonk::{ConstraintSystem, Error},
 };
 use halo2wrong_maingate::{fe_to_big, AssignedCondition, AssignedValue};
 
@@ -16,6 +16,7 @@ use crate::context::RegionCtx;
 use super::native_chip::{
     all_chip::{AllChip, AllChipConfig},
     arithmetic_chip::{ArithmeticChip, Term},
+    keccak_chip::KeccakChipConfig,
     utils::goldilocks_to_fe,
 };
 
@@ -24,6 +25,16 @@ pub struct GoldilocksChipConfig<F: PrimeField> {
     all_chip_config: AllChipConfig<F>,
 }
 
+impl<F: PrimeField> GoldilocksChipConfig<F> {
+    /// Configures the Keccak chip, unless it is already configured.
+    pub fn with_keccak(mut self, meta: &mut ConstraintSystem<F>) -> Self {
+        if self.all_chip_config.keccak_config.is_none() {
+            self.all_chip_config.keccak_config = Some(KeccakChipConfig::configure(meta));
+        }
+        self
+    }
+}
+
 pub struct GoldilocksChip<F: PrimeField> {
     goldilocks_chip_config: GoldilocksChipConfig<F>,
 }
@@ -417,7 +428,7 @@ impl<F: PrimeField> GoldilocksChip<F> {
         &self,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), halo2_proofs::plonk::Error> {
-        self.arithmetic_chip().load_table(layouter)
+        self.all_chip().load_table(layouter)
     }
 }
 
@@ -8,50 +8,51 @@ use super::{
 };
 
 #[derive(Clone, Debug)]
-pub struct AssignedExtensionAlgebra<F: PrimeField>(pub [AssignedExtensionFieldValue<F, 2>; 2]);
+pub struct AssignedExtensionAlgebra<F: PrimeField, const D: usize>(
+    pub [AssignedExtensionFieldValue<F, D>; D],
+);
 
-impl<F: PrimeField> AssignedExtensionAlgebra<F> {
-    pub fn to_ext_array(&self) -> [AssignedExtensionFieldValue<F, 2>; 2] {
+impl<F: PrimeField, const D: usize> AssignedExtensionAlgebra<F, D> {
+    pub fn to_ext_array(&self) -> [AssignedExtensionFieldValue<F, D>; D] {
         self.0.clone()
     }
 }
 
-pub struct GoldilocksExtensionAlgebraChip<F: PrimeField> {
+pub struct GoldilocksExtensionAlgebraChip<F: PrimeField, const D: usize> {
     goldilocks_chip_config: GoldilocksChipConfig<F>,
 }
 
-impl<F: PrimeField> GoldilocksExtensionAlgebraChip<F> {
+impl<F: PrimeField, const D: usize> GoldilocksExtensionAlgebraChip<F, D> {
     pub fn new(goldilocks_chip_config: &GoldilocksChipConfig<F>) -> Self {
         Self {
             goldilocks_chip_config: goldilocks_chip_config.clone(),
         }
     }
 
-    pub fn goldilocks_extension_chip(&self) -> GoldilocksExtensionChip<F> {
+    pub fn goldilocks_extension_chip(&self) -> GoldilocksExtensionChip<F, D> {
         GoldilocksExtensionChip::new(&self.goldilocks_chip_config)
     }
 
     pub fn zero_ext_algebra(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
         let zero_extension = goldilocks_extension_chip.zero_extension(ctx)?;
-        Ok(AssignedExtensionAlgebra([
-            zero_extension.clone(),
-            zero_extension,
-        ]))
+        Ok(AssignedExtensionAlgebra(std::array::from_fn(|_| {
+            zero_extension.clone()
+        })))
     }
 
     pub fn convert_to_ext_algebra(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        et: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
+        et: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
         let zero_extension = goldilocks_extension_chip.zero_extension(ctx)?;
-        let mut arr = vec![];
-        arr.extend([et.clone(), zero_extension]);
+        let mut arr = vec![et.clone()];
+        arr.extend((1..D).map(|_| zero_extension.clone()));
         Ok(AssignedExtensionAlgebra(arr.try_into().unwrap()))
     }
 
@@ -60,12 +61,12 @@ impl<F: PrimeField> GoldilocksExtensionAlgebraChip<F> {
         &self,
         ctx: &mut RegionCtx<'_, F>,
         constant: GoldilocksField,
-        starting_acc: &AssignedExtensionFieldValue<F, 2>,
+        starting_acc: &AssignedExtensionFieldValue<F, D>,
         pairs: &Vec<(
-            AssignedExtensionFieldValue<F, 2>,
-            AssignedExtensionFieldValue<F, 2>,
+            AssignedExtensionFieldValue<F, D>,
+            AssignedExtensionFieldValue<F, D>,
         )>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
         let mut acc = starting_acc.clone();
         for (a, b) in pairs {
@@ -85,13 +86,13 @@ impl<F: PrimeField> GoldilocksExtensionAlgebraChip<F> {
     pub fn scalar_mul_add_ext_algebra(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionFieldValue<F, 2>,
-        b: &AssignedExtensionAlgebra<F>,
-        c: &AssignedExtensionAlgebra<F>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
+        a: &AssignedExtensionFieldValue<F, D>,
+        b: &AssignedExtensionAlgebra<F, D>,
+        c: &AssignedExtensionAlgebra<F, D>,
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
         let mut res = c.clone();
-        for i in 0..2 {
+        for i in 0..D {
             res.0[i] = goldilocks_extension_chip.mul_add_extension(ctx, a, &b.0[i], &c.0[i])?;
         }
         Ok(res)
@@ -101,9 +102,9 @@ impl<F: PrimeField> GoldilocksExtensionAlgebraChip<F> {
     pub fn scalar_mul_ext_algebra(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionFieldValue<F, 2>,
-        b: &AssignedExtensionAlgebra<F>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
+        a: &AssignedExtensionFieldValue<F, D>,
+        b: &AssignedExtensionAlgebra<F, D>,
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
         let zero = self.zero_ext_algebra(ctx)?;
         self.scalar_mul_add_ext_algebra(ctx, a, b, &zero)
     }
@@ -112,19 +113,19 @@ impl<F: PrimeField> GoldilocksExtensionAlgebraChip<F> {
     pub fn mul_add_ext_algebra(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionAlgebra<F>,
-        b: &AssignedExtensionAlgebra<F>,
-        c: &AssignedExtensionAlgebra<F>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
-        let w = GoldilocksExtensionChip::<F>::w();
-        let mut inner = vec![vec![]; 2];
-        let mut inner_w = vec![vec![]; 2];
-        for i in 0..2 {
-            for j in 0..2 - i {
-                inner[(i + j) % 2].push((a.0[i].clone(), b.0[j].clone()));
+        a: &AssignedExtensionAlgebra<F, D>,
+        b: &AssignedExtensionAlgebra<F, D>,
+        c: &AssignedExtensionAlgebra<F, D>,
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
+        let w = GoldilocksExtensionChip::<F, D>::w();
+        let mut inner = vec![vec![]; D];
+        let mut inner_w = vec![vec![]; D];
+        for i in 0..D {
+            for j in 0..D - i {
+                inner[(i + j) % D].push((a.0[i].clone(), b.0[j].clone()));
             }
-            for j in 2 - i..2 {
-                inner_w[(i + j) % 2].push((a.0[i].clone(), b.0[j].clone()));
+            for j in D - i..D {
+                inner_w[(i + j) % D].push((a.0[i].clone(), b.0[j].clone()));
             }
         }
         let res = inner_w
@@ -149,9 +150,9 @@ impl<F: PrimeField> GoldilocksExtensionAlgebraChip<F> {
     pub fn mul_ext_algebra(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionAlgebra<F>,
-        b: &AssignedExtensionAlgebra<F>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
+        a: &AssignedExtensionAlgebra<F, D>,
+        b: &AssignedExtensionAlgebra<F, D>,
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
         let zero = self.zero_ext_algebra(ctx)?;
         self.mul_add_ext_algebra(ctx, a, b, &zero)
     }
@@ -159,12 +160,12 @@ impl<F: PrimeField> GoldilocksExtensionAlgebraChip<F> {
     pub fn sub_ext_algebra(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionAlgebra<F>,
-        b: &AssignedExtensionAlgebra<F>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
+        a: &AssignedExtensionAlgebra<F, D>,
+        b: &AssignedExtensionAlgebra<F, D>,
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip();
         let mut res = a.clone();
-        for i in 0..2 {
+        for i in 0..D {
             res.0[i] = goldilocks_extension_chip.sub_extension(ctx, &a.0[i], &b.0[i])?;
         }
         Ok(res)
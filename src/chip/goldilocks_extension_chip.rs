@@ -1,23 +1,37 @@
 use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::circuit::Value;
 use halo2_proofs::plonk::Error;
 use halo2wrong_maingate::{fe_to_big, AssignedValue};
 use plonky2::field::goldilocks_field::GoldilocksField;
 use plonky2::field::types::Field;
-use plonky2::field::{extension::quadratic::QuadraticExtension, types::PrimeField64};
+use plonky2::field::extension::{quadratic::QuadraticExtension, quartic::QuarticExtension};
+use plonky2::field::types::PrimeField64;
 
 use crate::context::RegionCtx;
+use crate::error::VerifierError;
 use crate::types::assigned::AssignedExtensionFieldValue;
 
 use super::goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig};
 use super::native_chip::arithmetic_chip::{ArithmeticChip, TermExt};
 
-pub struct AssignedExtensionAlgebra<F: PrimeField>(pub [AssignedExtensionFieldValue<F, 2>; 2]);
+/// Checks that the chips support the degree `D` extension of the Goldilocks field, i.e. that `D`
+/// is 2 or 4. Runs on the construction of the verifier circuits.
+pub fn check_extension_degree<const D: usize>() -> Result<(), VerifierError> {
+    match D {
+        2 | 4 => Ok(()),
+        _ => Err(VerifierError::UnsupportedExtensionDegree(D)),
+    }
+}
 
-pub struct GoldilocksExtensionChip<F: PrimeField> {
+pub struct AssignedExtensionAlgebra<F: PrimeField, const D: usize>(
+    pub [AssignedExtensionFieldValue<F, D>; D],
+);
+
+pub struct GoldilocksExtensionChip<F: PrimeField, const D: usize> {
     goldilocks_chip_config: GoldilocksChipConfig<F>,
 }
 
-impl<F: PrimeField> GoldilocksExtensionChip<F> {
+impl<F: PrimeField, const D: usize> GoldilocksExtensionChip<F, D> {
     pub fn new(goldilocks_chip_config: &GoldilocksChipConfig<F>) -> Self {
         Self {
             goldilocks_chip_config: goldilocks_chip_config.clone(),
@@ -51,15 +65,15 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     }
 }
 
-// Layouts GoldilocksField quadratic extension field arithmetic constraints
-impl<F: PrimeField> GoldilocksExtensionChip<F> {
+// Layouts GoldilocksField extension field arithmetic constraints
+impl<F: PrimeField, const D: usize> GoldilocksExtensionChip<F, D> {
     pub fn mul_add_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionFieldValue<F, 2>,
-        b: &AssignedExtensionFieldValue<F, 2>,
-        c: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        a: &AssignedExtensionFieldValue<F, D>,
+        b: &AssignedExtensionFieldValue<F, D>,
+        c: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let assigned = self.arithmetic_chip().apply_ext(
             ctx,
             TermExt::Assigned(&a.0),
@@ -72,31 +86,34 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn div_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        x: &AssignedExtensionFieldValue<F, 2>,
-        y: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
-        y.0[0]
-            .value()
-            .zip(y.0[1].value())
-            .map(|(a, b)| assert!(*a != F::from(0) || *b != F::from(0)));
+        x: &AssignedExtensionFieldValue<F, D>,
+        y: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
+        check_extension_degree::<D>().map_err(|_| Error::Synthesis)?;
+        let y_value = y
+            .0
+            .iter()
+            .map(|v| v.value().cloned())
+            .collect::<Value<Vec<F>>>();
+        y_value
+            .as_ref()
+            .map(|y| assert!(y.iter().any(|v| *v != F::from(0))));
         let goldilocks_chip = self.goldilocks_chip();
-        let y_inv = y.0[0]
-            .value()
-            .zip(y.0[1].value())
-            .map(|(&hi, &lo)| {
-                let y_inv = QuadraticExtension([
-                    self.native_fe_to_goldilocks(hi),
-                    self.native_fe_to_goldilocks(lo),
-                ])
-                .inverse()
-                .0
-                .map(|v| self.goldilocks_to_native_fe(v));
-                (y_inv[0], y_inv[1])
+        let y_inv = y_value
+            .map(|y| {
+                let y = y
+                    .into_iter()
+                    .map(|v| self.native_fe_to_goldilocks(v))
+                    .collect::<Vec<_>>();
+                inverse_extension::<D>(y.try_into().unwrap())
+                    .map(|v| self.goldilocks_to_native_fe(v))
             })
-            .unzip();
-        let y_inv0 = goldilocks_chip.assign_value(ctx, y_inv.0)?;
-        let y_inv1 = goldilocks_chip.assign_value(ctx, y_inv.1)?;
-        let y_inv = AssignedExtensionFieldValue([y_inv0, y_inv1]);
+            .transpose_vec(D);
+        let y_inv = y_inv
+            .into_iter()
+            .map(|v| goldilocks_chip.assign_value(ctx, v))
+            .collect::<Result<Vec<AssignedValue<F>>, Error>>()?;
+        let y_inv = AssignedExtensionFieldValue(y_inv.try_into().unwrap());
         // y * y_inv = 1
         let yy_inv = self.mul_extension(ctx, y, &y_inv)?;
         self.assert_one_extension(ctx, &yy_inv)?;
@@ -108,10 +125,10 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn div_add_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        x: &AssignedExtensionFieldValue<F, 2>,
-        y: &AssignedExtensionFieldValue<F, 2>,
-        z: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        x: &AssignedExtensionFieldValue<F, D>,
+        y: &AssignedExtensionFieldValue<F, D>,
+        z: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let x_div_y = self.div_extension(ctx, x, y)?;
         self.add_extension(ctx, &x_div_y, z)
     }
@@ -119,9 +136,9 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn add_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        addend_0: &AssignedExtensionFieldValue<F, 2>,
-        addend_1: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        addend_0: &AssignedExtensionFieldValue<F, D>,
+        addend_1: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_chip = self.goldilocks_chip();
         let added = addend_0
             .0
@@ -135,9 +152,9 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn scalar_mul(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        multiplicand: &AssignedExtensionFieldValue<F, 2>,
+        multiplicand: &AssignedExtensionFieldValue<F, D>,
         scalar: GoldilocksField,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_chip = self.goldilocks_chip();
         let assigned_scalar = goldilocks_chip.assign_constant(ctx, scalar)?;
         let multiplied = multiplicand
@@ -154,10 +171,10 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
         ctx: &mut RegionCtx<'_, F>,
         const_0: GoldilocksField,
         const_1: GoldilocksField,
-        multiplicand_0: &AssignedExtensionFieldValue<F, 2>,
-        multiplicand_1: &AssignedExtensionFieldValue<F, 2>,
-        addend: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        multiplicand_0: &AssignedExtensionFieldValue<F, D>,
+        multiplicand_1: &AssignedExtensionFieldValue<F, D>,
+        addend: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         // multiplicand_0 * multiplicand_1
         let mut term_1 = self.mul_extension(ctx, multiplicand_0, multiplicand_1)?;
         // const_0 * multiplicand_0 * multiplicand_1
@@ -170,9 +187,9 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn zero_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_chip = self.goldilocks_chip();
-        let elements = (0..2)
+        let elements = (0..D)
             .map(|_| goldilocks_chip.assign_constant(ctx, GoldilocksField::ZERO))
             .collect::<Result<Vec<AssignedValue<F>>, Error>>()?;
         Ok(AssignedExtensionFieldValue(elements.try_into().unwrap()))
@@ -181,34 +198,24 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn one_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
-        let goldilocks_chip = self.goldilocks_chip();
-        let elements = [
-            goldilocks_chip.assign_constant(ctx, GoldilocksField::ONE)?,
-            goldilocks_chip.assign_constant(ctx, GoldilocksField::ZERO)?,
-        ];
-        Ok(AssignedExtensionFieldValue(elements))
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
+        self.constant_extension_from_base(ctx, GoldilocksField::ONE)
     }
 
     pub fn two_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
-        let goldilocks_chip = self.goldilocks_chip();
-        let elements = [
-            goldilocks_chip.assign_constant(ctx, GoldilocksField::from_canonical_u64(2))?,
-            goldilocks_chip.assign_constant(ctx, GoldilocksField::ZERO)?,
-        ];
-        Ok(AssignedExtensionFieldValue(elements))
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
+        self.constant_extension_from_base(ctx, GoldilocksField::TWO)
     }
 
     pub fn mul_extension_with_const(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         const_0: GoldilocksField,
-        multiplicand_0: &AssignedExtensionFieldValue<F, 2>,
-        multiplicand_1: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        multiplicand_0: &AssignedExtensionFieldValue<F, D>,
+        multiplicand_1: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let zero = self.zero_extension(ctx)?;
         self.arithmetic_extension(
             ctx,
@@ -223,9 +230,9 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn mul_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        multiplicand_0: &AssignedExtensionFieldValue<F, 2>,
-        multiplicand_1: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        multiplicand_0: &AssignedExtensionFieldValue<F, D>,
+        multiplicand_1: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let zero = self.zero_extension(ctx)?;
         self.mul_add_extension(ctx, multiplicand_0, multiplicand_1, &zero)
     }
@@ -233,10 +240,10 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn mul_sub_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionFieldValue<F, 2>,
-        b: &AssignedExtensionFieldValue<F, 2>,
-        c: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        a: &AssignedExtensionFieldValue<F, D>,
+        b: &AssignedExtensionFieldValue<F, D>,
+        c: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let one = GoldilocksField::ONE;
         self.arithmetic_extension(ctx, one, -one, a, b, c)
     }
@@ -244,17 +251,17 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn square_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        x: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        x: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         self.mul_extension(ctx, x, x)
     }
 
     pub fn exp_power_of_2_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        mut base: AssignedExtensionFieldValue<F, 2>,
+        mut base: AssignedExtensionFieldValue<F, D>,
         power_log: usize,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         for _ in 0..power_log {
             base = self.square_extension(ctx, &base)?;
         }
@@ -264,9 +271,9 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn exp(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        base: &AssignedExtensionFieldValue<F, 2>,
+        base: &AssignedExtensionFieldValue<F, D>,
         power: usize,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         match power {
             0 => return self.one_extension(ctx),
             1 => return Ok(base.clone()),
@@ -283,8 +290,8 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn mul_many_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        terms: Vec<AssignedExtensionFieldValue<F, 2>>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        terms: Vec<AssignedExtensionFieldValue<F, D>>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let one = self.one_extension(ctx)?;
         let result = terms.into_iter().fold(one, |acc, term| {
             self.mul_extension(ctx, &acc, &term).unwrap()
@@ -295,9 +302,9 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn sub_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        lhs: &AssignedExtensionFieldValue<F, 2>,
-        rhs: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        lhs: &AssignedExtensionFieldValue<F, D>,
+        rhs: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let one = GoldilocksField::ONE;
         let one_extension = self.one_extension(ctx)?;
         self.arithmetic_extension(ctx, one, -one, lhs, &one_extension, rhs)
@@ -306,8 +313,8 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn constant_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        constant: &[GoldilocksField; 2],
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        constant: &[GoldilocksField; D],
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_chip = self.goldilocks_chip();
         let elements = constant
             .into_iter()
@@ -316,24 +323,33 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
         Ok(AssignedExtensionFieldValue(elements.try_into().unwrap()))
     }
 
+    /// Assigns the constant `constant + 0*X + ... + 0*X^(D-1)`.
+    pub fn constant_extension_from_base(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        constant: GoldilocksField,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
+        let mut elements = [GoldilocksField::ZERO; D];
+        elements[0] = constant;
+        self.constant_extension(ctx, &elements)
+    }
+
     pub fn convert_to_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         value: &AssignedValue<F>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
-        let goldilocks_chip = self.goldilocks_chip();
-        Ok(AssignedExtensionFieldValue([
-            value.clone(),
-            goldilocks_chip.assign_constant(ctx, GoldilocksField::ZERO)?,
-        ]))
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
+        let mut elements = self.zero_extension(ctx)?;
+        elements.0[0] = value.clone();
+        Ok(elements)
     }
 
     pub fn reduce_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        base: &AssignedExtensionFieldValue<F, 2>,
-        terms: &Vec<AssignedExtensionFieldValue<F, 2>>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        base: &AssignedExtensionFieldValue<F, D>,
+        terms: &Vec<AssignedExtensionFieldValue<F, D>>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let zero_extension = self.zero_extension(ctx)?;
         let result = terms.iter().rev().fold(zero_extension, |acc, term| {
             self.mul_add_extension(ctx, &acc, base, term).unwrap()
@@ -344,13 +360,13 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn reduce_base_field_terms_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        base: &AssignedExtensionFieldValue<F, 2>,
+        base: &AssignedExtensionFieldValue<F, D>,
         terms: &Vec<AssignedValue<F>>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let terms = terms
             .iter()
             .map(|t| self.convert_to_extension(ctx, t))
-            .collect::<Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error>>()?;
+            .collect::<Result<Vec<AssignedExtensionFieldValue<F, D>>, Error>>()?;
         self.reduce_extension(ctx, base, &terms)
     }
 
@@ -358,8 +374,8 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
         &self,
         ctx: &mut RegionCtx<'_, F>,
         base: &AssignedValue<F>,
-        terms: &Vec<AssignedExtensionFieldValue<F, 2>>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        terms: &Vec<AssignedExtensionFieldValue<F, D>>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let base = self.convert_to_extension(ctx, base)?;
         self.reduce_extension(ctx, &base, terms)
     }
@@ -368,10 +384,10 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn shift(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        factor: &AssignedExtensionFieldValue<F, 2>,
+        factor: &AssignedExtensionFieldValue<F, D>,
         power: usize,
-        shifted: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        shifted: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let exp = self.exp(ctx, factor, power)?;
         self.mul_extension(ctx, &exp, shifted)
     }
@@ -379,23 +395,26 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn assert_equal_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        lhs: &AssignedExtensionFieldValue<F, 2>,
-        rhs: &AssignedExtensionFieldValue<F, 2>,
+        lhs: &AssignedExtensionFieldValue<F, D>,
+        rhs: &AssignedExtensionFieldValue<F, D>,
     ) -> Result<(), Error> {
         let goldilocks_chip = self.goldilocks_chip();
-        goldilocks_chip.assert_equal(ctx, &lhs.0[0], &rhs.0[0])?;
-        goldilocks_chip.assert_equal(ctx, &lhs.0[1], &rhs.0[1])?;
+        for (lhs, rhs) in lhs.0.iter().zip(rhs.0.iter()) {
+            goldilocks_chip.assert_equal(ctx, lhs, rhs)?;
+        }
         Ok(())
     }
 
     pub fn assert_one_extension(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &AssignedExtensionFieldValue<F, 2>,
+        a: &AssignedExtensionFieldValue<F, D>,
     ) -> Result<(), Error> {
         let goldilocks_chip = self.goldilocks_chip();
         goldilocks_chip.assert_one(ctx, &a.0[0])?;
-        goldilocks_chip.assert_zero(ctx, &a.0[1])?;
+        for a in &a.0[1..] {
+            goldilocks_chip.assert_zero(ctx, a)?;
+        }
         Ok(())
     }
 
@@ -405,13 +424,41 @@ impl<F: PrimeField> GoldilocksExtensionChip<F> {
     pub fn select(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        cond: &AssignedExtensionFieldValue<F, 2>,
-        a: &AssignedExtensionFieldValue<F, 2>,
-        b: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        cond: &AssignedExtensionFieldValue<F, D>,
+        a: &AssignedExtensionFieldValue<F, D>,
+        b: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         // cond * (a - b) + b
         let a_minus_b = self.sub_extension(ctx, a, b)?;
         let one = GoldilocksField::ONE;
         self.arithmetic_extension(ctx, one, one, cond, &a_minus_b, b)
     }
+
+    /// Returns `prod_{i < n} (x - i)`, which is zero if and only if `x` is in `0..n`.
+    pub fn range_check_extension(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        x: &AssignedExtensionFieldValue<F, D>,
+        n: usize,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
+        let mut product = self.one_extension(ctx)?;
+        for i in 0..n {
+            let i =
+                self.constant_extension_from_base(ctx, GoldilocksField::from_canonical_usize(i))?;
+            let term = self.sub_extension(ctx, x, &i)?;
+            product = self.mul_extension(ctx, &product, &term)?;
+        }
+        Ok(product)
+    }
+}
+
+/// Inverts a nonzero element of the degree `D` extension of the Goldilocks field, `D` being
+/// checked by `check_extension_degree`. Other degrees return `x` unchanged.
+fn inverse_extension<const D: usize>(x: [GoldilocksField; D]) -> [GoldilocksField; D] {
+    let inverse = match D {
+        2 => QuadraticExtension(x[..].try_into().unwrap()).inverse().0.to_vec(),
+        4 => QuarticExtension(x[..].try_into().unwrap()).inverse().0.to_vec(),
+        _ => return x,
+    };
+    inverse.try_into().unwrap()
 }
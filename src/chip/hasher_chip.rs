@@ -1,16 +1,61 @@
-use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use halo2_proofs::{
+    halo2curves::ff::PrimeField,
+    plonk::{ConstraintSystem, Error},
+};
 use halo2wrong_maingate::AssignedValue;
 use plonky2::{
     field::{goldilocks_field::GoldilocksField, types::Field},
     hash::{keccak::SPONGE_RATE, poseidon::SPONGE_WIDTH},
 };
 
-use crate::context::RegionCtx;
+use crate::{context::RegionCtx, types::assigned::AssignedHashValues};
 
-use super::goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig};
+use super::{
+    goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+    native_chip::all_chip::AllChipConfig,
+};
 
 const RATE: usize = SPONGE_RATE;
 
+/// The hash function of the plonky2 config verified in the circuit, i.e. the permutation of its
+/// challenger and the compression of its Merkle trees.
+pub trait HasherInstructions<F: PrimeField>: Sized {
+    /// Whether `configure` configures the Keccak chip, which `PublicInputsMode::Keccak` needs.
+    const CONFIGURES_KECCAK: bool = false;
+
+    /// Configures the chips needed by the hasher.
+    fn configure(meta: &mut ConstraintSystem<F>) -> GoldilocksChipConfig<F> {
+        GoldilocksChip::configure(&AllChipConfig::configure(meta))
+    }
+
+    fn new(
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+    ) -> Result<Self, Error>;
+
+    /// Constrains the permutation of the sponge state, like `PlonkyPermutation::permute`.
+    fn permute_state(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedValue<F>; SPONGE_WIDTH],
+    ) -> Result<[AssignedValue<F>; SPONGE_WIDTH], Error>;
+
+    /// Constrains `Hasher::hash_or_noop`, which hashes the leaves of Merkle trees.
+    fn hash_or_noop(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        inputs: &[AssignedValue<F>],
+    ) -> Result<AssignedHashValues<F>, Error>;
+
+    /// Constrains `Hasher::two_to_one`, which compresses the nodes of Merkle trees.
+    fn two_to_one(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        left: &AssignedHashValues<F>,
+        right: &AssignedHashValues<F>,
+    ) -> Result<AssignedHashValues<F>, Error>;
+}
+
 /// `AssignedState` is composed of `T` sized assigned values
 #[derive(Debug, Clone)]
 pub struct AssignedState<F: PrimeField>(pub(super) [AssignedValue<F>; SPONGE_WIDTH]);
@@ -20,8 +65,6 @@ pub struct AssignedState<F: PrimeField>(pub(super) [AssignedValue<F>; SPONGE_WID
 #[derive(Debug, Clone)]
 pub struct HasherChip<F: PrimeField> {
     state: AssignedState<F>,
-    absorbing: Vec<AssignedValue<F>>,
-    output_buffer: Vec<AssignedValue<F>>,
     goldilocks_chip_config: GoldilocksChipConfig<F>,
 }
 
@@ -40,53 +83,9 @@ impl<F: PrimeField> HasherChip<F> {
 
         Ok(Self {
             state: AssignedState(initial_state.try_into().unwrap()),
-            absorbing: vec![],
-            output_buffer: vec![],
             goldilocks_chip_config: goldilocks_chip_config.clone(),
         })
     }
-
-    /// Appends field elements to the absorbation line. It won't perform
-    /// permutation here
-    pub fn update(
-        &mut self,
-        _ctx: &mut RegionCtx<'_, F>,
-        element: &AssignedValue<F>,
-    ) -> Result<(), Error> {
-        self.output_buffer.clear();
-        self.absorbing.push(element.clone());
-        Ok(())
-    }
-
-    fn absorb_buffered_inputs(&mut self, ctx: &mut RegionCtx<'_, F>) -> Result<(), Error> {
-        if self.absorbing.is_empty() {
-            return Ok(());
-        }
-        let buffered_inputs = self.absorbing.clone();
-        for input_chunk in buffered_inputs.chunks(RATE) {
-            self.duplexing(ctx, input_chunk)?;
-        }
-        self.absorbing.clear();
-        Ok(())
-    }
-
-    pub fn squeeze(
-        &mut self,
-        ctx: &mut RegionCtx<'_, F>,
-        num_outputs: usize,
-    ) -> Result<Vec<AssignedValue<F>>, Error> {
-        let mut output = vec![];
-        for _i in 0..num_outputs {
-            self.absorb_buffered_inputs(ctx)?;
-
-            if self.output_buffer.is_empty() {
-                self.permutation(ctx)?;
-                self.output_buffer = self.state.0[0..RATE].to_vec();
-            }
-            output.push(self.output_buffer.pop().unwrap())
-        }
-        Ok(output)
-    }
 }
 
 impl<F: PrimeField> HasherChip<F> {
@@ -104,30 +103,12 @@ impl<F: PrimeField> HasherChip<F> {
         Ok(())
     }
 
-    fn duplexing(
-        &mut self,
-        ctx: &mut RegionCtx<'_, F>,
-        input: &[AssignedValue<F>],
-    ) -> Result<(), Error> {
-        for (word, input) in self.state.0.iter_mut().zip(input.iter()) {
-            *word = input.clone();
-        }
-        self.permutation(ctx)?;
-
-        self.output_buffer.clear();
-        self.output_buffer.extend_from_slice(&self.state.0[0..RATE]);
-        Ok(())
-    }
-
     pub fn hash(
         &mut self,
         ctx: &mut RegionCtx<'_, F>,
         inputs: Vec<AssignedValue<F>>,
         num_outputs: usize,
     ) -> Result<Vec<AssignedValue<F>>, Error> {
-        // Flush the input que
-        self.absorbing.clear();
-
         for chunk in inputs.chunks(RATE) {
             for (word, input) in self.state.0.iter_mut().zip(chunk.iter()) {
                 *word = input.clone();
@@ -171,6 +152,60 @@ impl<F: PrimeField> HasherChip<F> {
     }
 }
 
+impl<F: PrimeField> HasherInstructions<F> for HasherChip<F> {
+    fn new(
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+    ) -> Result<Self, Error> {
+        HasherChip::new(ctx, goldilocks_chip_config)
+    }
+
+    fn permute_state(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedValue<F>; SPONGE_WIDTH],
+    ) -> Result<[AssignedValue<F>; SPONGE_WIDTH], Error> {
+        self.state.0 = state;
+        self.permutation(ctx)?;
+        Ok(self.state.0.clone())
+    }
+
+    fn hash_or_noop(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        inputs: &[AssignedValue<F>],
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let elements = if inputs.len() <= 4 {
+            // Short inputs are zero-padded instead of hashed.
+            let zero = self
+                .goldilocks_chip()
+                .assign_constant(ctx, GoldilocksField::ZERO)?;
+            let mut elements = inputs.to_vec();
+            elements.resize(4, zero);
+            elements
+        } else {
+            HasherChip::new(ctx, &self.goldilocks_chip_config)?.hash(ctx, inputs.to_vec(), 4)?
+        };
+        Ok(AssignedHashValues {
+            elements: elements.try_into().unwrap(),
+        })
+    }
+
+    fn two_to_one(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        left: &AssignedHashValues<F>,
+        right: &AssignedHashValues<F>,
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let inputs = [left.elements.clone(), right.elements.clone()].concat();
+        let elements =
+            HasherChip::new(ctx, &self.goldilocks_chip_config)?.permute(ctx, inputs, 4)?;
+        Ok(AssignedHashValues {
+            elements: elements.try_into().unwrap(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -197,7 +232,7 @@ mod tests {
         context::RegionCtx,
     };
 
-    use super::HasherChip;
+    use super::{HasherChip, HasherInstructions as _};
 
     #[derive(Clone, Default)]
     pub struct TestCircuit {
@@ -241,11 +276,9 @@ mod tests {
                         .collect::<Result<Vec<_>, Error>>()?;
 
                     let mut hasher_chip = HasherChip::<Fr>::new(ctx, &config)?;
-                    hasher_chip.state.0 = input_assigned.try_into().unwrap();
-                    hasher_chip.permutation(ctx)?;
-                    hasher_chip
-                        .state
-                        .0
+                    let output =
+                        hasher_chip.permute_state(ctx, input_assigned.try_into().unwrap())?;
+                    output
                         .iter()
                         .zip(self.expected_output.iter())
                         .for_each(|(x, e)| {
@@ -0,0 +1,310 @@
+use halo2_proofs::{
+    halo2curves::ff::PrimeField,
+    plonk::{ConstraintSystem, Error},
+};
+use halo2wrong_maingate::AssignedValue;
+use plonky2::{
+    field::{goldilocks_field::GoldilocksField, types::Field},
+    hash::poseidon::SPONGE_WIDTH,
+};
+
+use crate::{context::RegionCtx, types::assigned::AssignedHashValues};
+
+use super::{
+    goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+    hasher_chip::HasherInstructions,
+    native_chip::{
+        all_chip::AllChipConfig,
+        keccak_chip::{KeccakChip, NUM_NIBBLES},
+    },
+};
+
+/// The size in bytes of the hashes of `KeccakGoldilocksConfig`, i.e. `KeccakHash<25>`.
+pub const HASH_SIZE: usize = 25;
+
+/// The hash elements are read from the bytes in chunks of 7, so that they fit in the field.
+const ELEMENT_NIBBLES: usize = 14;
+
+/// Constrains the hasher of plonky2's `KeccakGoldilocksConfig`, on top of `KeccakChip`.
+#[derive(Debug, Clone)]
+pub struct KeccakHasherChip<F: PrimeField> {
+    goldilocks_chip_config: GoldilocksChipConfig<F>,
+}
+
+impl<F: PrimeField> KeccakHasherChip<F> {
+    pub fn goldilocks_chip(&self) -> GoldilocksChip<F> {
+        GoldilocksChip::new(&self.goldilocks_chip_config)
+    }
+
+    fn keccak_chip(&self) -> KeccakChip<F> {
+        self.goldilocks_chip().all_chip().keccak_chip()
+    }
+
+    // the nibbles of the bytes of a hash, like `BytesHash::to_bytes`
+    fn hash_to_nibbles(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let keccak_chip = self.keccak_chip();
+        let mut nibbles = vec![];
+        for (element, num_nibbles) in hash.elements.iter().zip(element_sizes()) {
+            let decomposed = keccak_chip.decompose(ctx, element, num_nibbles)?;
+            nibbles.extend_from_slice(&decomposed[..num_nibbles]);
+        }
+        Ok(nibbles)
+    }
+
+    // the hash of the given bytes, like `BytesHash::from_bytes` followed by `to_vec`
+    fn nibbles_to_hash(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        nibbles: &[AssignedValue<F>],
+    ) -> Result<AssignedHashValues<F>, Error> {
+        assert_eq!(nibbles.len(), 2 * HASH_SIZE);
+        let keccak_chip = self.keccak_chip();
+        let elements = nibbles
+            .chunks(ELEMENT_NIBBLES)
+            .map(|chunk| keccak_chip.compose(ctx, chunk))
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(AssignedHashValues {
+            elements: elements.try_into().unwrap(),
+        })
+    }
+
+    // the nibbles of the 8 little-endian bytes of each element
+    fn elements_to_nibbles(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        elements: &[AssignedValue<F>],
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let keccak_chip = self.keccak_chip();
+        let mut nibbles = vec![];
+        for element in elements {
+            nibbles.extend(keccak_chip.decompose(ctx, element, NUM_NIBBLES)?);
+        }
+        Ok(nibbles)
+    }
+}
+
+fn element_sizes() -> impl Iterator<Item = usize> {
+    (0..2 * HASH_SIZE)
+        .step_by(ELEMENT_NIBBLES)
+        .map(|start| ELEMENT_NIBBLES.min(2 * HASH_SIZE - start))
+}
+
+impl<F: PrimeField> HasherInstructions<F> for KeccakHasherChip<F> {
+    const CONFIGURES_KECCAK: bool = true;
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> GoldilocksChipConfig<F> {
+        GoldilocksChip::configure(&AllChipConfig::configure_with_keccak(meta))
+    }
+
+    fn new(
+        _ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            goldilocks_chip_config: goldilocks_chip_config.clone(),
+        })
+    }
+
+    /// Constrains `KeccakPermutation`, which reads the state from the "onion" of Keccak-256
+    /// hashes of the state bytes. Unlike plonky2, words that are not canonical field elements
+    /// are rejected instead of skipped, which happens with probability about `2^-32` per word.
+    fn permute_state(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedValue<F>; SPONGE_WIDTH],
+    ) -> Result<[AssignedValue<F>; SPONGE_WIDTH], Error> {
+        let keccak_chip = self.keccak_chip();
+        let arithmetic_chip = self.goldilocks_chip().arithmetic_chip();
+        let mut input = self.elements_to_nibbles(ctx, &state)?;
+        let mut words = vec![];
+        while words.len() < SPONGE_WIDTH {
+            let digest = keccak_chip.keccak256(ctx, &input)?;
+            for word in digest.chunks(NUM_NIBBLES) {
+                words.push(keccak_chip.compose(ctx, word)?);
+            }
+            input = digest;
+        }
+        words.truncate(SPONGE_WIDTH);
+        for word in words.iter() {
+            arithmetic_chip.range_check(ctx, word)?;
+        }
+        Ok(words.try_into().unwrap())
+    }
+
+    fn hash_or_noop(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        inputs: &[AssignedValue<F>],
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let mut nibbles = self.elements_to_nibbles(ctx, inputs)?;
+        if inputs.len() * 8 <= HASH_SIZE {
+            // Short inputs are zero-padded instead of hashed.
+            let zero = self
+                .goldilocks_chip()
+                .assign_constant(ctx, GoldilocksField::ZERO)?;
+            nibbles.resize(2 * HASH_SIZE, zero);
+        } else {
+            nibbles = self.keccak_chip().keccak256(ctx, &nibbles)?;
+            nibbles.truncate(2 * HASH_SIZE);
+        }
+        self.nibbles_to_hash(ctx, &nibbles)
+    }
+
+    fn two_to_one(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        left: &AssignedHashValues<F>,
+        right: &AssignedHashValues<F>,
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let nibbles = [
+            self.hash_to_nibbles(ctx, left)?,
+            self.hash_to_nibbles(ctx, right)?,
+        ]
+        .concat();
+        let digest = self.keccak_chip().keccak256(ctx, &nibbles)?;
+        self.nibbles_to_hash(ctx, &digest[..2 * HASH_SIZE])
+    }
+}
+
+/// Hashes like `H`, and also configures the Keccak chip, which `PublicInputsMode::Keccak` needs
+/// to commit to the public inputs.
+#[derive(Debug, Clone)]
+pub struct WithKeccakChip<H>(H);
+
+impl<F: PrimeField, H: HasherInstructions<F>> HasherInstructions<F> for WithKeccakChip<H> {
+    const CONFIGURES_KECCAK: bool = true;
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> GoldilocksChipConfig<F> {
+        H::configure(meta).with_keccak(meta)
+    }
+
+    fn new(
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+    ) -> Result<Self, Error> {
+        Ok(Self(H::new(ctx, goldilocks_chip_config)?))
+    }
+
+    fn permute_state(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedValue<F>; SPONGE_WIDTH],
+    ) -> Result<[AssignedValue<F>; SPONGE_WIDTH], Error> {
+        self.0.permute_state(ctx, state)
+    }
+
+    fn hash_or_noop(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        inputs: &[AssignedValue<F>],
+    ) -> Result<AssignedHashValues<F>, Error> {
+        self.0.hash_or_noop(ctx, inputs)
+    }
+
+    fn two_to_one(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        left: &AssignedHashValues<F>,
+        right: &AssignedHashValues<F>,
+    ) -> Result<AssignedHashValues<F>, Error> {
+        self.0.two_to_one(ctx, left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Sample},
+        hash::{hashing::PlonkyPermutation as _, keccak::KeccakPermutation},
+    };
+
+    use crate::{
+        chip::{
+            goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+            hasher_chip::HasherInstructions,
+            native_chip::utils::{fe_to_goldilocks, goldilocks_to_fe},
+        },
+        context::RegionCtx,
+    };
+
+    use super::KeccakHasherChip;
+
+    #[derive(Clone, Default)]
+    pub struct TestCircuit {
+        input: [GoldilocksField; 12],
+        expected_output: [GoldilocksField; 8],
+    }
+
+    impl Circuit<Fr> for TestCircuit {
+        type Config = GoldilocksChipConfig<Fr>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            KeccakHasherChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let goldilocks_chip = GoldilocksChip::new(&config);
+            goldilocks_chip.load_table(&mut layouter)?;
+            layouter.assign_region(
+                || "keccak hasher chip",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+
+                    let input_assigned = self
+                        .input
+                        .iter()
+                        .map(|x| goldilocks_chip.assign_value(ctx, Value::known(goldilocks_to_fe(*x))))
+                        .collect::<Result<Vec<_>, Error>>()?;
+
+                    let mut hasher_chip = KeccakHasherChip::<Fr>::new(ctx, &config)?;
+                    let output =
+                        hasher_chip.permute_state(ctx, input_assigned.try_into().unwrap())?;
+                    output
+                        .iter()
+                        .zip(self.expected_output.iter())
+                        .for_each(|(x, e)| {
+                            x.value().map(|x| assert_eq!(fe_to_goldilocks(*x), *e));
+                        });
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_keccak_hasher_chip_mock() {
+        let input = [(); 12].map(|_| GoldilocksField::rand());
+        let mut permutation = KeccakPermutation::<GoldilocksField>::new(input);
+        permutation.permute();
+        let expected_output = permutation.squeeze();
+
+        const DEGREE: u32 = 17;
+        let circuit = TestCircuit {
+            input,
+            expected_output: expected_output.to_vec().try_into().unwrap(),
+        };
+        let instance: Vec<Fr> = vec![];
+        let mock_prover = MockProver::run(DEGREE, &circuit, vec![instance.clone()]).unwrap();
+        mock_prover.assert_satisfied();
+    }
+}
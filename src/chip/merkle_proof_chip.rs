@@ -6,21 +6,21 @@ use itertools::Itertools;
 
 use crate::{
     context::RegionCtx,
-    types::assigned::{AssignedMerkleCapValues, AssignedMerkleProofValues},
+    types::assigned::{AssignedHashValues, AssignedMerkleCapValues, AssignedMerkleProofValues},
 };
 
 use super::{
     goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
-    hasher_chip::HasherChip,
+    hasher_chip::{HasherChip, HasherInstructions},
     vector_chip::VectorChip,
 };
 
-pub struct MerkleProofChip<F: PrimeField> {
+pub struct MerkleProofChip<F: PrimeField, H: HasherInstructions<F> = HasherChip<F>> {
     goldilocks_chip_config: GoldilocksChipConfig<F>,
-    _marker: PhantomData<F>,
+    _marker: PhantomData<(F, H)>,
 }
 
-impl<F: PrimeField> MerkleProofChip<F> {
+impl<F: PrimeField, H: HasherInstructions<F>> MerkleProofChip<F, H> {
     pub fn new(goldilocks_chip_config: &GoldilocksChipConfig<F>) -> Self {
         Self {
             goldilocks_chip_config: goldilocks_chip_config.clone(),
@@ -32,8 +32,8 @@ impl<F: PrimeField> MerkleProofChip<F> {
         GoldilocksChip::new(&self.goldilocks_chip_config)
     }
 
-    fn hasher(&self, ctx: &mut RegionCtx<'_, F>) -> Result<HasherChip<F>, Error> {
-        HasherChip::new(ctx, &self.goldilocks_chip_config)
+    fn hasher(&self, ctx: &mut RegionCtx<'_, F>) -> Result<H, Error> {
+        H::new(ctx, &self.goldilocks_chip_config)
     }
 
     pub fn verify_merkle_proof_to_cap_with_cap_index(
@@ -48,41 +48,235 @@ impl<F: PrimeField> MerkleProofChip<F> {
         let mut hasher = self.hasher(ctx)?;
         let goldilocks_chip = self.goldilocks_chip();
 
-        let mut state;
-        if leaf_data.len() <= 4 {
-            state = leaf_data.clone();
-        } else {
-            state = hasher.hash(ctx, leaf_data.clone(), 4)?;
+        // Salted leaves of zero-knowledge proofs are always long enough to be hashed, salt included.
+        let mut state = hasher.hash_or_noop(ctx, leaf_data)?;
+
+        for (level, (bit, sibling)) in leaf_index_bits
+            .iter()
+            .zip(proof.siblings.iter())
+            .enumerate()
+        {
+            state = ctx.namespaced(
+                || format!("Merkle level {level}"),
+                |ctx| {
+                    let mut left = vec![];
+                    let mut right = vec![];
+                    for i in 0..4 {
+                        left.push(goldilocks_chip.select(
+                            ctx,
+                            &sibling.elements[i],
+                            &state.elements[i],
+                            bit,
+                        )?);
+                    }
+                    for i in 0..4 {
+                        right.push(goldilocks_chip.select(
+                            ctx,
+                            &state.elements[i],
+                            &sibling.elements[i],
+                            bit,
+                        )?);
+                    }
+                    hasher.two_to_one(
+                        ctx,
+                        &AssignedHashValues {
+                            elements: left.try_into().unwrap(),
+                        },
+                        &AssignedHashValues {
+                            elements: right.try_into().unwrap(),
+                        },
+                    )
+                },
+            )?;
         }
 
-        for (bit, sibling) in leaf_index_bits.iter().zip(proof.siblings.iter()) {
-            let mut hasher = self.hasher(ctx)?;
-            let mut inputs = vec![];
-            for i in 0..4 {
-                let left = goldilocks_chip.select(ctx, &sibling.elements[i], &state[i], bit)?;
-                inputs.push(left);
-            }
-
-            for i in 0..4 {
-                let right = goldilocks_chip.select(ctx, &state[i], &sibling.elements[i], bit)?;
-                inputs.push(right);
-            }
-            state = hasher.permute(ctx, inputs, 4)?;
+        ctx.check(
+            || "Merkle cap mismatch",
+            |ctx| {
+                for i in 0..4 {
+                    let vector_chip = VectorChip::new(
+                        &self.goldilocks_chip_config,
+                        merkle_cap
+                            .0
+                            .iter()
+                            .map(|hash| hash.elements[i].clone())
+                            .collect_vec(),
+                    );
+                    let cap_i = vector_chip.access(ctx, &cap_index)?;
+                    goldilocks_chip.assert_equal(ctx, &cap_i, &state.elements[i])?;
+                }
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use plonky2::{
+        field::{
+            goldilocks_field::GoldilocksField,
+            types::{Field, Sample},
+        },
+        hash::{keccak::KeccakHash, merkle_tree::MerkleTree, poseidon::PoseidonHash},
+        plonk::config::Hasher,
+    };
+
+    use super::MerkleProofChip;
+    use crate::{
+        bn254_poseidon::plonky2_config::{Bn254Poseidon2Hash, Bn254PoseidonHash},
+        chip::{
+            goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+            hasher_chip::{HasherChip, HasherInstructions},
+            keccak_hasher_chip::KeccakHasherChip,
+            native_chip::utils::goldilocks_to_fe,
+            poseidon2_hasher_chip::Poseidon2HasherChip,
+            public_inputs_hasher_chip::PublicInputsHasherChip,
+        },
+        context::RegionCtx,
+        types::{assigned::SALT_SIZE, proof::MerkleProofValues, MerkleCapValues},
+    };
+
+    #[derive(Clone)]
+    struct TestCircuit<H> {
+        leaf: Vec<GoldilocksField>,
+        leaf_index: usize,
+        tree_height: usize,
+        cap_height: usize,
+        proof: MerkleProofValues<Fr>,
+        cap: MerkleCapValues<Fr>,
+        _marker: PhantomData<H>,
+    }
+
+    impl<H: HasherInstructions<Fr> + Clone> Circuit<Fr> for TestCircuit<H> {
+        type Config = GoldilocksChipConfig<Fr>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
         }
 
-        for i in 0..4 {
-            let vector_chip = VectorChip::new(
-                &self.goldilocks_chip_config,
-                merkle_cap
-                    .0
-                    .iter()
-                    .map(|hash| hash.elements[i].clone())
-                    .collect_vec(),
-            );
-            let cap_i = vector_chip.access(ctx, &cap_index)?;
-            goldilocks_chip.assert_equal(ctx, &cap_i, &state[i])?;
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            H::configure(meta)
         }
 
-        Ok(())
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let goldilocks_chip = GoldilocksChip::new(&config);
+            goldilocks_chip.load_table(&mut layouter)?;
+            layouter.assign_region(
+                || "merkle proof chip",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+                    let leaf = self
+                        .leaf
+                        .iter()
+                        .map(|x| {
+                            goldilocks_chip.assign_value(ctx, Value::known(goldilocks_to_fe(*x)))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    let leaf_index = goldilocks_chip
+                        .assign_value(ctx, Value::known(Fr::from(self.leaf_index as u64)))?;
+                    let leaf_index_bits =
+                        goldilocks_chip.to_bits(ctx, &leaf_index, self.tree_height)?;
+                    let cap_index = goldilocks_chip.from_bits(
+                        ctx,
+                        &leaf_index_bits[self.tree_height - self.cap_height..].to_vec(),
+                    )?;
+                    let proof = MerkleProofValues::assign(&config, ctx, &self.proof)?;
+                    let cap = MerkleCapValues::assign(&config, ctx, &self.cap)?;
+
+                    let merkle_proof_chip = MerkleProofChip::<Fr, H>::new(&config);
+                    merkle_proof_chip.verify_merkle_proof_to_cap_with_cap_index(
+                        ctx,
+                        &leaf,
+                        &leaf_index_bits,
+                        &cap_index,
+                        &cap,
+                        &proof,
+                    )?;
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    fn test_merkle_proof_with_leaf_len<PH, H>(leaf_len: usize)
+    where
+        PH: Hasher<GoldilocksField>,
+        H: HasherInstructions<Fr> + Clone,
+    {
+        const DEGREE: u32 = 17;
+        let tree_height = 5;
+        let cap_height = 2;
+        let leaves = (0..1 << tree_height)
+            .map(|_| GoldilocksField::rand_vec(leaf_len))
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::<GoldilocksField, PH>::new(leaves.clone(), cap_height);
+        let leaf_index = 13;
+        let circuit = TestCircuit::<H> {
+            leaf: leaves[leaf_index].clone(),
+            leaf_index,
+            tree_height,
+            cap_height,
+            proof: MerkleProofValues::from(tree.prove(leaf_index)),
+            cap: MerkleCapValues::from(tree.cap.clone()),
+            _marker: PhantomData,
+        };
+        let mock_prover = MockProver::run(DEGREE, &circuit, vec![vec![]]).unwrap();
+        mock_prover.assert_satisfied();
+
+        // A leaf with a different salt must be rejected.
+        let mut leaf = leaves[leaf_index].clone();
+        leaf[leaf_len - 1] += GoldilocksField::ONE;
+        let circuit = TestCircuit { leaf, ..circuit };
+        let mock_prover = MockProver::run(DEGREE, &circuit, vec![vec![]]).unwrap();
+        assert!(mock_prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_merkle_proof_salted_leaves() {
+        // Evaluations followed by the salt, as in the blinded oracles of zero-knowledge proofs.
+        test_merkle_proof_with_leaf_len::<Bn254PoseidonHash, HasherChip<Fr>>(3 + SALT_SIZE);
+    }
+
+    #[test]
+    fn test_merkle_proof_short_leaves() {
+        test_merkle_proof_with_leaf_len::<Bn254PoseidonHash, HasherChip<Fr>>(2);
+    }
+
+    #[test]
+    fn test_merkle_proof_poseidon2() {
+        test_merkle_proof_with_leaf_len::<Bn254Poseidon2Hash, Poseidon2HasherChip<Fr>>(
+            3 + SALT_SIZE,
+        );
+        test_merkle_proof_with_leaf_len::<Bn254Poseidon2Hash, Poseidon2HasherChip<Fr>>(2);
+    }
+
+    #[test]
+    fn test_merkle_proof_goldilocks_poseidon() {
+        test_merkle_proof_with_leaf_len::<PoseidonHash, PublicInputsHasherChip<Fr>>(3 + SALT_SIZE);
+        test_merkle_proof_with_leaf_len::<PoseidonHash, PublicInputsHasherChip<Fr>>(2);
+    }
+
+    #[test]
+    fn test_merkle_proof_keccak() {
+        // Leaves of at most 3 elements fit in the 25 bytes of the hash and are not hashed.
+        test_merkle_proof_with_leaf_len::<KeccakHash<25>, KeccakHasherChip<Fr>>(3 + SALT_SIZE);
+        test_merkle_proof_with_leaf_len::<KeccakHash<25>, KeccakHasherChip<Fr>>(2);
     }
 }
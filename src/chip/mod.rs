@@ -3,10 +3,13 @@ pub mod goldilocks_chip;
 pub mod goldilocks_extension_algebra_chip;
 pub mod goldilocks_extension_chip;
 pub mod hasher_chip;
+pub mod keccak_hasher_chip;
 pub mod merkle_proof_chip;
 pub mod native_chip;
 pub mod plonk;
+pub mod poseidon2_hasher_chip;
 pub mod poseidon_spec;
 pub mod public_inputs_hasher_chip;
+pub mod stark;
 pub mod transcript_chip;
 pub mod vector_chip;
@@ -5,10 +5,15 @@ use halo2_proofs::{
 };
 use plonky2::hash::poseidon::SPONGE_WIDTH;
 
-use crate::{bn254_poseidon::constants::T_BN254_POSEIDON, context::RegionCtx};
+use crate::{
+    bn254_poseidon::{constants::T_BN254_POSEIDON, poseidon2_constants::T_BN254_POSEIDON2},
+    context::RegionCtx,
+};
 
 use super::{
     arithmetic_chip::{ArithmeticChip, ArithmeticChipConfig},
+    keccak_chip::{KeccakChip, KeccakChipConfig},
+    poseidon2_bn254_chip::{Poseidon2Bn254Chip, Poseidon2Bn254ChipConfig},
     poseidon_bn254_chip::{PoseidonBn254Chip, PoseidonBn254ChipConfig},
 };
 
@@ -16,6 +21,10 @@ use super::{
 pub struct AllChipConfig<F: PrimeField> {
     pub arithmetic_config: ArithmeticChipConfig<F>,
     pub poseidon_config: PoseidonBn254ChipConfig<F>,
+    /// Only configured for circuits verifying Keccak-hashed proofs, as its table is large.
+    pub keccak_config: Option<KeccakChipConfig<F>>,
+    /// Only configured for circuits verifying `Bn254Poseidon2GoldilocksConfig` proofs.
+    pub poseidon2_config: Option<Poseidon2Bn254ChipConfig<F>>,
 }
 
 impl<F: PrimeField> AllChipConfig<F> {
@@ -25,6 +34,24 @@ impl<F: PrimeField> AllChipConfig<F> {
         Self {
             arithmetic_config,
             poseidon_config,
+            keccak_config: None,
+            poseidon2_config: None,
+        }
+    }
+
+    pub fn configure_with_keccak(meta: &mut ConstraintSystem<F>) -> Self {
+        let keccak_config = KeccakChipConfig::configure(meta);
+        Self {
+            keccak_config: Some(keccak_config),
+            ..Self::configure(meta)
+        }
+    }
+
+    pub fn configure_with_poseidon2(meta: &mut ConstraintSystem<F>) -> Self {
+        let poseidon2_config = Poseidon2Bn254ChipConfig::configure(meta);
+        Self {
+            poseidon2_config: Some(poseidon2_config),
+            ..Self::configure(meta)
         }
     }
 }
@@ -49,14 +76,70 @@ impl<F: PrimeField> AllChip<F> {
         PoseidonBn254Chip::new(&self.config.poseidon_config)
     }
 
+    pub fn poseidon2_chip(&self) -> Poseidon2Bn254Chip<F> {
+        let poseidon2_config = self
+            .config
+            .poseidon2_config
+            .as_ref()
+            .expect("poseidon2 chip is not configured");
+        Poseidon2Bn254Chip::new(poseidon2_config)
+    }
+
+    pub fn keccak_chip(&self) -> KeccakChip<F> {
+        let keccak_config = self
+            .config
+            .keccak_config
+            .as_ref()
+            .expect("keccak chip is not configured");
+        KeccakChip::new(keccak_config, self.arithmetic_chip())
+    }
+
     pub fn permute(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         state: [AssignedCell<F, F>; SPONGE_WIDTH],
     ) -> Result<[AssignedCell<F, F>; SPONGE_WIDTH], halo2_proofs::plonk::Error> {
-        let arithmetic_chip = self.arithmetic_chip();
         let poseidon_chip = self.poseidon_chip();
-        let zero = arithmetic_chip.assign_constant(ctx, F::ZERO)?;
+        self.permute_packed(ctx, state, T_BN254_POSEIDON, |ctx, encoded_state| {
+            let output_state =
+                poseidon_chip.apply_permute(ctx, encoded_state.try_into().unwrap())?;
+            Ok(output_state.to_vec())
+        })
+    }
+
+    /// The Poseidon2 counterpart of `permute`, for `Bn254Poseidon2GoldilocksConfig`.
+    pub fn permute_poseidon2(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedCell<F, F>; SPONGE_WIDTH],
+    ) -> Result<[AssignedCell<F, F>; SPONGE_WIDTH], halo2_proofs::plonk::Error> {
+        let poseidon2_chip = self.poseidon2_chip();
+        self.permute_packed(ctx, state, T_BN254_POSEIDON2, |ctx, encoded_state| {
+            let output_state =
+                poseidon2_chip.apply_permute(ctx, encoded_state.try_into().unwrap())?;
+            Ok(output_state.to_vec())
+        })
+    }
+
+    // Packs the state three elements at a time, zero-padded to `width`, applies `permutation` and
+    // unpacks the first four outputs. The packing and the permutation use different columns, so
+    // they are assigned side by side.
+    fn permute_packed(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedCell<F, F>; SPONGE_WIDTH],
+        width: usize,
+        permutation: impl FnOnce(
+            &mut RegionCtx<'_, F>,
+            Vec<AssignedCell<F, F>>,
+        ) -> Result<Vec<AssignedCell<F, F>>, Error>,
+    ) -> Result<[AssignedCell<F, F>; SPONGE_WIDTH], halo2_proofs::plonk::Error> {
+        let arithmetic_chip = self.arithmetic_chip();
+        let zero = if width > SPONGE_WIDTH / 3 {
+            Some(arithmetic_chip.assign_constant(ctx, F::ZERO)?)
+        } else {
+            None
+        };
         let offset_start = ctx.offset();
 
         // compose input
@@ -67,12 +150,14 @@ impl<F: PrimeField> AllChip<F> {
                 Ok(composed)
             })
             .collect::<Result<Vec<_>, Error>>()?;
-        encoded_state.resize(T_BN254_POSEIDON, zero.clone());
+        if let Some(zero) = zero {
+            encoded_state.resize(width, zero);
+        }
         let offset_end_compose = ctx.offset();
 
         // aplly permutation
         ctx.set_offset(offset_start);
-        let output_state = poseidon_chip.apply_permute(ctx, encoded_state.try_into().unwrap())?;
+        let output_state = permutation(ctx, encoded_state)?;
         let offset_end_permute = ctx.offset();
 
         // decompose output
@@ -92,7 +177,11 @@ impl<F: PrimeField> AllChip<F> {
         &self,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), halo2_proofs::plonk::Error> {
-        self.arithmetic_chip().load_table(layouter)
+        self.arithmetic_chip().load_table(layouter)?;
+        if self.config.keccak_config.is_some() {
+            self.keccak_chip().load_table(layouter)?;
+        }
+        Ok(())
     }
 }
 
@@ -33,10 +33,11 @@ pub struct ArithmeticChipConfig<F: PrimeField> {
     pub table: TableColumn,
     pub instance: Column<Instance>,
     pub constant: Column<Fixed>,
-    pub s_limb: Selector,  // limb decomposition of q and r
-    pub s_range: Selector, // contraint q = p - r
-    pub s_base: Selector,  // contraint a*b + c == q*p + r
-    pub s_ext: Selector,   // contraint a*b + c == q*p + r on extension field
+    pub s_limb: Selector,        // limb decomposition of q and r
+    pub s_range: Selector,       // contraint q = p - r
+    pub s_base: Selector,        // contraint a*b + c == q*p + r
+    pub s_ext: Selector,         // contraint a*b + c == q*p + r on quadratic extension field
+    pub s_ext_quartic: Selector, // contraint a*b + c == q*p + r on quartic extension field
     _marker: PhantomData<F>,
 }
 
@@ -55,6 +56,7 @@ impl<F: PrimeField> ArithmeticChipConfig<F> {
         let s_range = meta.selector();
         let s_base = meta.selector();
         let s_ext = meta.selector();
+        let s_ext_quartic = meta.selector();
 
         let table = meta.lookup_table_column();
         let instance = meta.instance_column();
@@ -106,30 +108,13 @@ impl<F: PrimeField> ArithmeticChipConfig<F> {
             vec![s_base.clone() * (a * b + c - p * q.clone() - r.clone())]
         });
 
-        meta.create_gate("extension field contraint", |meta| {
-            let s_ext = meta.query_selector(s_ext);
-            let a_x = meta.query_advice(a, Rotation::cur());
-            let a_y = meta.query_advice(a, Rotation::next());
-            let b_x = meta.query_advice(b, Rotation::cur());
-            let b_y = meta.query_advice(b, Rotation::next());
-            let c_x = meta.query_advice(c, Rotation::cur());
-            let c_y = meta.query_advice(c, Rotation::next());
-            let q_x = meta.query_advice(q, Rotation::cur());
-            let q_y = meta.query_advice(q, Rotation::next());
-            let r_x = meta.query_advice(r, Rotation::cur());
-            let r_y = meta.query_advice(r, Rotation::next());
-            let p = Expression::Constant(F::from(GOLDILOCKS_MODULUS));
-            let left_x = a_x.clone() * b_x.clone()
-                + Expression::Constant(F::from(7)) * a_y.clone() * b_y.clone()
-                + c_x.clone();
-            let left_y = a_x.clone() * b_y.clone() + a_y.clone() * b_x.clone() + c_y.clone();
-            let right_x = p.clone() * q_x.clone() + r_x.clone();
-            let right_y = p.clone() * q_y.clone() + r_y.clone();
-            vec![
-                s_ext.clone() * (left_x - right_x),
-                s_ext.clone() * (left_y - right_y),
-            ]
-        });
+        create_ext_gate::<F, 2>(meta, "extension field contraint", s_ext, [a, b, c, q, r]);
+        create_ext_gate::<F, 4>(
+            meta,
+            "quartic extension field contraint",
+            s_ext_quartic,
+            [a, b, c, q, r],
+        );
 
         q_limbs.iter().for_each(|limb| {
             meta.lookup("q_limbs range check", |meta| {
@@ -158,9 +143,57 @@ impl<F: PrimeField> ArithmeticChipConfig<F> {
             s_range,
             s_base,
             s_ext,
+            s_ext_quartic,
             _marker: PhantomData,
         }
     }
+
+    /// The selector of the `a*b + c == q*p + r` gate on the degree `D` extension field. There is
+    /// none for degrees rejected by `check_extension_degree`.
+    fn s_ext<const D: usize>(&self) -> Result<Selector, Error> {
+        match D {
+            2 => Ok(self.s_ext),
+            4 => Ok(self.s_ext_quartic),
+            _ => Err(Error::Synthesis),
+        }
+    }
+}
+
+/// Creates the gate constraining `a*b + c == q*p + r` on `F_p[X]/(X^D - W)` with `W = 7`.
+/// The `i`th coefficients of the operands are placed on the `i`th row from the selector.
+fn create_ext_gate<F: PrimeField, const D: usize>(
+    meta: &mut ConstraintSystem<F>,
+    name: &'static str,
+    selector: Selector,
+    [a, b, c, q, r]: [Column<Advice>; 5],
+) {
+    meta.create_gate(name, |meta| {
+        let s_ext = meta.query_selector(selector);
+        let mut query = |column: Column<Advice>| {
+            (0..D)
+                .map(|i| meta.query_advice(column, Rotation(i as i32)))
+                .collect::<Vec<_>>()
+        };
+        let (a, b, c, q, r) = (query(a), query(b), query(c), query(q), query(r));
+        let p = Expression::Constant(F::from(GOLDILOCKS_MODULUS));
+        let w = Expression::Constant(F::from(7));
+        (0..D)
+            .map(|k| {
+                let mut left = c[k].clone();
+                for i in 0..D {
+                    let j = (D + k - i) % D;
+                    let term = a[i].clone() * b[j].clone();
+                    left = if i + j < D {
+                        left + term
+                    } else {
+                        left + w.clone() * term
+                    };
+                }
+                let right = p.clone() * q[k].clone() + r[k].clone();
+                s_ext.clone() * (left - right)
+            })
+            .collect::<Vec<_>>()
+    });
 }
 
 pub struct AssignedArithmetic<F: PrimeField> {
@@ -170,11 +203,11 @@ pub struct AssignedArithmetic<F: PrimeField> {
     pub r: AssignedCell<F, F>,
 }
 
-pub struct AssignedArithmeticExt<F: PrimeField> {
-    pub a: [AssignedCell<F, F>; 2],
-    pub b: [AssignedCell<F, F>; 2],
-    pub c: [AssignedCell<F, F>; 2],
-    pub r: [AssignedCell<F, F>; 2],
+pub struct AssignedArithmeticExt<F: PrimeField, const D: usize> {
+    pub a: [AssignedCell<F, F>; D],
+    pub b: [AssignedCell<F, F>; D],
+    pub c: [AssignedCell<F, F>; D],
+    pub r: [AssignedCell<F, F>; D],
 }
 
 #[derive(Clone)]
@@ -184,9 +217,9 @@ pub enum Term<'a, F: PrimeField> {
 }
 
 #[derive(Clone)]
-pub enum TermExt<'a, F: PrimeField> {
-    Assigned(&'a [AssignedCell<F, F>; 2]),
-    Unassigned([Value<F>; 2]),
+pub enum TermExt<'a, F: PrimeField, const D: usize> {
+    Assigned(&'a [AssignedCell<F, F>; D]),
+    Unassigned([Value<F>; D]),
 }
 
 #[derive(Clone, Debug)]
@@ -220,13 +253,13 @@ impl<F: PrimeField> ArithmeticChip<F> {
         Ok(())
     }
 
-    pub fn assert_equal_ext(
+    pub fn assert_equal_ext<const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: &[AssignedCell<F, F>; 2],
-        b: &[AssignedCell<F, F>; 2],
+        a: &[AssignedCell<F, F>; D],
+        b: &[AssignedCell<F, F>; D],
     ) -> Result<(), Error> {
-        for i in 0..2 {
+        for i in 0..D {
             ctx.constrain_equal(a[i].cell(), b[i].cell())?;
         }
         Ok(())
@@ -307,44 +340,47 @@ impl<F: PrimeField> ArithmeticChip<F> {
         })
     }
 
-    fn assign_ext(
+    fn assign_ext<const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: [Value<F>; 2],
-        b: [Value<F>; 2],
-        c: [Value<F>; 2],
-    ) -> Result<AssignedArithmeticExt<F>, Error> {
-        ctx.enable(self.config.s_ext)?;
-        ctx.enable(self.config.s_limb)?;
-        let tmp_x = a[0] * b[0] + Value::known(F::from(7)) * a[1] * b[1] + c[0];
-        let tmp_y = a[0] * b[1] + a[1] * b[0] + c[1];
-        let (q_x, r_x) = tmp_x
-            .map(|t| {
-                let (q, r) = fe_to_big(t).div_rem(&BigUint::from(GOLDILOCKS_MODULUS));
-                (big_to_fe::<F>(q), big_to_fe::<F>(r))
-            })
-            .unzip();
-        let (q_y, r_y) = tmp_y
-            .map(|t| {
-                let (q, r) = fe_to_big(t).div_rem(&BigUint::from(GOLDILOCKS_MODULUS));
-                (big_to_fe::<F>(q), big_to_fe::<F>(r))
-            })
-            .unzip();
-        let (_q_x_assigned, r_x_assigned) = assign_q_and_r(&self.config, ctx, q_x, r_x)?;
-        let a_x_assigned = ctx.assign_advice(|| "a", self.config.a, a[0])?;
-        let b_x_assigned = ctx.assign_advice(|| "b", self.config.b, b[0])?;
-        let c_x_assigned = ctx.assign_advice(|| "c", self.config.c, c[0])?;
-        ctx.next();
-        let (_q_y_assigned, r_y_assigned) = assign_q_and_r(&self.config, ctx, q_y, r_y)?;
-        let a_y_assigned = ctx.assign_advice(|| "a", self.config.a, a[1])?;
-        let b_y_assigned = ctx.assign_advice(|| "b", self.config.b, b[1])?;
-        let c_y_assigned = ctx.assign_advice(|| "c", self.config.c, c[1])?;
-        ctx.next();
+        a: [Value<F>; D],
+        b: [Value<F>; D],
+        c: [Value<F>; D],
+    ) -> Result<AssignedArithmeticExt<F, D>, Error> {
+        ctx.enable(self.config.s_ext::<D>()?)?;
+        let mut a_assigned = vec![];
+        let mut b_assigned = vec![];
+        let mut c_assigned = vec![];
+        let mut r_assigned = vec![];
+        for k in 0..D {
+            let mut tmp = c[k];
+            for i in 0..D {
+                let j = (D + k - i) % D;
+                tmp = if i + j < D {
+                    tmp + a[i] * b[j]
+                } else {
+                    tmp + Value::known(F::from(7)) * a[i] * b[j]
+                };
+            }
+            let (q, r) = tmp
+                .map(|t| {
+                    let (q, r) = fe_to_big(t).div_rem(&BigUint::from(GOLDILOCKS_MODULUS));
+                    (big_to_fe::<F>(q), big_to_fe::<F>(r))
+                })
+                .unzip();
+            ctx.enable(self.config.s_limb)?;
+            let (_q_assigned, r) = assign_q_and_r(&self.config, ctx, q, r)?;
+            a_assigned.push(ctx.assign_advice(|| "a", self.config.a, a[k])?);
+            b_assigned.push(ctx.assign_advice(|| "b", self.config.b, b[k])?);
+            c_assigned.push(ctx.assign_advice(|| "c", self.config.c, c[k])?);
+            r_assigned.push(r);
+            ctx.next();
+        }
         Ok(AssignedArithmeticExt {
-            a: [a_x_assigned, a_y_assigned],
-            b: [b_x_assigned, b_y_assigned],
-            c: [c_x_assigned, c_y_assigned],
-            r: [r_x_assigned, r_y_assigned],
+            a: a_assigned.try_into().unwrap(),
+            b: b_assigned.try_into().unwrap(),
+            c: c_assigned.try_into().unwrap(),
+            r: r_assigned.try_into().unwrap(),
         })
     }
 
@@ -378,19 +414,19 @@ impl<F: PrimeField> ArithmeticChip<F> {
         Ok(assigned)
     }
 
-    pub fn apply_ext(
+    pub fn apply_ext<const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        a: TermExt<F>,
-        b: TermExt<F>,
-        c: TermExt<F>,
-    ) -> Result<AssignedArithmeticExt<F>, Error> {
+        a: TermExt<F, D>,
+        b: TermExt<F, D>,
+        c: TermExt<F, D>,
+    ) -> Result<AssignedArithmeticExt<F, D>, Error> {
         let inputs = vec![a, b, c];
         let unassigned = inputs
             .iter()
             .map(|x| {
                 let x = match x {
-                    TermExt::Assigned(x) => [x[0].value().cloned(), x[1].value().cloned()],
+                    TermExt::Assigned(x) => std::array::from_fn(|i| x[i].value().cloned()),
                     TermExt::Unassigned(x) => x.clone(),
                 };
                 x
@@ -411,7 +447,7 @@ impl<F: PrimeField> ArithmeticChip<F> {
     }
 
     // returns a*b + c without taking modulo
-    fn mul_add_no_mod(
+    pub fn mul_add_no_mod(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         a: &AssignedCell<F, F>,
@@ -593,6 +629,14 @@ mod tests {
                         TermExt::Assigned(&c),
                     )?;
 
+                    let c = [c_x.clone(), c_y.clone(), c_x.clone(), c_y.clone()];
+                    let _d = chip.apply_ext(
+                        ctx,
+                        TermExt::Assigned(&c),
+                        TermExt::Assigned(&c),
+                        TermExt::Assigned(&c),
+                    )?;
+
                     Ok(())
                 },
             )?;
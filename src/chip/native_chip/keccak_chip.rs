@@ -0,0 +1,609 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::ff::PrimeField,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+use halo2wrong_maingate::fe_to_big;
+
+use crate::context::RegionCtx;
+
+use super::arithmetic_chip::ArithmeticChip;
+
+/// A 64-bit lane of the Keccak-f[1600] state is split into 16 nibbles, in little-endian order.
+pub const NUM_NIBBLES: usize = 16;
+
+/// Keccak-256 absorbs 136 bytes per permutation, that is 17 lanes.
+const RATE_LANES: usize = 17;
+
+const NUM_ROUNDS: usize = 24;
+
+/// Rotation offsets of the rho step, indexed by `x + 5 * y`.
+const ROTATION_OFFSETS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The nibble-wise operations of the lookup table. Each row of the chip applies one of them to
+/// the 16 nibbles of its lanes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeccakOp {
+    /// `x ^ y`
+    Xor,
+    /// `x ^ (!y & z)`, the chi step
+    Chi,
+    /// `(x << s) | (y >> (4 - s))` on 4 bits, where `y` is the next less significant nibble
+    Rot(u32),
+}
+
+impl KeccakOp {
+    const ALL: [KeccakOp; 5] = [
+        KeccakOp::Xor,
+        KeccakOp::Chi,
+        KeccakOp::Rot(1),
+        KeccakOp::Rot(2),
+        KeccakOp::Rot(3),
+    ];
+
+    fn tag(&self) -> u64 {
+        match self {
+            KeccakOp::Xor => 0,
+            KeccakOp::Chi => 1,
+            KeccakOp::Rot(s) => 1 + *s as u64,
+        }
+    }
+
+    fn apply(&self, x: u64, y: u64, z: u64) -> u64 {
+        match self {
+            KeccakOp::Xor => x ^ y,
+            KeccakOp::Chi => x ^ (!y & z & 0xF),
+            KeccakOp::Rot(s) => ((x << s) & 0xF) | (y >> (4 - s)),
+        }
+    }
+}
+
+// out = op(x, y, z) for each nibble, where op is given by the tag of the row
+#[derive(Clone, Debug)]
+pub struct KeccakChipConfig<F: PrimeField> {
+    pub x: [Column<Advice>; NUM_NIBBLES],
+    pub y: [Column<Advice>; NUM_NIBBLES],
+    pub z: [Column<Advice>; NUM_NIBBLES],
+    pub out: [Column<Advice>; NUM_NIBBLES],
+    pub value: Column<Advice>,
+    pub tag: Column<Fixed>,
+    pub s_compose: Selector, // contraint value == sum of x[i] * 16^i
+    pub table: [TableColumn; 5],
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakChipConfig<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let x = [(); NUM_NIBBLES].map(|_| meta.advice_column());
+        let y = [(); NUM_NIBBLES].map(|_| meta.advice_column());
+        let z = [(); NUM_NIBBLES].map(|_| meta.advice_column());
+        let out = [(); NUM_NIBBLES].map(|_| meta.advice_column());
+        let value = meta.advice_column();
+        let tag = meta.fixed_column();
+        let s_compose = meta.selector();
+        let table = [(); 5].map(|_| meta.lookup_table_column());
+
+        x.iter()
+            .chain(y.iter())
+            .chain(z.iter())
+            .chain(out.iter())
+            .chain(std::iter::once(&value))
+            .for_each(|column| meta.enable_equality(*column));
+
+        meta.create_gate("nibble composition", |meta| {
+            let s_compose = meta.query_selector(s_compose);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc = (0..NUM_NIBBLES).fold(Expression::Constant(F::ZERO), |acc, i| {
+                acc + meta.query_advice(x[i], Rotation::cur())
+                    * Expression::Constant(F::from_u128(1u128 << (4 * i)))
+            });
+            vec![s_compose * (value - acc)]
+        });
+
+        // Unused rows are all zero, which is a valid xor.
+        for i in 0..NUM_NIBBLES {
+            meta.lookup("keccak nibble op", |meta| {
+                let tag = meta.query_fixed(tag, Rotation::cur());
+                let x = meta.query_advice(x[i], Rotation::cur());
+                let y = meta.query_advice(y[i], Rotation::cur());
+                let z = meta.query_advice(z[i], Rotation::cur());
+                let out = meta.query_advice(out[i], Rotation::cur());
+                vec![
+                    (tag, table[0]),
+                    (x, table[1]),
+                    (y, table[2]),
+                    (z, table[3]),
+                    (out, table[4]),
+                ]
+            });
+        }
+
+        KeccakChipConfig {
+            x,
+            y,
+            z,
+            out,
+            value,
+            tag,
+            s_compose,
+            table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+pub type AssignedLane<F> = [AssignedCell<F, F>; NUM_NIBBLES];
+
+fn to_u64<F: PrimeField>(x: &F) -> u64 {
+    let mut digits = fe_to_big(*x).to_u64_digits();
+    digits.resize(1, 0);
+    digits[0]
+}
+
+fn rotate_nibbles<T: Clone>(lane: &[T; NUM_NIBBLES], k: usize) -> [T; NUM_NIBBLES] {
+    std::array::from_fn(|j| lane[(j + NUM_NIBBLES - k % NUM_NIBBLES) % NUM_NIBBLES].clone())
+}
+
+/// Constrains Keccak-f[1600] and Keccak-256 on lanes of nibbles, with one row of lookups per
+/// lane operation.
+#[derive(Clone, Debug)]
+pub struct KeccakChip<F: PrimeField> {
+    config: KeccakChipConfig<F>,
+    arithmetic_chip: ArithmeticChip<F>,
+}
+
+impl<F: PrimeField> KeccakChip<F> {
+    pub fn new(config: &KeccakChipConfig<F>, arithmetic_chip: ArithmeticChip<F>) -> Self {
+        KeccakChip {
+            config: config.clone(),
+            arithmetic_chip,
+        }
+    }
+
+    fn apply(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        op: KeccakOp,
+        x: &AssignedLane<F>,
+        y: &AssignedLane<F>,
+        z: Option<&AssignedLane<F>>,
+    ) -> Result<AssignedLane<F>, Error> {
+        ctx.assign_fixed(|| "tag", self.config.tag, F::from(op.tag()))?;
+        let mut out = vec![];
+        for i in 0..NUM_NIBBLES {
+            let x_assigned = ctx.assign_advice(|| "x", self.config.x[i], x[i].value().cloned())?;
+            let y_assigned = ctx.assign_advice(|| "y", self.config.y[i], y[i].value().cloned())?;
+            ctx.constrain_equal(x[i].cell(), x_assigned.cell())?;
+            ctx.constrain_equal(y[i].cell(), y_assigned.cell())?;
+            // z is only read by chi, the table forces it to zero for the other ops.
+            let z_value = match z {
+                Some(z) => {
+                    let z_assigned =
+                        ctx.assign_advice(|| "z", self.config.z[i], z[i].value().cloned())?;
+                    ctx.constrain_equal(z[i].cell(), z_assigned.cell())?;
+                    z[i].value().cloned()
+                }
+                None => Value::known(F::ZERO),
+            };
+            let out_value = x[i]
+                .value()
+                .zip(y[i].value())
+                .zip(z_value)
+                .map(|((x, y), z)| F::from(op.apply(to_u64(x), to_u64(y), to_u64(&z))));
+            out.push(ctx.assign_advice(|| "out", self.config.out[i], out_value)?);
+        }
+        ctx.next();
+        Ok(out.try_into().unwrap())
+    }
+
+    pub fn xor(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        x: &AssignedLane<F>,
+        y: &AssignedLane<F>,
+    ) -> Result<AssignedLane<F>, Error> {
+        self.apply(ctx, KeccakOp::Xor, x, y, None)
+    }
+
+    // x ^ (!y & z)
+    pub fn chi(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        x: &AssignedLane<F>,
+        y: &AssignedLane<F>,
+        z: &AssignedLane<F>,
+    ) -> Result<AssignedLane<F>, Error> {
+        self.apply(ctx, KeccakOp::Chi, x, y, Some(z))
+    }
+
+    /// Rotates the lane left by `r` bits. Whole nibbles are rotated by permuting the cells, and
+    /// the remaining bits with a single row.
+    pub fn rotate_left(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lane: &AssignedLane<F>,
+        r: u32,
+    ) -> Result<AssignedLane<F>, Error> {
+        let (k, s) = ((r / 4) as usize, r % 4);
+        let rotated = rotate_nibbles(lane, k);
+        if s == 0 {
+            return Ok(rotated);
+        }
+        let carry = rotate_nibbles(lane, k + 1);
+        self.apply(ctx, KeccakOp::Rot(s), &rotated, &carry, None)
+    }
+
+    pub fn constant_lane(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        constant: u64,
+    ) -> Result<AssignedLane<F>, Error> {
+        let nibbles = (0..NUM_NIBBLES)
+            .map(|i| {
+                self.arithmetic_chip
+                    .assign_constant(ctx, F::from((constant >> (4 * i)) & 0xF))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(nibbles.try_into().unwrap())
+    }
+
+    // assign value = sum of x[i] * 16^i, where the nibbles from `num_nibbles` on are zero
+    fn assign_composition(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: Value<F>,
+        nibbles: Vec<Value<F>>,
+        num_nibbles: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedLane<F>), Error> {
+        assert!(num_nibbles <= NUM_NIBBLES);
+        let zero = self.arithmetic_chip.assign_constant(ctx, F::ZERO)?;
+        ctx.enable(self.config.s_compose)?;
+        // Lookups of this row check the nibbles as `x ^ 0 = x`.
+        ctx.assign_fixed(|| "tag", self.config.tag, F::from(KeccakOp::Xor.tag()))?;
+        let value_assigned = ctx.assign_advice(|| "value", self.config.value, value)?;
+        let mut assigned = vec![];
+        for (i, nibble) in nibbles.into_iter().enumerate() {
+            let x_assigned = ctx.assign_advice(|| "x", self.config.x[i], nibble)?;
+            ctx.assign_advice(|| "out", self.config.out[i], nibble)?;
+            if i >= num_nibbles {
+                ctx.constrain_equal(x_assigned.cell(), zero.cell())?;
+            }
+            assigned.push(x_assigned);
+        }
+        ctx.next();
+        Ok((value_assigned, assigned.try_into().unwrap()))
+    }
+
+    /// Decomposes a value of less than `4 * num_nibbles` bits into nibbles.
+    pub fn decompose(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        value: &AssignedCell<F, F>,
+        num_nibbles: usize,
+    ) -> Result<AssignedLane<F>, Error> {
+        let nibbles = value
+            .value()
+            .map(|x| {
+                let x = to_u64(x);
+                (0..NUM_NIBBLES)
+                    .map(|i| F::from((x >> (4 * i)) & 0xF))
+                    .collect::<Vec<_>>()
+            })
+            .transpose_vec(NUM_NIBBLES);
+        let (value_assigned, nibbles) =
+            self.assign_composition(ctx, value.value().cloned(), nibbles, num_nibbles)?;
+        ctx.constrain_equal(value.cell(), value_assigned.cell())?;
+        Ok(nibbles)
+    }
+
+    /// Composes up to 16 nibbles, in little-endian order, into a value.
+    pub fn compose(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        nibbles: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let value = nibbles
+            .iter()
+            .rev()
+            .fold(Value::known(F::ZERO), |acc, nibble| {
+                acc * Value::known(F::from(16)) + nibble.value().cloned()
+            });
+        let mut values = nibbles.iter().map(|x| x.value().cloned()).collect::<Vec<_>>();
+        values.resize(NUM_NIBBLES, Value::known(F::ZERO));
+        let (value_assigned, assigned) =
+            self.assign_composition(ctx, value, values, nibbles.len())?;
+        for (nibble, assigned) in nibbles.iter().zip(assigned.iter()) {
+            ctx.constrain_equal(nibble.cell(), assigned.cell())?;
+        }
+        Ok(value_assigned)
+    }
+
+    /// Constrains the Keccak-f[1600] permutation, where the lane `(x, y)` is at `x + 5 * y`.
+    pub fn keccak_f(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedLane<F>; 25],
+    ) -> Result<[AssignedLane<F>; 25], Error> {
+        let mut a = state;
+        for round_constant in ROUND_CONSTANTS {
+            // theta
+            let mut c = vec![];
+            for x in 0..5 {
+                let mut acc = a[x].clone();
+                for y in 1..5 {
+                    acc = self.xor(ctx, &acc, &a[x + 5 * y])?;
+                }
+                c.push(acc);
+            }
+            for x in 0..5 {
+                let rotated = self.rotate_left(ctx, &c[(x + 1) % 5], 1)?;
+                let d = self.xor(ctx, &c[(x + 4) % 5], &rotated)?;
+                for y in 0..5 {
+                    a[x + 5 * y] = self.xor(ctx, &a[x + 5 * y], &d)?;
+                }
+            }
+
+            // rho and pi
+            let mut b = a.clone();
+            for x in 0..5 {
+                for y in 0..5 {
+                    b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                        self.rotate_left(ctx, &a[x + 5 * y], ROTATION_OFFSETS[x + 5 * y])?;
+                }
+            }
+
+            // chi
+            for x in 0..5 {
+                for y in 0..5 {
+                    a[x + 5 * y] = self.chi(
+                        ctx,
+                        &b[x + 5 * y],
+                        &b[(x + 1) % 5 + 5 * y],
+                        &b[(x + 2) % 5 + 5 * y],
+                    )?;
+                }
+            }
+
+            // iota
+            let round_constant = self.constant_lane(ctx, round_constant)?;
+            a[0] = self.xor(ctx, &a[0], &round_constant)?;
+        }
+        Ok(a)
+    }
+
+    /// Constrains Keccak-256 of a message given as nibbles, two per byte in little-endian order.
+    /// The nibbles must be range checked by the caller. Returns the 64 nibbles of the digest.
+    pub fn keccak256(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        input: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(input.len() % 2 == 0, "the input must be made of whole bytes");
+        let rate_nibbles = RATE_LANES * NUM_NIBBLES;
+
+        // pad10*1 with the 0x01 domain separator of the original Keccak
+        let num_padded = (input.len() / rate_nibbles + 1) * rate_nibbles;
+        let mut padding = vec![0u8; (num_padded - input.len()) / 2];
+        padding[0] |= 0x01;
+        *padding.last_mut().unwrap() |= 0x80;
+        let mut message = input.to_vec();
+        for byte in padding {
+            message.push(self.arithmetic_chip.assign_constant(ctx, F::from(byte as u64 & 0xF))?);
+            message.push(self.arithmetic_chip.assign_constant(ctx, F::from(byte as u64 >> 4))?);
+        }
+
+        let mut state: Option<[AssignedLane<F>; 25]> = None;
+        for block in message.chunks(rate_nibbles) {
+            let lanes = block
+                .chunks(NUM_NIBBLES)
+                .map(|lane| lane.to_vec().try_into().unwrap())
+                .collect::<Vec<AssignedLane<F>>>();
+            let absorbed = match state {
+                None => {
+                    let zero = self.constant_lane(ctx, 0)?;
+                    let mut absorbed = lanes;
+                    absorbed.resize(25, zero);
+                    absorbed.try_into().unwrap()
+                }
+                Some(mut state) => {
+                    for (word, lane) in state.iter_mut().zip(lanes.iter()) {
+                        *word = self.xor(ctx, word, lane)?;
+                    }
+                    state
+                }
+            };
+            state = Some(self.keccak_f(ctx, absorbed)?);
+        }
+        Ok(state.unwrap()[0..4].iter().flatten().cloned().collect())
+    }
+
+    /// Constrains Keccak-256 of the concatenated 8-byte big-endian encodings of `words`, i.e. of
+    /// the `bytes8(word)` of each word in Solidity. This is not `abi.encodePacked(words)` for a
+    /// `uint64[]`, which pads each element to 32 bytes. The words must be less than `2^64`.
+    /// Returns the high and low 128 bits of the digest, read as a big-endian `uint256`.
+    pub fn keccak256_be_words(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        words: &[AssignedCell<F, F>],
+    ) -> Result<[AssignedCell<F, F>; 2], Error> {
+        let mut input = vec![];
+        for word in words {
+            let nibbles = self.decompose(ctx, word, NUM_NIBBLES)?;
+            // the bytes of a lane are little-endian
+            nibbles
+                .chunks(2)
+                .rev()
+                .for_each(|byte| input.extend_from_slice(byte));
+        }
+        let digest = self.keccak256(ctx, &input)?;
+        let two_to_64 = self
+            .arithmetic_chip
+            .assign_constant(ctx, F::from_u128(1 << 64))?;
+        let halves = digest
+            .chunks(32)
+            .map(|half| {
+                let nibbles = half.chunks(2).rev().flatten().cloned().collect::<Vec<_>>();
+                let low = self.compose(ctx, &nibbles[..NUM_NIBBLES])?;
+                let high = self.compose(ctx, &nibbles[NUM_NIBBLES..])?;
+                self.arithmetic_chip
+                    .mul_add_no_mod(ctx, &high, &two_to_64, &low)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(halves.try_into().unwrap())
+    }
+
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "keccak table",
+            |mut table| {
+                let mut offset = 0;
+                for op in KeccakOp::ALL {
+                    let num_z = if op == KeccakOp::Chi { 16 } else { 1 };
+                    for x in 0..16 {
+                        for y in 0..16 {
+                            for z in 0..num_z {
+                                let row = [op.tag(), x, y, z, op.apply(x, y, z)];
+                                for (column, value) in self.config.table.iter().zip(row) {
+                                    table.assign_cell(
+                                        || "keccak table",
+                                        *column,
+                                        offset,
+                                        || Value::known(F::from(value)),
+                                    )?;
+                                }
+                                offset += 1;
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use plonky2::{
+        field::{
+            goldilocks_field::GoldilocksField,
+            types::{Field, Sample},
+        },
+        hash::keccak::KeccakHash,
+        plonk::config::Hasher,
+    };
+
+    use crate::{
+        chip::{
+            goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+            native_chip::{all_chip::AllChipConfig, utils::goldilocks_to_fe},
+        },
+        context::RegionCtx,
+    };
+
+    #[derive(Clone, Default)]
+    pub struct TestCircuit {
+        input: Vec<GoldilocksField>,
+        expected_output: Vec<u8>,
+    }
+
+    impl Circuit<Fr> for TestCircuit {
+        type Config = GoldilocksChipConfig<Fr>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let all_chip = AllChipConfig::<Fr>::configure_with_keccak(meta);
+            GoldilocksChip::configure(&all_chip)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let goldilocks_chip = GoldilocksChip::new(&config);
+            goldilocks_chip.load_table(&mut layouter)?;
+            layouter.assign_region(
+                || "keccak chip",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+                    let keccak_chip = goldilocks_chip.all_chip().keccak_chip();
+                    let mut nibbles = vec![];
+                    for x in self.input.iter() {
+                        let x = goldilocks_chip.assign_value(ctx, Value::known(goldilocks_to_fe(*x)))?;
+                        nibbles.extend(keccak_chip.decompose(ctx, &x, 16)?);
+                    }
+                    let output = keccak_chip.keccak256(ctx, &nibbles)?;
+                    for (byte, expected) in output.chunks(2).zip(self.expected_output.iter()) {
+                        let byte = keccak_chip.compose(ctx, byte)?;
+                        let expected = goldilocks_chip.assign_constant(
+                            ctx,
+                            GoldilocksField::from_canonical_u64(*expected as u64),
+                        )?;
+                        goldilocks_chip.assert_equal(ctx, &byte, &expected)?;
+                    }
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_keccak_chip_mock() {
+        // 160 bytes, so that two blocks are absorbed.
+        let input = GoldilocksField::rand_vec(20);
+        let expected_output = KeccakHash::<32>::hash_no_pad(&input).0.to_vec();
+
+        const DEGREE: u32 = 17;
+        let circuit = TestCircuit {
+            input,
+            expected_output,
+        };
+        let instance: Vec<Fr> = vec![];
+        let mock_prover = MockProver::run(DEGREE, &circuit, vec![instance.clone()]).unwrap();
+        mock_prover.assert_satisfied();
+    }
+}
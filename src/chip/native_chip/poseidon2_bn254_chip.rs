@@ -0,0 +1,325 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{
+    circuit::{AssignedCell, Value},
+    halo2curves::ff::PrimeField,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+use crate::bn254_poseidon::{
+    poseidon2_constants::{
+        MAT_EXTERNAL, MAT_INTERNAL_DIAG_M_1, ROUND_CONSTANTS_BG, R_F_BN254_POSEIDON2,
+        R_P_BN254_POSEIDON2, T_BN254_POSEIDON2,
+    },
+    value::{
+        bg_to_fe, poseidon2_external_layer_value, poseidon2_full_round_value,
+        poseidon2_partial_round_value,
+    },
+};
+
+fn pow5<F: PrimeField>(x: Expression<F>) -> Expression<F> {
+    x.clone() * x.clone() * x.clone() * x.clone() * x
+}
+
+fn external_layer<F: PrimeField>(state: &[Expression<F>]) -> Vec<Expression<F>> {
+    MAT_EXTERNAL
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(state.iter())
+                .fold(Expression::Constant(F::from(0)), |acc, (m, s)| {
+                    acc + s.clone() * Expression::Constant(F::from(*m))
+                })
+        })
+        .collect()
+}
+
+fn internal_layer<F: PrimeField>(state: &[Expression<F>]) -> Vec<Expression<F>> {
+    let sum = state
+        .iter()
+        .fold(Expression::Constant(F::from(0)), |acc, s| acc + s.clone());
+    state
+        .iter()
+        .zip(MAT_INTERNAL_DIAG_M_1.iter())
+        .map(|(s, d)| s.clone() * Expression::Constant(F::from(*d)) + sum.clone())
+        .collect()
+}
+
+/// Constrains the Poseidon2 permutation of `bn254_poseidon::native`, one round per row. Unlike
+/// `PoseidonBn254Chip`, the partial rounds only add a constant to the first word, and their
+/// linear layer is the cheap internal matrix.
+#[derive(Clone, Debug)]
+pub struct Poseidon2Bn254ChipConfig<F: PrimeField> {
+    pub state: [Column<Advice>; T_BN254_POSEIDON2],
+    pub constants: [Column<Fixed>; T_BN254_POSEIDON2],
+    pub q_e: Selector,
+    pub q_f: Selector,
+    pub q_p: Selector,
+    _maker: PhantomData<F>,
+}
+
+impl<F: PrimeField> Poseidon2Bn254ChipConfig<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let state = [(); T_BN254_POSEIDON2].map(|_| meta.advice_column());
+        let constants = [(); T_BN254_POSEIDON2].map(|_| meta.fixed_column());
+        let q_e = meta.selector();
+        let q_f = meta.selector();
+        let q_p = meta.selector();
+        state.iter().for_each(|s| meta.enable_equality(*s));
+
+        meta.create_gate("external linear layer", |meta| {
+            let next_state = state
+                .iter()
+                .map(|s| meta.query_advice(*s, Rotation::next()))
+                .collect::<Vec<_>>();
+            let state = state
+                .iter()
+                .map(|s| meta.query_advice(*s, Rotation::cur()))
+                .collect::<Vec<_>>();
+            let q = meta.query_selector(q_e);
+            let after_layer = external_layer(&state);
+            next_state
+                .iter()
+                .zip(after_layer.iter())
+                .map(|(n, a)| q.clone() * (n.clone() - a.clone()))
+                .collect::<Vec<_>>()
+        });
+        meta.create_gate("poseidon2 full round", |meta| {
+            let next_state = state
+                .iter()
+                .map(|s| meta.query_advice(*s, Rotation::next()))
+                .collect::<Vec<_>>();
+            let state = state
+                .iter()
+                .map(|s| meta.query_advice(*s, Rotation::cur()))
+                .collect::<Vec<_>>();
+            let constants = constants
+                .iter()
+                .map(|c| meta.query_fixed(*c, Rotation::cur()))
+                .collect::<Vec<_>>();
+            let q = meta.query_selector(q_f);
+            let after_sbox = state
+                .iter()
+                .zip(constants.iter())
+                .map(|(s, c)| pow5(s.clone() + c.clone()))
+                .collect::<Vec<_>>();
+            let after_layer = external_layer(&after_sbox);
+            next_state
+                .iter()
+                .zip(after_layer.iter())
+                .map(|(n, a)| q.clone() * (n.clone() - a.clone()))
+                .collect::<Vec<_>>()
+        });
+        meta.create_gate("poseidon2 partial round", |meta| {
+            let next_state = state
+                .iter()
+                .map(|s| meta.query_advice(*s, Rotation::next()))
+                .collect::<Vec<_>>();
+            let state = state
+                .iter()
+                .map(|s| meta.query_advice(*s, Rotation::cur()))
+                .collect::<Vec<_>>();
+            let constant = meta.query_fixed(constants[0], Rotation::cur());
+            let q = meta.query_selector(q_p);
+            let mut after_sbox = state.clone();
+            after_sbox[0] = pow5(state[0].clone() + constant);
+            let after_layer = internal_layer(&after_sbox);
+            next_state
+                .iter()
+                .zip(after_layer.iter())
+                .map(|(n, a)| q.clone() * (n.clone() - a.clone()))
+                .collect::<Vec<_>>()
+        });
+
+        Self {
+            state,
+            constants,
+            q_e,
+            q_f,
+            q_p,
+            _maker: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Poseidon2Bn254Chip<F: PrimeField> {
+    config: Poseidon2Bn254ChipConfig<F>,
+}
+
+impl<F: PrimeField> Poseidon2Bn254Chip<F> {
+    pub fn new(config: &Poseidon2Bn254ChipConfig<F>) -> Self {
+        Poseidon2Bn254Chip {
+            config: config.clone(),
+        }
+    }
+
+    pub fn assign_initial_state(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [Value<F>; T_BN254_POSEIDON2],
+    ) -> Result<[AssignedCell<F, F>; T_BN254_POSEIDON2], Error> {
+        let state_assigned = state
+            .iter()
+            .zip(self.config.state.iter())
+            .map(|(s, c)| ctx.assign_advice(|| "", *c, *s))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(state_assigned.try_into().unwrap())
+    }
+
+    // assume that the state is already assigned and apply the external linear layer
+    fn assign_external_layer(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [Value<F>; T_BN254_POSEIDON2],
+    ) -> Result<[AssignedCell<F, F>; T_BN254_POSEIDON2], Error> {
+        ctx.enable(self.config.q_e.clone())?;
+        ctx.next();
+        let mut state = state.clone();
+        poseidon2_external_layer_value(&mut state);
+        self.assign_initial_state(ctx, state)
+    }
+
+    fn assign_full_round(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [Value<F>; T_BN254_POSEIDON2],
+        counter: &mut usize,
+    ) -> Result<[AssignedCell<F, F>; T_BN254_POSEIDON2], Error> {
+        ctx.enable(self.config.q_f.clone())?;
+        self.config
+            .constants
+            .iter()
+            .zip(ROUND_CONSTANTS_BG[*counter..*counter + T_BN254_POSEIDON2].iter())
+            .map(|(c, r)| ctx.assign_fixed(|| "", *c, bg_to_fe::<F>(r)))
+            .collect::<Result<Vec<_>, _>>()?;
+        ctx.next();
+        // assign next
+        let mut state = state.clone();
+        poseidon2_full_round_value(&mut state, counter);
+        self.assign_initial_state(ctx, state)
+    }
+
+    fn assign_partial_round(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [Value<F>; T_BN254_POSEIDON2],
+        counter: &mut usize,
+    ) -> Result<[AssignedCell<F, F>; T_BN254_POSEIDON2], Error> {
+        ctx.enable(self.config.q_p.clone())?;
+        ctx.assign_fixed(
+            || "",
+            self.config.constants[0],
+            bg_to_fe::<F>(&ROUND_CONSTANTS_BG[*counter]),
+        )?;
+        ctx.next();
+        // assign next
+        let mut state = state.clone();
+        poseidon2_partial_round_value(&mut state, counter);
+        self.assign_initial_state(ctx, state)
+    }
+
+    pub fn apply_permute(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedCell<F, F>; T_BN254_POSEIDON2],
+    ) -> Result<[AssignedCell<F, F>; T_BN254_POSEIDON2], Error> {
+        let mut counter = 0;
+
+        let state_value = state.iter().map(|s| s.value().cloned()).collect::<Vec<_>>();
+        // re-assign state to the current row.
+        let state_assigned = self.assign_initial_state(ctx, state_value.try_into().unwrap())?;
+        for i in 0..T_BN254_POSEIDON2 {
+            ctx.constrain_equal(state[i].cell(), state_assigned[i].cell())?;
+        }
+
+        let state_value = state.iter().map(|s| s.value().cloned()).collect::<Vec<_>>();
+        let mut state = self.assign_external_layer(ctx, state_value.try_into().unwrap())?;
+        for _ in 0..R_F_BN254_POSEIDON2 / 2 {
+            let state_value = state.iter().map(|s| s.value().cloned()).collect::<Vec<_>>();
+            state = self.assign_full_round(ctx, state_value.try_into().unwrap(), &mut counter)?;
+        }
+        for _ in 0..R_P_BN254_POSEIDON2 {
+            let state_value = state.iter().map(|s| s.value().cloned()).collect::<Vec<_>>();
+            state =
+                self.assign_partial_round(ctx, state_value.try_into().unwrap(), &mut counter)?;
+        }
+        for _ in 0..R_F_BN254_POSEIDON2 / 2 {
+            let state_value = state.iter().map(|s| s.value().cloned()).collect::<Vec<_>>();
+            state = self.assign_full_round(ctx, state_value.try_into().unwrap(), &mut counter)?;
+        }
+        ctx.next();
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::RegionCtx;
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use crate::bn254_poseidon::native::permute_bn254_poseidon2_native;
+
+    use super::{Poseidon2Bn254Chip, Poseidon2Bn254ChipConfig};
+
+    #[derive(Clone, Default)]
+    pub struct TestCircuit;
+
+    impl Circuit<Fr> for TestCircuit {
+        type Config = Poseidon2Bn254ChipConfig<Fr>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            Poseidon2Bn254ChipConfig::<Fr>::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let poseidon2_chip = Poseidon2Bn254Chip::new(&config);
+            layouter.assign_region(
+                || "test",
+                |region| {
+                    let mut ctx = RegionCtx::new(region, 0);
+
+                    let initial_state = [0u64, 1, 2, 3].map(Fr::from);
+                    let mut state = poseidon2_chip
+                        .assign_initial_state(&mut ctx, initial_state.map(Value::known))?;
+                    state = poseidon2_chip.apply_permute(&mut ctx, state)?;
+
+                    let mut expected_state = initial_state;
+                    permute_bn254_poseidon2_native(&mut expected_state);
+                    state.iter().zip(expected_state.iter()).for_each(|(x, e)| {
+                        x.value().map(|x| assert_eq!(x, e));
+                    });
+
+                    for _ in 0..1200 {
+                        state = poseidon2_chip.apply_permute(&mut ctx, state)?;
+                    }
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_poseidon2_mock() {
+        const DEGREE: u32 = 17;
+        let circuit = TestCircuit;
+        let mock_prover = MockProver::run(DEGREE, &circuit, vec![]).unwrap();
+        mock_prover.assert_satisfied();
+    }
+}
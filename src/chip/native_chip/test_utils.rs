@@ -1,19 +1,18 @@
 use halo2_proofs::halo2curves::bn256::G1Affine;
 use halo2_proofs::plonk::keygen_pk;
 use halo2_proofs::plonk::keygen_vk;
-use halo2_proofs::plonk::verify_proof;
 use halo2_proofs::plonk::ProvingKey;
 use halo2_proofs::poly::kzg::commitment::ParamsKZG;
-use halo2_proofs::transcript::TranscriptWriterBuffer;
 use halo2_proofs::{
     halo2curves::bn256::{Bn256, Fr},
-    plonk::{create_proof, Circuit},
+    plonk::Circuit,
 };
 use halo2_solidity_verifier::encode_calldata;
 use halo2_solidity_verifier::BatchOpenScheme::Bdfg21;
-use halo2_solidity_verifier::Keccak256Transcript;
 use halo2_solidity_verifier::{compile_solidity, Evm, SolidityGenerator};
-use rand::RngCore;
+
+use crate::error::VerifierError;
+use crate::verifier_api::{create_snark_proof, verify_snark_proof};
 
 pub fn test_contract_size(k: u32, circuit: &impl Circuit<Fr>) {
     let mut rng = rand::thread_rng();
@@ -45,7 +44,7 @@ pub fn test_verify_on_contract(k: u32, circuit: &(impl Circuit<Fr> + Clone), ins
     let pk = keygen_pk(&param, vk, circuit).unwrap();
     let now = std::time::Instant::now();
     let calldata = {
-        let proof = create_proof_checked(&param, &pk, circuit.clone(), &instance, &mut rng);
+        let proof = create_proof_checked(&param, &pk, circuit.clone(), &instance).unwrap();
         encode_calldata(Some(vk_address.into()), &proof, &instance)
     };
     println!("Proof creation time: {:?}", now.elapsed());
@@ -59,37 +58,8 @@ pub fn create_proof_checked(
     pk: &ProvingKey<G1Affine>,
     circuit: impl Circuit<Fr>,
     instances: &[Fr],
-    mut rng: impl RngCore,
-) -> Vec<u8> {
-    use halo2_proofs::poly::kzg::{
-        multiopen::{ProverSHPLONK, VerifierSHPLONK},
-        strategy::SingleStrategy,
-    };
-
-    let proof = {
-        let mut transcript = Keccak256Transcript::new(Vec::new());
-        create_proof::<_, ProverSHPLONK<_>, _, _, _, _>(
-            params,
-            pk,
-            &[circuit],
-            &[&[instances]],
-            &mut rng,
-            &mut transcript,
-        )
-        .unwrap();
-        transcript.finalize()
-    };
-
-    let result = {
-        let mut transcript = Keccak256Transcript::new(proof.as_slice());
-        verify_proof::<_, VerifierSHPLONK<_>, _, _, SingleStrategy<_>>(
-            params,
-            pk.get_vk(),
-            SingleStrategy::new(params),
-            &[&[instances]],
-            &mut transcript,
-        )
-    };
-    assert!(result.is_ok());
-    proof
+) -> Result<Vec<u8>, VerifierError> {
+    let proof = create_snark_proof(params, pk, circuit, instances)?;
+    assert!(verify_snark_proof(params, pk.get_vk(), &proof, instances)?);
+    Ok(proof)
 }
@@ -0,0 +1,288 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+};
+
+use super::{
+    range_check_native,
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+const LOG2_MAX_NUM_ADDENDS: usize = 4;
+
+/// A gate to perform addition on `num_addends` different 32-bit values, plus a small carry.
+/// The plonky2 gate is `U32AddManyGate` of plonky2-u32.
+#[derive(Clone, Debug)]
+pub struct U32AddManyGateConstrainer {
+    pub num_addends: usize,
+    pub num_ops: usize,
+}
+
+impl U32AddManyGateConstrainer {
+    pub fn wire_ith_op_jth_addend(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < self.num_addends);
+        (self.num_addends + 3) * i + j
+    }
+
+    pub fn wire_ith_carry(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        (self.num_addends + 3) * i + self.num_addends
+    }
+
+    pub fn wire_ith_output_result(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        (self.num_addends + 3) * i + self.num_addends + 1
+    }
+
+    pub fn wire_ith_output_carry(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        (self.num_addends + 3) * i + self.num_addends + 2
+    }
+
+    pub fn limb_bits() -> usize {
+        2
+    }
+
+    pub fn num_result_limbs() -> usize {
+        32 / Self::limb_bits()
+    }
+
+    pub fn num_carry_limbs() -> usize {
+        LOG2_MAX_NUM_ADDENDS.div_ceil(Self::limb_bits())
+    }
+
+    pub fn num_limbs() -> usize {
+        Self::num_result_limbs() + Self::num_carry_limbs()
+    }
+
+    /// The `j`th limb of the `i`th output, result limbs first, in little-endian order.
+    pub fn wire_ith_output_jth_limb(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < Self::num_limbs());
+        (self.num_addends + 3) * self.num_ops + Self::num_limbs() * i + j
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.num_ops * (3 + Self::num_limbs())
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for U32AddManyGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("U32AddManyGate")
+            .with_param("num_addends", self.num_addends)
+            .with_param("num_ops", self.num_ops)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let mut computed_output = local_wires[self.wire_ith_carry(i)].clone();
+            for j in 0..self.num_addends {
+                computed_output = goldilocks_extension_chip.add_extension(
+                    ctx,
+                    &computed_output,
+                    &local_wires[self.wire_ith_op_jth_addend(i, j)],
+                )?;
+            }
+
+            let output_result = &local_wires[self.wire_ith_output_result(i)];
+            let output_carry = &local_wires[self.wire_ith_output_carry(i)];
+            let base = goldilocks_extension_chip
+                .constant_extension_from_base(ctx, GoldilocksField::from_canonical_u64(1 << 32))?;
+            let combined_output = goldilocks_extension_chip.mul_add_extension(
+                ctx,
+                output_carry,
+                &base,
+                output_result,
+            )?;
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &combined_output,
+                &computed_output,
+            )?);
+
+            let mut combined_result_limbs = goldilocks_extension_chip.zero_extension(ctx)?;
+            let mut combined_carry_limbs = goldilocks_extension_chip.zero_extension(ctx)?;
+            let limb_base = goldilocks_extension_chip.constant_extension_from_base(
+                ctx,
+                GoldilocksField::from_canonical_u64(1 << Self::limb_bits()),
+            )?;
+            for j in (0..Self::num_limbs()).rev() {
+                let this_limb = &local_wires[self.wire_ith_output_jth_limb(i, j)];
+                constraints.push(goldilocks_extension_chip.range_check_extension(
+                    ctx,
+                    this_limb,
+                    1 << Self::limb_bits(),
+                )?);
+
+                let combined = if j < Self::num_result_limbs() {
+                    &mut combined_result_limbs
+                } else {
+                    &mut combined_carry_limbs
+                };
+                *combined = goldilocks_extension_chip
+                    .mul_add_extension(ctx, combined, &limb_base, this_limb)?;
+            }
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &combined_result_limbs,
+                output_result,
+            )?);
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &combined_carry_limbs,
+                output_carry,
+            )?);
+        }
+
+        Ok(constraints)
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let base = <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(1 << 32);
+        let limb_base = <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(
+            1 << Self::limb_bits(),
+        );
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let computed_output = (0..self.num_addends)
+                .map(|j| vars.local_wires[self.wire_ith_op_jth_addend(i, j)])
+                .fold(vars.local_wires[self.wire_ith_carry(i)], |acc, addend| {
+                    acc + addend
+                });
+
+            let output_result = vars.local_wires[self.wire_ith_output_result(i)];
+            let output_carry = vars.local_wires[self.wire_ith_output_carry(i)];
+            constraints.push(output_carry * base + output_result - computed_output);
+
+            let mut combined_result_limbs = <GoldilocksField as Extendable<D>>::Extension::ZERO;
+            let mut combined_carry_limbs = <GoldilocksField as Extendable<D>>::Extension::ZERO;
+            for j in (0..Self::num_limbs()).rev() {
+                let this_limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                constraints.push(range_check_native(this_limb, 1 << Self::limb_bits()));
+
+                let combined = if j < Self::num_result_limbs() {
+                    &mut combined_result_limbs
+                } else {
+                    &mut combined_carry_limbs
+                };
+                *combined = *combined * limb_base + this_limb;
+            }
+            constraints.push(combined_result_limbs - output_result);
+            constraints.push(combined_carry_limbs - output_carry);
+        }
+        Ok(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::U32AddManyGateConstrainer;
+    use crate::chip::plonk::gates::gate_test::{custom_gate_satisfied, test_custom_gate};
+    use plonky2::{
+        field::{
+            extension::quadratic::QuadraticExtension,
+            goldilocks_field::GoldilocksField,
+            types::{Field, Sample},
+        },
+        plonk::circuit_data::CircuitConfig,
+    };
+    use plonky2_u32::gates::add_many_u32::U32AddManyGate;
+    use rand::Rng;
+
+    type FE = QuadraticExtension<GoldilocksField>;
+
+    fn generate_wires(
+        gate: &U32AddManyGateConstrainer,
+        addends: &[Vec<u32>],
+        carries: &[u32],
+    ) -> Vec<FE> {
+        let mut wires = FE::rand_vec(
+            (gate.num_addends + 3 + U32AddManyGateConstrainer::num_limbs()) * gate.num_ops,
+        );
+        let mut set = |wire: usize, value: u64| {
+            wires[wire] = FE::from_canonical_u64(value);
+        };
+        for (i, (addends, &carry)) in addends.iter().zip(carries).enumerate() {
+            for (j, &addend) in addends.iter().enumerate() {
+                set(gate.wire_ith_op_jth_addend(i, j), addend as u64);
+            }
+            set(gate.wire_ith_carry(i), carry as u64);
+            let output = addends.iter().map(|&a| a as u64).sum::<u64>() + carry as u64;
+            set(gate.wire_ith_output_result(i), output & 0xffffffff);
+            set(gate.wire_ith_output_carry(i), output >> 32);
+            for j in 0..U32AddManyGateConstrainer::num_limbs() {
+                set(gate.wire_ith_output_jth_limb(i, j), (output >> (2 * j)) & 3);
+            }
+        }
+        wires
+    }
+
+    #[test]
+    fn test_add_many_u32_gate() {
+        let gate = U32AddManyGateConstrainer {
+            num_addends: 4,
+            num_ops: 3,
+        };
+        let mut rng = rand::thread_rng();
+        let addends = (0..gate.num_ops)
+            .map(|_| (0..gate.num_addends).map(|_| rng.gen()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let carries = (0..gate.num_ops)
+            .map(|_| rng.gen_range(0..8))
+            .collect::<Vec<_>>();
+        let num_constraints = gate.num_constraints();
+
+        let wires = generate_wires(&gate, &addends, &carries);
+        assert!(custom_gate_satisfied(
+            gate.clone(),
+            &wires,
+            num_constraints,
+            17
+        ));
+
+        let mut wrong_wires = wires;
+        wrong_wires[gate.wire_ith_output_result(1)] += FE::ONE;
+        assert!(!custom_gate_satisfied(
+            gate,
+            &wrong_wires,
+            num_constraints,
+            17
+        ));
+    }
+
+    #[test]
+    fn test_add_many_u32_gate_against_plonky2() {
+        let plonky2_gate =
+            U32AddManyGate::new_from_config(&CircuitConfig::standard_recursion_config(), 4);
+        let halo2_gate = U32AddManyGateConstrainer {
+            num_addends: plonky2_gate.num_addends,
+            num_ops: plonky2_gate.num_ops,
+        };
+        test_custom_gate(plonky2_gate, halo2_gate, 17);
+    }
+}
@@ -1,5 +1,9 @@
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::{
@@ -8,7 +12,10 @@ use crate::{
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 #[derive(Debug, Clone)]
 pub struct ArithmeticGateConstrainer {
@@ -34,15 +41,19 @@ impl ArithmeticGateConstrainer {
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for ArithmeticGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for ArithmeticGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("ArithmeticGate").with_param("num_ops", self.num_ops)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
         let goldilocks_extension_chip = GoldilocksExtensionChip::new(goldilocks_chip_config);
         let const_0 = &local_constants[0];
         let const_1 = &local_constants[1];
@@ -69,6 +80,26 @@ impl<F: PrimeField> CustomGateConstrainer<F> for ArithmeticGateConstrainer {
 
         Ok(constraints)
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let const_0 = vars.local_constants[0];
+        let const_1 = vars.local_constants[1];
+        Ok((0..self.num_ops)
+            .map(|i| {
+                let multiplicand_0 = vars.local_wires[Self::wires_ith_multiplicand_0(i)];
+                let multiplicand_1 = vars.local_wires[Self::wires_ith_multiplicand_1(i)];
+                let addend = vars.local_wires[Self::wires_ith_addend(i)];
+                let output = vars.local_wires[Self::wires_ith_output(i)];
+                output - (multiplicand_0 * multiplicand_1 * const_0 + addend * const_1)
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -2,46 +2,59 @@ use std::ops::Range;
 
 use crate::context::RegionCtx;
 use halo2_proofs::halo2curves::ff::PrimeField;
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::goldilocks_chip::GoldilocksChipConfig,
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 /// A gate which can perform a weighted multiply-add, i.e. `result = c0 x y + c1 z`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
 #[derive(Debug, Clone)]
-pub struct ArithmeticExtensionGateConstrainer {
+pub struct ArithmeticExtensionGateConstrainer<const D: usize> {
     /// Number of arithmetic operations performed by an arithmetic gate.
     pub num_ops: usize,
 }
 
-impl ArithmeticExtensionGateConstrainer {
+impl<const D: usize> ArithmeticExtensionGateConstrainer<D> {
     pub fn wires_ith_multiplicand_0(i: usize) -> Range<usize> {
-        4 * 2 * i..4 * 2 * i + 2
+        4 * D * i..4 * D * i + D
     }
     pub fn wires_ith_multiplicand_1(i: usize) -> Range<usize> {
-        4 * 2 * i + 2..4 * 2 * i + 2 * 2
+        4 * D * i + D..4 * D * i + 2 * D
     }
     pub fn wires_ith_addend(i: usize) -> Range<usize> {
-        4 * 2 * i + 2 * 2..4 * 2 * i + 3 * 2
+        4 * D * i + 2 * D..4 * D * i + 3 * D
     }
     pub fn wires_ith_output(i: usize) -> Range<usize> {
-        4 * 2 * i + 3 * 2..4 * 2 * i + 4 * 2
+        4 * D * i + 3 * D..4 * D * i + 4 * D
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for ArithmeticExtensionGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D>
+    for ArithmeticExtensionGateConstrainer<D>
+{
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("ArithmeticExtensionGate").with_param("num_ops", self.num_ops)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, halo2_proofs::plonk::Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, halo2_proofs::plonk::Error> {
         let goldilocks_extension_algebra_chip =
             self.goldilocks_extension_algebra_chip(goldilocks_chip_config);
         let const_0 = &local_constants[0];
@@ -80,12 +93,34 @@ impl<F: PrimeField> CustomGateConstrainer<F> for ArithmeticExtensionGateConstrai
         }
         Ok(constraints)
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let const_0 = vars.local_constants[0];
+        let const_1 = vars.local_constants[1];
+        let mut constraints = Vec::new();
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.get_local_ext_algebra(Self::wires_ith_multiplicand_0(i));
+            let multiplicand_1 = vars.get_local_ext_algebra(Self::wires_ith_multiplicand_1(i));
+            let addend = vars.get_local_ext_algebra(Self::wires_ith_addend(i));
+            let output = vars.get_local_ext_algebra(Self::wires_ith_output(i));
+            let computed_output =
+                (multiplicand_0 * multiplicand_1).scalar_mul(const_0) + addend.scalar_mul(const_1);
+            constraints.extend((output - computed_output).to_basefield_array());
+        }
+        Ok(constraints)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::chip::plonk::gates::gate_test::test_custom_gate;
+    use crate::chip::plonk::gates::gate_test::{test_custom_gate, test_custom_gate_with_degree};
     use plonky2::{
         gates::arithmetic_extension::ArithmeticExtensionGate, plonk::circuit_data::CircuitConfig,
     };
@@ -101,4 +136,15 @@ mod tests {
         };
         test_custom_gate(plonky2_gate, halo2_gate, 17);
     }
+
+    #[test]
+    fn test_arithmetic_extension_gate_quartic() {
+        let plonky2_gate = ArithmeticExtensionGate::<4>::new_from_config(
+            &CircuitConfig::standard_recursion_config(),
+        );
+        let halo2_gate = ArithmeticExtensionGateConstrainer::<4> {
+            num_ops: plonky2_gate.num_ops,
+        };
+        test_custom_gate_with_degree(plonky2_gate, halo2_gate, 17);
+    }
 }
@@ -0,0 +1,324 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+};
+
+use super::{
+    range_check_native,
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// A gate to perform a basic mul-add on 32-bit values (we assume they are range-checked beforehand).
+/// The plonky2 gate is `U32ArithmeticGate` of plonky2-u32.
+#[derive(Clone, Debug)]
+pub struct U32ArithmeticGateConstrainer {
+    pub num_ops: usize,
+}
+
+impl U32ArithmeticGateConstrainer {
+    pub fn wire_ith_multiplicand_0(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::routed_wires_per_op() * i
+    }
+
+    pub fn wire_ith_multiplicand_1(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::routed_wires_per_op() * i + 1
+    }
+
+    pub fn wire_ith_addend(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::routed_wires_per_op() * i + 2
+    }
+
+    pub fn wire_ith_output_low_half(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::routed_wires_per_op() * i + 3
+    }
+
+    pub fn wire_ith_output_high_half(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::routed_wires_per_op() * i + 4
+    }
+
+    /// Inverse of `u32::MAX - output_high_half`, or anything when the high half is `u32::MAX`.
+    pub fn wire_ith_inverse(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::routed_wires_per_op() * i + 5
+    }
+
+    pub fn limb_bits() -> usize {
+        2
+    }
+
+    pub fn num_limbs() -> usize {
+        64 / Self::limb_bits()
+    }
+
+    pub fn routed_wires_per_op() -> usize {
+        6
+    }
+
+    /// The `j`th limb of the `i`th output, in little-endian order.
+    pub fn wire_ith_output_jth_limb(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < Self::num_limbs());
+        Self::routed_wires_per_op() * self.num_ops + Self::num_limbs() * i + j
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.num_ops * (4 + Self::num_limbs())
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for U32ArithmeticGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("U32ArithmeticGate").with_param("num_ops", self.num_ops)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+        let one = goldilocks_extension_chip.one_extension(ctx)?;
+        let base = goldilocks_extension_chip
+            .constant_extension_from_base(ctx, GoldilocksField::from_canonical_u64(1 << 32))?;
+        let u32_max = goldilocks_extension_chip
+            .constant_extension_from_base(ctx, GoldilocksField::from_canonical_u32(u32::MAX))?;
+        let limb_base = goldilocks_extension_chip.constant_extension_from_base(
+            ctx,
+            GoldilocksField::from_canonical_u64(1 << Self::limb_bits()),
+        )?;
+
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let multiplicand_0 = &local_wires[self.wire_ith_multiplicand_0(i)];
+            let multiplicand_1 = &local_wires[self.wire_ith_multiplicand_1(i)];
+            let addend = &local_wires[self.wire_ith_addend(i)];
+            let computed_output = goldilocks_extension_chip.mul_add_extension(
+                ctx,
+                multiplicand_0,
+                multiplicand_1,
+                addend,
+            )?;
+
+            let output_low = &local_wires[self.wire_ith_output_low_half(i)];
+            let output_high = &local_wires[self.wire_ith_output_high_half(i)];
+            let inverse = &local_wires[self.wire_ith_inverse(i)];
+
+            // Check canonicity of combined_output = output_high * 2^32 + output_low.
+            // This is zero if and only if the high limb is `u32::MAX`.
+            let diff = goldilocks_extension_chip.sub_extension(ctx, &u32_max, output_high)?;
+            // If this is zero, the diff is invertible, so the high limb is not `u32::MAX`.
+            let hi_not_max =
+                goldilocks_extension_chip.mul_sub_extension(ctx, inverse, &diff, &one)?;
+            // If this is zero, either the high limb is not `u32::MAX`, or the low limb is zero.
+            let hi_not_max_or_lo_zero =
+                goldilocks_extension_chip.mul_extension(ctx, &hi_not_max, output_low)?;
+            constraints.push(hi_not_max_or_lo_zero);
+
+            let combined_output =
+                goldilocks_extension_chip.mul_add_extension(ctx, output_high, &base, output_low)?;
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &combined_output,
+                &computed_output,
+            )?);
+
+            let mut combined_low_limbs = goldilocks_extension_chip.zero_extension(ctx)?;
+            let mut combined_high_limbs = goldilocks_extension_chip.zero_extension(ctx)?;
+            let midpoint = Self::num_limbs() / 2;
+            for j in (0..Self::num_limbs()).rev() {
+                let this_limb = &local_wires[self.wire_ith_output_jth_limb(i, j)];
+                constraints.push(goldilocks_extension_chip.range_check_extension(
+                    ctx,
+                    this_limb,
+                    1 << Self::limb_bits(),
+                )?);
+
+                let combined = if j < midpoint {
+                    &mut combined_low_limbs
+                } else {
+                    &mut combined_high_limbs
+                };
+                *combined = goldilocks_extension_chip
+                    .mul_add_extension(ctx, combined, &limb_base, this_limb)?;
+            }
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &combined_low_limbs,
+                output_low,
+            )?);
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &combined_high_limbs,
+                output_high,
+            )?);
+        }
+
+        Ok(constraints)
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let one = <GoldilocksField as Extendable<D>>::Extension::ONE;
+        let base = <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(1 << 32);
+        let u32_max = <GoldilocksField as Extendable<D>>::Extension::from_canonical_u32(u32::MAX);
+        let limb_base = <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(
+            1 << Self::limb_bits(),
+        );
+
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.local_wires[self.wire_ith_multiplicand_0(i)];
+            let multiplicand_1 = vars.local_wires[self.wire_ith_multiplicand_1(i)];
+            let addend = vars.local_wires[self.wire_ith_addend(i)];
+            let computed_output = multiplicand_0 * multiplicand_1 + addend;
+
+            let output_low = vars.local_wires[self.wire_ith_output_low_half(i)];
+            let output_high = vars.local_wires[self.wire_ith_output_high_half(i)];
+            let inverse = vars.local_wires[self.wire_ith_inverse(i)];
+
+            let hi_not_max = inverse * (u32_max - output_high) - one;
+            constraints.push(hi_not_max * output_low);
+            constraints.push(output_high * base + output_low - computed_output);
+
+            let mut combined_low_limbs = <GoldilocksField as Extendable<D>>::Extension::ZERO;
+            let mut combined_high_limbs = <GoldilocksField as Extendable<D>>::Extension::ZERO;
+            let midpoint = Self::num_limbs() / 2;
+            for j in (0..Self::num_limbs()).rev() {
+                let this_limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                constraints.push(range_check_native(this_limb, 1 << Self::limb_bits()));
+
+                let combined = if j < midpoint {
+                    &mut combined_low_limbs
+                } else {
+                    &mut combined_high_limbs
+                };
+                *combined = *combined * limb_base + this_limb;
+            }
+            constraints.push(combined_low_limbs - output_low);
+            constraints.push(combined_high_limbs - output_high);
+        }
+        Ok(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::U32ArithmeticGateConstrainer;
+    use crate::chip::plonk::gates::gate_test::{custom_gate_satisfied, test_custom_gate};
+    use plonky2::{
+        field::{
+            extension::quadratic::QuadraticExtension,
+            goldilocks_field::GoldilocksField,
+            types::{Field, Sample},
+        },
+        plonk::circuit_data::CircuitConfig,
+    };
+    use plonky2_u32::gates::arithmetic_u32::U32ArithmeticGate;
+    use rand::Rng;
+
+    type FE = QuadraticExtension<GoldilocksField>;
+
+    fn generate_wires(gate: &U32ArithmeticGateConstrainer, inputs: &[[u32; 3]]) -> Vec<FE> {
+        let mut wires = FE::rand_vec(
+            (U32ArithmeticGateConstrainer::routed_wires_per_op()
+                + U32ArithmeticGateConstrainer::num_limbs())
+                * gate.num_ops,
+        );
+        let mut set = |wire: usize, value: FE| {
+            wires[wire] = value;
+        };
+        for (i, &[multiplicand_0, multiplicand_1, addend]) in inputs.iter().enumerate() {
+            set(
+                gate.wire_ith_multiplicand_0(i),
+                FE::from_canonical_u32(multiplicand_0),
+            );
+            set(
+                gate.wire_ith_multiplicand_1(i),
+                FE::from_canonical_u32(multiplicand_1),
+            );
+            set(gate.wire_ith_addend(i), FE::from_canonical_u32(addend));
+            let output = multiplicand_0 as u64 * multiplicand_1 as u64 + addend as u64;
+            let output_high = output >> 32;
+            set(
+                gate.wire_ith_output_low_half(i),
+                FE::from_canonical_u64(output & 0xffffffff),
+            );
+            set(
+                gate.wire_ith_output_high_half(i),
+                FE::from_canonical_u64(output_high),
+            );
+            let diff = FE::from_canonical_u32(u32::MAX) - FE::from_canonical_u64(output_high);
+            set(
+                gate.wire_ith_inverse(i),
+                diff.try_inverse().unwrap_or(FE::ZERO),
+            );
+            for j in 0..U32ArithmeticGateConstrainer::num_limbs() {
+                set(
+                    gate.wire_ith_output_jth_limb(i, j),
+                    FE::from_canonical_u64((output >> (2 * j)) & 3),
+                );
+            }
+        }
+        wires
+    }
+
+    #[test]
+    fn test_arithmetic_u32_gate() {
+        let gate = U32ArithmeticGateConstrainer { num_ops: 3 };
+        let mut rng = rand::thread_rng();
+        let mut inputs = (0..gate.num_ops)
+            .map(|_| rng.gen())
+            .collect::<Vec<[u32; 3]>>();
+        // The largest output, `2^64 - 2^32`, has a high half of `u32::MAX` and a zero low half.
+        inputs[0] = [u32::MAX, u32::MAX, u32::MAX];
+        let num_constraints = gate.num_constraints();
+
+        let wires = generate_wires(&gate, &inputs);
+        assert!(custom_gate_satisfied(
+            gate.clone(),
+            &wires,
+            num_constraints,
+            17
+        ));
+
+        let mut wrong_wires = wires;
+        wrong_wires[gate.wire_ith_addend(2)] += FE::ONE;
+        assert!(!custom_gate_satisfied(
+            gate,
+            &wrong_wires,
+            num_constraints,
+            17
+        ));
+    }
+
+    #[test]
+    fn test_arithmetic_u32_gate_against_plonky2() {
+        let plonky2_gate =
+            U32ArithmeticGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        let halo2_gate = U32ArithmeticGateConstrainer {
+            num_ops: plonky2_gate.num_ops,
+        };
+        test_custom_gate(plonky2_gate, halo2_gate, 17);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::context::RegionCtx;
 use halo2_proofs::halo2curves::ff::PrimeField;
-use plonky2::field::{goldilocks_field::GoldilocksField, types::Field};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
 use std::ops::Range;
 
 use crate::{
@@ -8,7 +11,11 @@ use crate::{
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    range_check_native, reduce_native,
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 #[derive(Debug, Clone)]
 pub struct BaseSumGateConstrainer {
@@ -25,15 +32,21 @@ impl BaseSumGateConstrainer {
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for BaseSumGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for BaseSumGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("BaseSumGate")
+            .with_param("num_limbs", self.num_limbs)
+            .with_param("Base", 2)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        _local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, halo2_proofs::plonk::Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, halo2_proofs::plonk::Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         let base = goldilocks_extension_chip.two_extension(ctx)?;
         let sum = &local_wires[Self::WIRE_SUM];
@@ -59,6 +72,21 @@ impl<F: PrimeField> CustomGateConstrainer<F> for BaseSumGateConstrainer {
         }
         Ok(constraints)
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let base = <GoldilocksField as Extendable<D>>::Extension::TWO;
+        let sum = vars.local_wires[Self::WIRE_SUM];
+        let limbs = &vars.local_wires[self.limbs()];
+        let mut constraints = vec![reduce_native(base, limbs) - sum];
+        constraints.extend(limbs.iter().map(|&limb| range_check_native(limb, 2)));
+        Ok(constraints)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,398 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+};
+
+use super::{
+    range_check_native, reduce_native,
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// A gate for checking that one value is less than or equal to another.
+/// The plonky2 gate is `ComparisonGate` of plonky2-u32.
+#[derive(Clone, Debug)]
+pub struct ComparisonGateConstrainer {
+    pub num_bits: usize,
+    pub num_chunks: usize,
+}
+
+impl ComparisonGateConstrainer {
+    pub fn chunk_bits(&self) -> usize {
+        self.num_bits.div_ceil(self.num_chunks)
+    }
+
+    pub fn wire_first_input(&self) -> usize {
+        0
+    }
+
+    pub fn wire_second_input(&self) -> usize {
+        1
+    }
+
+    pub fn wire_result_bool(&self) -> usize {
+        2
+    }
+
+    pub fn wire_most_significant_diff(&self) -> usize {
+        3
+    }
+
+    pub fn wire_first_chunk_val(&self, chunk: usize) -> usize {
+        debug_assert!(chunk < self.num_chunks);
+        4 + chunk
+    }
+
+    pub fn wire_second_chunk_val(&self, chunk: usize) -> usize {
+        debug_assert!(chunk < self.num_chunks);
+        4 + self.num_chunks + chunk
+    }
+
+    pub fn wire_equality_dummy(&self, chunk: usize) -> usize {
+        debug_assert!(chunk < self.num_chunks);
+        4 + 2 * self.num_chunks + chunk
+    }
+
+    pub fn wire_chunks_equal(&self, chunk: usize) -> usize {
+        debug_assert!(chunk < self.num_chunks);
+        4 + 3 * self.num_chunks + chunk
+    }
+
+    pub fn wire_intermediate_value(&self, chunk: usize) -> usize {
+        debug_assert!(chunk < self.num_chunks);
+        4 + 4 * self.num_chunks + chunk
+    }
+
+    /// The `bit_index`th bit of `2^chunk_bits + most_significant_diff`.
+    pub fn wire_most_significant_diff_bit(&self, bit_index: usize) -> usize {
+        debug_assert!(bit_index <= self.chunk_bits());
+        4 + 5 * self.num_chunks + bit_index
+    }
+
+    pub fn num_wires(&self) -> usize {
+        4 + 5 * self.num_chunks + (self.chunk_bits() + 1)
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        6 + 5 * self.num_chunks + self.chunk_bits()
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for ComparisonGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("ComparisonGate")
+            .with_param("num_bits", self.num_bits)
+            .with_param("num_chunks", self.num_chunks)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+        let one = goldilocks_extension_chip.one_extension(ctx)?;
+        let two = goldilocks_extension_chip.two_extension(ctx)?;
+        let chunk_size = 1 << self.chunk_bits();
+        let chunk_base = goldilocks_extension_chip
+            .constant_extension_from_base(ctx, GoldilocksField::from_canonical_usize(chunk_size))?;
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        let first_input = &local_wires[self.wire_first_input()];
+        let second_input = &local_wires[self.wire_second_input()];
+
+        // Get chunks and assert that they match.
+        let first_chunks = (0..self.num_chunks)
+            .map(|i| local_wires[self.wire_first_chunk_val(i)].clone())
+            .collect::<Vec<_>>();
+        let second_chunks = (0..self.num_chunks)
+            .map(|i| local_wires[self.wire_second_chunk_val(i)].clone())
+            .collect::<Vec<_>>();
+
+        let first_chunks_combined =
+            goldilocks_extension_chip.reduce_extension(ctx, &chunk_base, &first_chunks)?;
+        let second_chunks_combined =
+            goldilocks_extension_chip.reduce_extension(ctx, &chunk_base, &second_chunks)?;
+        constraints.push(goldilocks_extension_chip.sub_extension(
+            ctx,
+            &first_chunks_combined,
+            first_input,
+        )?);
+        constraints.push(goldilocks_extension_chip.sub_extension(
+            ctx,
+            &second_chunks_combined,
+            second_input,
+        )?);
+
+        let mut most_significant_diff_so_far = goldilocks_extension_chip.zero_extension(ctx)?;
+        for (i, (first_chunk, second_chunk)) in first_chunks.iter().zip(&second_chunks).enumerate()
+        {
+            // Range-check the chunks to be less than `chunk_size`.
+            constraints.push(goldilocks_extension_chip.range_check_extension(
+                ctx,
+                first_chunk,
+                chunk_size,
+            )?);
+            constraints.push(goldilocks_extension_chip.range_check_extension(
+                ctx,
+                second_chunk,
+                chunk_size,
+            )?);
+
+            let difference =
+                goldilocks_extension_chip.sub_extension(ctx, second_chunk, first_chunk)?;
+            let equality_dummy = &local_wires[self.wire_equality_dummy(i)];
+            let chunks_equal = &local_wires[self.wire_chunks_equal(i)];
+
+            // Two constraints to assert that `chunks_equal` is valid.
+            let not_equal = goldilocks_extension_chip.sub_extension(ctx, &one, chunks_equal)?;
+            constraints.push(goldilocks_extension_chip.mul_sub_extension(
+                ctx,
+                &difference,
+                equality_dummy,
+                &not_equal,
+            )?);
+            constraints.push(goldilocks_extension_chip.mul_extension(
+                ctx,
+                chunks_equal,
+                &difference,
+            )?);
+
+            // Update `most_significant_diff_so_far`.
+            let intermediate_value = &local_wires[self.wire_intermediate_value(i)];
+            let computed_intermediate_value = goldilocks_extension_chip.mul_extension(
+                ctx,
+                chunks_equal,
+                &most_significant_diff_so_far,
+            )?;
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                intermediate_value,
+                &computed_intermediate_value,
+            )?);
+            most_significant_diff_so_far = goldilocks_extension_chip.mul_add_extension(
+                ctx,
+                &not_equal,
+                &difference,
+                intermediate_value,
+            )?;
+        }
+
+        let most_significant_diff = &local_wires[self.wire_most_significant_diff()];
+        constraints.push(goldilocks_extension_chip.sub_extension(
+            ctx,
+            most_significant_diff,
+            &most_significant_diff_so_far,
+        )?);
+
+        let most_significant_diff_bits = (0..self.chunk_bits() + 1)
+            .map(|i| local_wires[self.wire_most_significant_diff_bit(i)].clone())
+            .collect::<Vec<_>>();
+
+        // Range-check the bits.
+        for bit in &most_significant_diff_bits {
+            let not_bit = goldilocks_extension_chip.sub_extension(ctx, &one, bit)?;
+            constraints.push(goldilocks_extension_chip.mul_extension(ctx, bit, &not_bit)?);
+        }
+
+        let bits_combined =
+            goldilocks_extension_chip.reduce_extension(ctx, &two, &most_significant_diff_bits)?;
+        let shifted_diff =
+            goldilocks_extension_chip.add_extension(ctx, &chunk_base, most_significant_diff)?;
+        constraints.push(goldilocks_extension_chip.sub_extension(
+            ctx,
+            &shifted_diff,
+            &bits_combined,
+        )?);
+
+        // Iff first <= second, the top (n + 1st) bit of (2^n + most_significant_diff) will be 1.
+        let result_bool = &local_wires[self.wire_result_bool()];
+        constraints.push(goldilocks_extension_chip.sub_extension(
+            ctx,
+            result_bool,
+            &most_significant_diff_bits[self.chunk_bits()],
+        )?);
+
+        Ok(constraints)
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let one = <GoldilocksField as Extendable<D>>::Extension::ONE;
+        let two = <GoldilocksField as Extendable<D>>::Extension::TWO;
+        let chunk_size = 1 << self.chunk_bits();
+        let chunk_base =
+            <GoldilocksField as Extendable<D>>::Extension::from_canonical_usize(chunk_size);
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        let first_input = vars.local_wires[self.wire_first_input()];
+        let second_input = vars.local_wires[self.wire_second_input()];
+        let first_chunks = (0..self.num_chunks)
+            .map(|i| vars.local_wires[self.wire_first_chunk_val(i)])
+            .collect::<Vec<_>>();
+        let second_chunks = (0..self.num_chunks)
+            .map(|i| vars.local_wires[self.wire_second_chunk_val(i)])
+            .collect::<Vec<_>>();
+        constraints.push(reduce_native(chunk_base, &first_chunks) - first_input);
+        constraints.push(reduce_native(chunk_base, &second_chunks) - second_input);
+
+        let mut most_significant_diff_so_far = <GoldilocksField as Extendable<D>>::Extension::ZERO;
+        for (i, (&first_chunk, &second_chunk)) in
+            first_chunks.iter().zip(&second_chunks).enumerate()
+        {
+            constraints.push(range_check_native(first_chunk, chunk_size));
+            constraints.push(range_check_native(second_chunk, chunk_size));
+
+            let difference = second_chunk - first_chunk;
+            let equality_dummy = vars.local_wires[self.wire_equality_dummy(i)];
+            let chunks_equal = vars.local_wires[self.wire_chunks_equal(i)];
+            let not_equal = one - chunks_equal;
+            constraints.push(difference * equality_dummy - not_equal);
+            constraints.push(chunks_equal * difference);
+
+            let intermediate_value = vars.local_wires[self.wire_intermediate_value(i)];
+            constraints.push(intermediate_value - chunks_equal * most_significant_diff_so_far);
+            most_significant_diff_so_far = not_equal * difference + intermediate_value;
+        }
+
+        let most_significant_diff = vars.local_wires[self.wire_most_significant_diff()];
+        constraints.push(most_significant_diff - most_significant_diff_so_far);
+
+        let most_significant_diff_bits = (0..self.chunk_bits() + 1)
+            .map(|i| vars.local_wires[self.wire_most_significant_diff_bit(i)])
+            .collect::<Vec<_>>();
+        for &bit in &most_significant_diff_bits {
+            constraints.push(bit * (one - bit));
+        }
+        constraints.push(
+            chunk_base + most_significant_diff - reduce_native(two, &most_significant_diff_bits),
+        );
+
+        let result_bool = vars.local_wires[self.wire_result_bool()];
+        constraints.push(result_bool - most_significant_diff_bits[self.chunk_bits()]);
+        Ok(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ComparisonGateConstrainer;
+    use crate::chip::plonk::gates::gate_test::{custom_gate_satisfied, test_custom_gate};
+    use plonky2::field::{
+        extension::quadratic::QuadraticExtension,
+        goldilocks_field::GoldilocksField,
+        types::{Field, PrimeField64, Sample},
+    };
+    use plonky2_u32::gates::comparison::ComparisonGate;
+    use rand::Rng;
+
+    type F = GoldilocksField;
+    type FE = QuadraticExtension<F>;
+
+    fn generate_wires(gate: &ComparisonGateConstrainer, first: u64, second: u64) -> Vec<FE> {
+        let mut wires = FE::rand_vec(gate.num_wires());
+        let mut set = |wire: usize, value: F| {
+            wires[wire] = FE::from_basefield(value);
+        };
+        let chunk_bits = gate.chunk_bits();
+        let chunk_mask = (1 << chunk_bits) - 1;
+        set(gate.wire_first_input(), F::from_canonical_u64(first));
+        set(gate.wire_second_input(), F::from_canonical_u64(second));
+        set(gate.wire_result_bool(), F::from_bool(first <= second));
+
+        let mut most_significant_diff_so_far = F::ZERO;
+        for i in 0..gate.num_chunks {
+            let first_chunk = F::from_canonical_u64((first >> (chunk_bits * i)) & chunk_mask);
+            let second_chunk = F::from_canonical_u64((second >> (chunk_bits * i)) & chunk_mask);
+            set(gate.wire_first_chunk_val(i), first_chunk);
+            set(gate.wire_second_chunk_val(i), second_chunk);
+
+            let difference = second_chunk - first_chunk;
+            let chunks_equal = difference == F::ZERO;
+            set(gate.wire_chunks_equal(i), F::from_bool(chunks_equal));
+            set(
+                gate.wire_equality_dummy(i),
+                difference.try_inverse().unwrap_or(F::ZERO),
+            );
+
+            let intermediate_value = if chunks_equal {
+                most_significant_diff_so_far
+            } else {
+                F::ZERO
+            };
+            set(gate.wire_intermediate_value(i), intermediate_value);
+            if !chunks_equal {
+                most_significant_diff_so_far = difference;
+            }
+        }
+
+        set(
+            gate.wire_most_significant_diff(),
+            most_significant_diff_so_far,
+        );
+        let shifted_diff = (F::from_canonical_u64(1 << chunk_bits) + most_significant_diff_so_far)
+            .to_canonical_u64();
+        for i in 0..chunk_bits + 1 {
+            set(
+                gate.wire_most_significant_diff_bit(i),
+                F::from_canonical_u64((shifted_diff >> i) & 1),
+            );
+        }
+        wires
+    }
+
+    #[test]
+    fn test_comparison_gate() {
+        let gate = ComparisonGateConstrainer {
+            num_bits: 16,
+            num_chunks: 4,
+        };
+        let mut rng = rand::thread_rng();
+        let num_constraints = gate.num_constraints();
+        let first = rng.gen_range(0..1 << gate.num_bits);
+        let second = rng.gen_range(0..1 << gate.num_bits);
+        for (first, second) in [(first, second), (second, first), (first, first)] {
+            let wires = generate_wires(&gate, first, second);
+            assert!(custom_gate_satisfied(
+                gate.clone(),
+                &wires,
+                num_constraints,
+                17
+            ));
+
+            let mut wrong_wires = wires;
+            wrong_wires[gate.wire_result_bool()] = FE::from_bool(first > second);
+            assert!(!custom_gate_satisfied(
+                gate.clone(),
+                &wrong_wires,
+                num_constraints,
+                17
+            ));
+        }
+    }
+
+    #[test]
+    fn test_comparison_gate_against_plonky2() {
+        let plonky2_gate = ComparisonGate::new(16, 4);
+        let halo2_gate = ComparisonGateConstrainer {
+            num_bits: plonky2_gate.num_bits,
+            num_chunks: plonky2_gate.num_chunks,
+        };
+        test_custom_gate(plonky2_gate, halo2_gate, 17);
+    }
+}
@@ -1,4 +1,8 @@
 use halo2_proofs::halo2curves::ff::PrimeField;
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::{
@@ -7,7 +11,10 @@ use crate::{
     context::RegionCtx,
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 /// A gate which takes a single constant parameter and outputs that value.
 #[derive(Copy, Clone, Debug)]
@@ -15,16 +22,20 @@ pub struct ConstantGateConstrainer {
     pub(crate) num_consts: usize,
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for ConstantGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for ConstantGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("ConstantGate").with_param("num_consts", self.num_consts)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         main_gate_config: &GoldilocksChipConfig<F>,
-        local_constants: &[crate::types::assigned::AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[crate::types::assigned::AssignedExtensionFieldValue<F, 2>],
+        local_constants: &[crate::types::assigned::AssignedExtensionFieldValue<F, D>],
+        local_wires: &[crate::types::assigned::AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &crate::types::assigned::AssignedHashValues<F>,
     ) -> Result<
-        Vec<crate::types::assigned::AssignedExtensionFieldValue<F, 2>>,
+        Vec<crate::types::assigned::AssignedExtensionFieldValue<F, D>>,
         halo2_proofs::plonk::Error,
     > {
         let goldilocks_extension_chip = GoldilocksExtensionChip::new(main_gate_config);
@@ -35,6 +46,18 @@ impl<F: PrimeField> CustomGateConstrainer<F> for ConstantGateConstrainer {
             })
             .collect()
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        Ok((0..self.num_consts)
+            .map(|i| vars.local_constants[i] - vars.local_wires[i])
+            .collect())
+    }
 }
 
 // #[cfg(test)]
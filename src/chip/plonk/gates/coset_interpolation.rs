@@ -0,0 +1,411 @@
+use std::ops::Range;
+
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{
+        extension::{algebra::ExtensionAlgebra, Extendable, FieldExtension},
+        goldilocks_field::GoldilocksField,
+        interpolation::barycentric_weights,
+        types::Field,
+    },
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::{
+        goldilocks_chip::GoldilocksChipConfig,
+        goldilocks_extension_algebra_chip::{
+            AssignedExtensionAlgebra, GoldilocksExtensionAlgebraChip,
+        },
+    },
+    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+};
+
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// Interpolates a polynomial, whose values are given on a coset of a multiplicative subgroup, and
+/// evaluates it at a point. The evaluation uses the barycentric formula, split into chunks of
+/// `degree` points whose partial results are stored in intermediate wires.
+#[derive(Clone, Debug)]
+pub struct CosetInterpolationGateConstrainer<const D: usize> {
+    pub subgroup_bits: usize,
+    pub degree: usize,
+    pub barycentric_weights: Vec<GoldilocksField>,
+}
+
+impl<const D: usize> CosetInterpolationGateConstrainer<D> {
+    pub fn new(subgroup_bits: usize, degree: usize) -> Self {
+        let points = GoldilocksField::two_adic_subgroup(subgroup_bits)
+            .into_iter()
+            .map(|x| (x, GoldilocksField::ZERO))
+            .collect::<Vec<_>>();
+        Self {
+            subgroup_bits,
+            degree,
+            barycentric_weights: barycentric_weights(&points),
+        }
+    }
+
+    fn num_points(&self) -> usize {
+        1 << self.subgroup_bits
+    }
+
+    /// Wire index of the coset shift.
+    fn wire_shift(&self) -> usize {
+        0
+    }
+
+    fn start_values(&self) -> usize {
+        1
+    }
+
+    /// Wire indices of the `i`th interpolant value.
+    fn wires_value(&self, i: usize) -> Range<usize> {
+        debug_assert!(i < self.num_points());
+        let start = self.start_values() + i * D;
+        start..start + D
+    }
+
+    fn start_evaluation_point(&self) -> usize {
+        self.start_values() + self.num_points() * D
+    }
+
+    /// Wire indices of the point to evaluate the interpolant at.
+    fn wires_evaluation_point(&self) -> Range<usize> {
+        let start = self.start_evaluation_point();
+        start..start + D
+    }
+
+    fn start_evaluation_value(&self) -> usize {
+        self.start_evaluation_point() + D
+    }
+
+    /// Wire indices of the interpolated value.
+    fn wires_evaluation_value(&self) -> Range<usize> {
+        let start = self.start_evaluation_value();
+        start..start + D
+    }
+
+    fn start_intermediates(&self) -> usize {
+        self.start_evaluation_value() + D
+    }
+
+    fn num_intermediates(&self) -> usize {
+        (self.num_points() - 2) / (self.degree - 1)
+    }
+
+    /// The wires corresponding to the i'th intermediate evaluation.
+    fn wires_intermediate_eval(&self, i: usize) -> Range<usize> {
+        debug_assert!(i < self.num_intermediates());
+        let start = self.start_intermediates() + D * i;
+        start..start + D
+    }
+
+    /// The wires corresponding to the i'th intermediate product.
+    fn wires_intermediate_prod(&self, i: usize) -> Range<usize> {
+        debug_assert!(i < self.num_intermediates());
+        let start = self.start_intermediates() + D * (self.num_intermediates() + i);
+        start..start + D
+    }
+
+    /// Wire indices of the shifted point to evaluate the interpolant at.
+    fn wires_shifted_evaluation_point(&self) -> Range<usize> {
+        let start = self.start_intermediates() + 2 * D * self.num_intermediates();
+        start..start + D
+    }
+
+    fn num_constraints(&self) -> usize {
+        D + D + 2 * D * self.num_intermediates()
+    }
+
+    /// Continues the barycentric evaluation at `point` from `(eval, prod)` over the domain points
+    /// in `range`. Returns the partial evaluation and product.
+    fn partial_interpolate<F: PrimeField>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_extension_algebra_chip: &GoldilocksExtensionAlgebraChip<F, D>,
+        values: &[AssignedExtensionAlgebra<F, D>],
+        point: &AssignedExtensionAlgebra<F, D>,
+        range: Range<usize>,
+        (mut eval, mut prod): (
+            AssignedExtensionAlgebra<F, D>,
+            AssignedExtensionAlgebra<F, D>,
+        ),
+    ) -> Result<
+        (
+            AssignedExtensionAlgebra<F, D>,
+            AssignedExtensionAlgebra<F, D>,
+        ),
+        Error,
+    > {
+        let goldilocks_extension_chip =
+            goldilocks_extension_algebra_chip.goldilocks_extension_chip();
+        let domain = GoldilocksField::two_adic_subgroup(self.subgroup_bits);
+        for i in range {
+            let (x, value, weight) = (domain[i], &values[i], self.barycentric_weights[i]);
+            // `point - x`, where `x` is embedded in the first component of the algebra.
+            let x = goldilocks_extension_chip.constant_extension_from_base(ctx, x)?;
+            let mut point_minus_x = point.clone();
+            point_minus_x.0[0] = goldilocks_extension_chip.sub_extension(ctx, &point.0[0], &x)?;
+            let weighted_value = value
+                .0
+                .iter()
+                .map(|v| goldilocks_extension_chip.scalar_mul(ctx, v, weight))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let weighted_value = AssignedExtensionAlgebra(weighted_value.try_into().unwrap());
+            let term =
+                goldilocks_extension_algebra_chip.mul_ext_algebra(ctx, &weighted_value, &prod)?;
+            eval = goldilocks_extension_algebra_chip.mul_add_ext_algebra(
+                ctx,
+                &eval,
+                &point_minus_x,
+                &term,
+            )?;
+            prod = goldilocks_extension_algebra_chip.mul_ext_algebra(ctx, &prod, &point_minus_x)?;
+        }
+        Ok((eval, prod))
+    }
+
+    /// Same as `partial_interpolate`, evaluated natively.
+    fn partial_interpolate_native(
+        &self,
+        values: &[ExtensionAlgebra<<GoldilocksField as Extendable<D>>::Extension, D>],
+        point: ExtensionAlgebra<<GoldilocksField as Extendable<D>>::Extension, D>,
+        range: Range<usize>,
+        (mut eval, mut prod): (
+            ExtensionAlgebra<<GoldilocksField as Extendable<D>>::Extension, D>,
+            ExtensionAlgebra<<GoldilocksField as Extendable<D>>::Extension, D>,
+        ),
+    ) -> (
+        ExtensionAlgebra<<GoldilocksField as Extendable<D>>::Extension, D>,
+        ExtensionAlgebra<<GoldilocksField as Extendable<D>>::Extension, D>,
+    )
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let domain = GoldilocksField::two_adic_subgroup(self.subgroup_bits);
+        for i in range {
+            let (x, value, weight) = (domain[i], values[i], self.barycentric_weights[i]);
+            let mut point_minus_x = point;
+            point_minus_x.0[0] -= <GoldilocksField as Extendable<D>>::Extension::from_basefield(x);
+            let weighted_value = value
+                .scalar_mul(<GoldilocksField as Extendable<D>>::Extension::from_basefield(weight));
+            eval = eval * point_minus_x + weighted_value * prod;
+            prod = prod * point_minus_x;
+        }
+        (eval, prod)
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D>
+    for CosetInterpolationGateConstrainer<D>
+{
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("CosetInterpolationGate")
+            .with_param("subgroup_bits", self.subgroup_bits)
+            .with_param("degree", self.degree)
+            .with_param("D", D)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+        let goldilocks_extension_algebra_chip =
+            self.goldilocks_extension_algebra_chip(goldilocks_chip_config);
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        let shift = &local_wires[self.wire_shift()];
+        let evaluation_point =
+            self.get_local_ext_algebra(local_wires, self.wires_evaluation_point());
+        let shifted_evaluation_point =
+            self.get_local_ext_algebra(local_wires, self.wires_shifted_evaluation_point());
+        let evaluation_value =
+            self.get_local_ext_algebra(local_wires, self.wires_evaluation_value());
+        let values = (0..self.num_points())
+            .map(|i| self.get_local_ext_algebra(local_wires, self.wires_value(i)))
+            .collect::<Vec<_>>();
+
+        // The evaluation point is the shifted point times the coset shift.
+        let computed_evaluation_point = goldilocks_extension_algebra_chip.scalar_mul_ext_algebra(
+            ctx,
+            shift,
+            &shifted_evaluation_point,
+        )?;
+        constraints.push(goldilocks_extension_algebra_chip.sub_ext_algebra(
+            ctx,
+            &evaluation_point,
+            &computed_evaluation_point,
+        )?);
+
+        let zero = goldilocks_extension_algebra_chip.zero_ext_algebra(ctx)?;
+        let one = goldilocks_extension_algebra_chip
+            .convert_to_ext_algebra(ctx, &goldilocks_extension_chip.one_extension(ctx)?)?;
+        let (mut computed_eval, mut computed_prod) = self.partial_interpolate(
+            ctx,
+            &goldilocks_extension_algebra_chip,
+            &values,
+            &shifted_evaluation_point,
+            0..self.degree,
+            (zero, one),
+        )?;
+
+        for i in 0..self.num_intermediates() {
+            let intermediate_eval =
+                self.get_local_ext_algebra(local_wires, self.wires_intermediate_eval(i));
+            let intermediate_prod =
+                self.get_local_ext_algebra(local_wires, self.wires_intermediate_prod(i));
+            constraints.push(goldilocks_extension_algebra_chip.sub_ext_algebra(
+                ctx,
+                &intermediate_eval,
+                &computed_eval,
+            )?);
+            constraints.push(goldilocks_extension_algebra_chip.sub_ext_algebra(
+                ctx,
+                &intermediate_prod,
+                &computed_prod,
+            )?);
+
+            let start_index = 1 + (self.degree - 1) * (i + 1);
+            let end_index = (start_index + self.degree - 1).min(self.num_points());
+            (computed_eval, computed_prod) = self.partial_interpolate(
+                ctx,
+                &goldilocks_extension_algebra_chip,
+                &values,
+                &shifted_evaluation_point,
+                start_index..end_index,
+                (intermediate_eval, intermediate_prod),
+            )?;
+        }
+
+        constraints.push(goldilocks_extension_algebra_chip.sub_ext_algebra(
+            ctx,
+            &evaluation_value,
+            &computed_eval,
+        )?);
+
+        Ok(constraints
+            .into_iter()
+            .flat_map(|alg| alg.to_ext_array())
+            .collect())
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        let shift = vars.local_wires[self.wire_shift()];
+        let evaluation_point = vars.get_local_ext_algebra(self.wires_evaluation_point());
+        let shifted_evaluation_point =
+            vars.get_local_ext_algebra(self.wires_shifted_evaluation_point());
+        let evaluation_value = vars.get_local_ext_algebra(self.wires_evaluation_value());
+        let values = (0..self.num_points())
+            .map(|i| vars.get_local_ext_algebra(self.wires_value(i)))
+            .collect::<Vec<_>>();
+
+        constraints.push(evaluation_point - shifted_evaluation_point.scalar_mul(shift));
+
+        let (mut computed_eval, mut computed_prod) = self.partial_interpolate_native(
+            &values,
+            shifted_evaluation_point,
+            0..self.degree,
+            (ExtensionAlgebra::ZERO, ExtensionAlgebra::one()),
+        );
+        for i in 0..self.num_intermediates() {
+            let intermediate_eval = vars.get_local_ext_algebra(self.wires_intermediate_eval(i));
+            let intermediate_prod = vars.get_local_ext_algebra(self.wires_intermediate_prod(i));
+            constraints.push(intermediate_eval - computed_eval);
+            constraints.push(intermediate_prod - computed_prod);
+
+            let start_index = 1 + (self.degree - 1) * (i + 1);
+            let end_index = (start_index + self.degree - 1).min(self.num_points());
+            (computed_eval, computed_prod) = self.partial_interpolate_native(
+                &values,
+                shifted_evaluation_point,
+                start_index..end_index,
+                (intermediate_eval, intermediate_prod),
+            );
+        }
+        constraints.push(evaluation_value - computed_eval);
+
+        Ok(constraints
+            .into_iter()
+            .flat_map(|alg| alg.to_basefield_array())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CosetInterpolationGateConstrainer;
+    use crate::chip::plonk::gates::{
+        gate_test::{test_custom_gate, test_custom_gate_ref},
+        registry::GateDescriptor,
+    };
+    use plonky2::{
+        field::goldilocks_field::GoldilocksField,
+        gates::coset_interpolation::CosetInterpolationGate,
+        plonk::{
+            circuit_builder::CircuitBuilder, circuit_data::CircuitConfig,
+            config::PoseidonGoldilocksConfig,
+        },
+    };
+
+    #[test]
+    fn test_coset_interpolation_gate() {
+        for subgroup_bits in 1..=3 {
+            let plonky2_gate = CosetInterpolationGate::<GoldilocksField, 2>::new(subgroup_bits);
+            let halo2_gate =
+                CosetInterpolationGateConstrainer::new(subgroup_bits, 1 << subgroup_bits);
+            test_custom_gate(plonky2_gate, halo2_gate, 17);
+        }
+    }
+
+    #[test]
+    fn test_coset_interpolation_gate_with_intermediates() {
+        // The recursive FRI verifier bounds the degree of the gate, which splits the
+        // interpolation over intermediate wires.
+        let config = CircuitConfig::standard_recursion_config();
+        assert!(config.use_interpolation_gate);
+        let inner_data = {
+            let mut builder = CircuitBuilder::<GoldilocksField, 2>::new(config.clone());
+            let x = builder.add_virtual_public_input();
+            let y = builder.mul(x, x);
+            builder.register_public_input(y);
+            builder.build::<PoseidonGoldilocksConfig>()
+        };
+        let mut builder = CircuitBuilder::<GoldilocksField, 2>::new(config);
+        let proof_t = builder.add_virtual_proof_with_pis(&inner_data.common);
+        let vd = builder.constant_verifier_data(&inner_data.verifier_only);
+        builder.verify_proof::<PoseidonGoldilocksConfig>(&proof_t, &vd, &inner_data.common);
+        let data = builder.build::<PoseidonGoldilocksConfig>();
+
+        let plonky2_gate = data
+            .common
+            .gates
+            .iter()
+            .find(|gate| gate.0.id().starts_with("CosetInterpolationGate"))
+            .unwrap();
+        let descriptor = GateDescriptor::parse(&plonky2_gate.0.id()).unwrap();
+        let halo2_gate = CosetInterpolationGateConstrainer::new(
+            descriptor.param("subgroup_bits").unwrap(),
+            descriptor.param("degree").unwrap(),
+        );
+        assert!(halo2_gate.num_intermediates() > 0);
+        test_custom_gate_ref(plonky2_gate.0.as_ref(), halo2_gate, 17);
+    }
+}
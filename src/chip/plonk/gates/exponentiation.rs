@@ -0,0 +1,163 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+};
+
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// A gate for raising a value to a power.
+#[derive(Clone, Debug)]
+pub struct ExponentiationGateConstrainer {
+    pub num_power_bits: usize,
+}
+
+impl ExponentiationGateConstrainer {
+    pub fn wire_base(&self) -> usize {
+        0
+    }
+
+    /// The `i`th bit of the exponent, in little-endian order.
+    pub fn wire_power_bit(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_power_bits);
+        1 + i
+    }
+
+    pub fn wire_output(&self) -> usize {
+        1 + self.num_power_bits
+    }
+
+    pub fn wire_intermediate_value(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_power_bits);
+        2 + self.num_power_bits + i
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_power_bits + 1
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for ExponentiationGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("ExponentiationGate")
+            .with_param("num_power_bits", self.num_power_bits)
+            .with_param("D", D)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+        let one = goldilocks_extension_chip.one_extension(ctx)?;
+        let base = &local_wires[self.wire_base()];
+        let power_bits = (0..self.num_power_bits)
+            .map(|i| &local_wires[self.wire_power_bit(i)])
+            .collect::<Vec<_>>();
+        let intermediate_values = (0..self.num_power_bits)
+            .map(|i| &local_wires[self.wire_intermediate_value(i)])
+            .collect::<Vec<_>>();
+        let output = &local_wires[self.wire_output()];
+
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_power_bits {
+            let prev_intermediate_value = if i == 0 {
+                one.clone()
+            } else {
+                goldilocks_extension_chip.square_extension(ctx, intermediate_values[i - 1])?
+            };
+
+            // power_bits is in LE order, but we accumulate in BE order.
+            let cur_bit = power_bits[self.num_power_bits - i - 1];
+            let not_cur_bit = goldilocks_extension_chip.sub_extension(ctx, &one, cur_bit)?;
+            let multiplier =
+                goldilocks_extension_chip.mul_add_extension(ctx, cur_bit, base, &not_cur_bit)?;
+            let computed_intermediate_value = goldilocks_extension_chip.mul_extension(
+                ctx,
+                &prev_intermediate_value,
+                &multiplier,
+            )?;
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &computed_intermediate_value,
+                intermediate_values[i],
+            )?);
+        }
+
+        constraints.push(goldilocks_extension_chip.sub_extension(
+            ctx,
+            output,
+            intermediate_values[self.num_power_bits - 1],
+        )?);
+
+        Ok(constraints)
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let one = <GoldilocksField as Extendable<D>>::Extension::ONE;
+        let base = vars.local_wires[self.wire_base()];
+        let power_bits = (0..self.num_power_bits)
+            .map(|i| vars.local_wires[self.wire_power_bit(i)])
+            .collect::<Vec<_>>();
+        let intermediate_values = (0..self.num_power_bits)
+            .map(|i| vars.local_wires[self.wire_intermediate_value(i)])
+            .collect::<Vec<_>>();
+        let output = vars.local_wires[self.wire_output()];
+
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_power_bits {
+            let prev_intermediate_value = if i == 0 {
+                one
+            } else {
+                intermediate_values[i - 1].square()
+            };
+
+            // power_bits is in LE order, but we accumulate in BE order.
+            let cur_bit = power_bits[self.num_power_bits - i - 1];
+            let computed_intermediate_value =
+                prev_intermediate_value * (cur_bit * base + one - cur_bit);
+            constraints.push(computed_intermediate_value - intermediate_values[i]);
+        }
+        constraints.push(output - intermediate_values[self.num_power_bits - 1]);
+        Ok(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExponentiationGateConstrainer;
+    use crate::chip::plonk::gates::gate_test::test_custom_gate;
+    use plonky2::{
+        field::goldilocks_field::GoldilocksField, gates::exponentiation::ExponentiationGate,
+    };
+
+    #[test]
+    fn test_exponentiation_gate() {
+        for num_power_bits in [1, 5, 16] {
+            let plonky2_gate = ExponentiationGate::<GoldilocksField, 2>::new(num_power_bits);
+            let halo2_gate = ExponentiationGateConstrainer {
+                num_power_bits: plonky2_gate.num_power_bits,
+            };
+            test_custom_gate(plonky2_gate, halo2_gate, 17);
+        }
+    }
+}
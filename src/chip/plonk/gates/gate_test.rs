@@ -6,9 +6,9 @@ use halo2_proofs::{
     plonk::{Circuit, ConstraintSystem},
 };
 use plonky2::{
-    field::types::Sample,
+    field::types::{Field, Sample},
     field::{
-        extension::{quadratic::QuadraticExtension, Extendable},
+        extension::{quadratic::QuadraticExtension, Extendable, FieldExtension},
         goldilocks_field::GoldilocksField,
         types::PrimeField64,
     },
@@ -26,36 +26,40 @@ use crate::{
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-const D: usize = 2;
 type F = GoldilocksField;
-type FE = <GoldilocksField as Extendable<D>>::Extension;
+type FE = QuadraticExtension<F>;
 
 #[derive(Clone)]
-struct TestCircuit<'a, Gate: CustomGateConstrainer<Fr>> {
+struct TestCircuit<'a, Gate: CustomGateConstrainer<Fr, D>, const D: usize>
+where
+    F: Extendable<D>,
+{
     gate: Gate,
     evaluation_vars: EvaluationVars<'a, F, D>,
-    output: Vec<QuadraticExtension<F>>,
+    output: Vec<<F as Extendable<D>>::Extension>,
 }
 
 fn goldilocks_to_fr(x: GoldilocksField) -> Fr {
     Fr::from(x.to_canonical_u64())
 }
 
-fn assign_quadratic_extensions(
+fn assign_extensions<const D: usize>(
     ctx: &mut RegionCtx<'_, Fr>,
     goldilocks_chip: &GoldilocksChip<Fr>,
-    input: &[QuadraticExtension<F>],
-) -> Vec<AssignedExtensionFieldValue<Fr, 2>> {
+    input: &[<F as Extendable<D>>::Extension],
+) -> Vec<AssignedExtensionFieldValue<Fr, D>>
+where
+    F: Extendable<D>,
+{
     input
         .iter()
         .map(|x| {
-            let a_assigned = goldilocks_chip
-                .assign_value(ctx, Value::known(goldilocks_to_fr(x.0[0])))
-                .unwrap();
-            let b_assigned = goldilocks_chip
-                .assign_value(ctx, Value::known(goldilocks_to_fr(x.0[1])))
-                .unwrap();
-            AssignedExtensionFieldValue([a_assigned, b_assigned])
+            let assigned = x.to_basefield_array().map(|x| {
+                goldilocks_chip
+                    .assign_value(ctx, Value::known(goldilocks_to_fr(x)))
+                    .unwrap()
+            });
+            AssignedExtensionFieldValue(assigned)
         })
         .collect::<Vec<_>>()
 }
@@ -79,7 +83,11 @@ fn assign_hash_values(
     }
 }
 
-impl<'a, Gate: CustomGateConstrainer<Fr>> Circuit<Fr> for TestCircuit<'a, Gate> {
+impl<'a, Gate: CustomGateConstrainer<Fr, D>, const D: usize> Circuit<Fr>
+    for TestCircuit<'a, Gate, D>
+where
+    F: Extendable<D>,
+{
     type Config = GoldilocksChipConfig<Fr>;
 
     type FloorPlanner = SimpleFloorPlanner;
@@ -105,12 +113,12 @@ impl<'a, Gate: CustomGateConstrainer<Fr>> Circuit<Fr> for TestCircuit<'a, Gate>
             || "",
             |region| {
                 let mut ctx = RegionCtx::new(region, 0);
-                let local_constants = assign_quadratic_extensions(
+                let local_constants = assign_extensions::<D>(
                     &mut ctx,
                     &goldilocks_chip,
                     self.evaluation_vars.local_constants,
                 );
-                let local_wires = assign_quadratic_extensions(
+                let local_wires = assign_extensions::<D>(
                     &mut ctx,
                     &goldilocks_chip,
                     self.evaluation_vars.local_wires,
@@ -128,19 +136,16 @@ impl<'a, Gate: CustomGateConstrainer<Fr>> Circuit<Fr> for TestCircuit<'a, Gate>
                     &public_inputs_hash,
                 )?;
                 let output_expected =
-                    assign_quadratic_extensions(&mut ctx, &goldilocks_chip, &self.output);
+                    assign_extensions::<D>(&mut ctx, &goldilocks_chip, &self.output);
 
                 assert_eq!(output.len(), output_expected.len());
                 output
                     .iter()
                     .zip(output_expected.iter())
                     .for_each(|(a, b)| {
-                        goldilocks_chip
-                            .assert_equal(&mut ctx, &a.0[0], &b.0[0])
-                            .unwrap();
-                        goldilocks_chip
-                            .assert_equal(&mut ctx, &a.0[1], &b.0[1])
-                            .unwrap();
+                        for (a, b) in a.0.iter().zip(b.0.iter()) {
+                            goldilocks_chip.assert_equal(&mut ctx, a, b).unwrap();
+                        }
                     });
                 println!("final ctx:{}", ctx.offset());
                 Ok(())
@@ -151,20 +156,59 @@ impl<'a, Gate: CustomGateConstrainer<Fr>> Circuit<Fr> for TestCircuit<'a, Gate>
     }
 }
 
-pub fn test_custom_gate<PG: Gate<F, D>, HG: CustomGateConstrainer<Fr>>(
+pub fn test_custom_gate<PG: Gate<F, 2>, HG: CustomGateConstrainer<Fr, 2>>(
     plonky2_gate: PG,
     halo2_gate: HG,
     k: u32,
 ) {
-    let wires = FE::rand_vec(plonky2_gate.num_wires());
-    let constants = FE::rand_vec(plonky2_gate.num_constants());
+    test_custom_gate_ref(&plonky2_gate, halo2_gate, k);
+}
+
+/// Same as `test_custom_gate`, for gates taken from the `GateRef`s of a built circuit.
+pub fn test_custom_gate_ref<HG: CustomGateConstrainer<Fr, 2>>(
+    plonky2_gate: &dyn Gate<F, 2>,
+    halo2_gate: HG,
+    k: u32,
+) {
+    test_custom_gate_ref_with_degree::<HG, 2>(plonky2_gate, halo2_gate, k);
+}
+
+/// Same as `test_custom_gate`, over the degree `D` extension field.
+pub fn test_custom_gate_with_degree<
+    PG: Gate<F, D>,
+    HG: CustomGateConstrainer<Fr, D>,
+    const D: usize,
+>(
+    plonky2_gate: PG,
+    halo2_gate: HG,
+    k: u32,
+) where
+    F: Extendable<D>,
+{
+    test_custom_gate_ref_with_degree::<HG, D>(&plonky2_gate, halo2_gate, k);
+}
+
+/// Same as `test_custom_gate_ref`, over the degree `D` extension field.
+pub fn test_custom_gate_ref_with_degree<HG: CustomGateConstrainer<Fr, D>, const D: usize>(
+    plonky2_gate: &dyn Gate<F, D>,
+    halo2_gate: HG,
+    k: u32,
+) where
+    F: Extendable<D>,
+{
+    let wires = <F as Extendable<D>>::Extension::rand_vec(plonky2_gate.num_wires());
+    let constants = <F as Extendable<D>>::Extension::rand_vec(plonky2_gate.num_constants());
     let public_inputs_hash = HashOut::<F>::rand();
     let evaluation_vars = EvaluationVars::<F, D> {
         local_constants: &constants,
         local_wires: &wires,
         public_inputs_hash: &public_inputs_hash,
     };
-    let output: Vec<QuadraticExtension<F>> = plonky2_gate.eval_unfiltered(evaluation_vars);
+    let output = plonky2_gate.eval_unfiltered(evaluation_vars);
+    assert_eq!(
+        halo2_gate.eval_unfiltered_native(evaluation_vars).unwrap(),
+        output
+    );
     let circuit = TestCircuit {
         gate: halo2_gate,
         evaluation_vars,
@@ -174,3 +218,34 @@ pub fn test_custom_gate<PG: Gate<F, D>, HG: CustomGateConstrainer<Fr>>(
         .unwrap()
         .assert_satisfied();
 }
+
+/// Checks `halo2_gate` against wires generated natively. Unlike the random wires of
+/// `test_custom_gate`, they satisfy the gate, so the tests also cover wires computed by a witness
+/// generator. Returns whether all `num_constraints` constraints vanish, after checking that the
+/// native evaluation agrees.
+pub fn custom_gate_satisfied<HG: CustomGateConstrainer<Fr, 2>>(
+    halo2_gate: HG,
+    wires: &[FE],
+    num_constraints: usize,
+    k: u32,
+) -> bool {
+    let public_inputs_hash = HashOut::<F>::rand();
+    let evaluation_vars = EvaluationVars::<F, 2> {
+        local_constants: &[],
+        local_wires: wires,
+        public_inputs_hash: &public_inputs_hash,
+    };
+    let native_output = halo2_gate.eval_unfiltered_native(evaluation_vars).unwrap();
+    assert_eq!(native_output.len(), num_constraints);
+    let circuit = TestCircuit {
+        gate: halo2_gate,
+        evaluation_vars,
+        output: vec![FE::ZERO; num_constraints],
+    };
+    let satisfied = MockProver::run(k, &circuit, vec![vec![]])
+        .unwrap()
+        .verify()
+        .is_ok();
+    assert_eq!(native_output.iter().all(|c| c.is_zero()), satisfied);
+    satisfied
+}
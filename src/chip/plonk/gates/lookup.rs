@@ -0,0 +1,68 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::{
+        assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+        common_data::CircuitConfig,
+    },
+};
+
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// A gate which stores `(input, output)` pairs looked up in a lookup table. The gate itself has
+/// no constraints: its slots are checked by the lookup argument, see `check_lookup_constraints`.
+#[derive(Clone, Debug)]
+pub struct LookupGateConstrainer {
+    pub num_slots: usize,
+}
+
+impl LookupGateConstrainer {
+    pub fn num_slots(config: &CircuitConfig) -> usize {
+        let wires_per_lookup = 2;
+        config.num_routed_wires / wires_per_lookup
+    }
+
+    pub fn wire_ith_looking_inp(i: usize) -> usize {
+        2 * i
+    }
+
+    pub fn wire_ith_looking_out(i: usize) -> usize {
+        2 * i + 1
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for LookupGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("LookupGate").with_param("num_slots", self.num_slots)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        _ctx: &mut RegionCtx<'_, F>,
+        _goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        _local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        Ok(vec![])
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        _vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        Ok(vec![])
+    }
+}
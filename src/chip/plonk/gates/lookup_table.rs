@@ -0,0 +1,75 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::{
+        assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+        common_data::CircuitConfig,
+    },
+};
+
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// A gate which stores the `(input, output)` pairs of a lookup table, along with the number of
+/// times each pair is looked up. Like `LookupGate`, it is only checked by the lookup argument.
+#[derive(Clone, Debug)]
+pub struct LookupTableGateConstrainer {
+    pub num_slots: usize,
+    pub last_lut_row: usize,
+}
+
+impl LookupTableGateConstrainer {
+    pub fn num_slots(config: &CircuitConfig) -> usize {
+        let wires_per_entry = 3;
+        config.num_routed_wires / wires_per_entry
+    }
+
+    pub fn wire_ith_looked_inp(i: usize) -> usize {
+        3 * i
+    }
+
+    pub fn wire_ith_looked_out(i: usize) -> usize {
+        3 * i + 1
+    }
+
+    pub fn wire_ith_multiplicity(i: usize) -> usize {
+        3 * i + 2
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for LookupTableGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("LookupTableGate")
+            .with_param("num_slots", self.num_slots)
+            .with_param("last_lut_row", self.last_lut_row)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        _ctx: &mut RegionCtx<'_, F>,
+        _goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        _local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        Ok(vec![])
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        _vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        Ok(vec![])
+    }
+}
@@ -3,21 +3,13 @@ use std::ops::Range;
 use crate::context::RegionCtx;
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::plonk::Error;
+use plonky2::field::extension::Extendable;
 use plonky2::field::types::Field;
+use plonky2::plonk::vars::EvaluationVars;
 use plonky2::{field::goldilocks_field::GoldilocksField, gates::gate::GateRef};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 
-use self::arithmetic_extension::ArithmeticExtensionGateConstrainer;
-use self::base_sum::BaseSumGateConstrainer;
-use self::multiplication_extension::MulExtensionGateConstrainer;
-use self::poseidon::PoseidonGateConstrainer;
-use self::poseidon_mds::PoseidonMDSGateConstrainer;
-use self::random_access::RandomAccessGateConstrainer;
-use self::reducing::ReducingGateConstrainer;
-use self::reducing_extension::ReducingExtensionGateConstrainer;
-use self::{
-    arithmetic::ArithmeticGateConstrainer, constant::ConstantGateConstrainer,
-    noop::NoopGateConstrainer, public_input::PublicInputGateConstrainer,
-};
+use self::registry::{CustomGateRegistry, GateDescriptor, GateRegistryError};
 
 use crate::chip::goldilocks_chip::GoldilocksChipConfig;
 use crate::chip::goldilocks_extension_algebra_chip::{
@@ -29,31 +21,41 @@ use crate::types::assigned::{AssignedExtensionFieldValue, AssignedHashValues};
 /// Placeholder value to indicate that a gate doesn't use a selector polynomial.
 const UNUSED_SELECTOR: usize = u32::MAX as usize;
 
+pub mod add_many_u32;
 pub mod arithmetic;
 pub mod arithmetic_extension;
+pub mod arithmetic_u32;
 pub mod base_sum;
+pub mod comparison;
 pub mod constant;
+pub mod coset_interpolation;
+pub mod exponentiation;
+pub mod lookup;
+pub mod lookup_table;
 pub mod multiplication_extension;
 pub mod noop;
 pub mod poseidon;
 pub mod poseidon_mds;
 pub mod public_input;
 pub mod random_access;
+pub mod range_check_u32;
 pub mod reducing;
 pub mod reducing_extension;
+pub mod registry;
+pub mod subtraction_u32;
 
 pub mod gate_test;
 
 /// Evaluate custom gate constraints in `plonk_zeta` inside maingate.
-pub trait CustomGateConstrainer<F: PrimeField>:
-    CustomGateConstrainerClone<F> + Send + Sync
+pub trait CustomGateConstrainer<F: PrimeField, const D: usize>:
+    CustomGateConstrainerClone<F, D> + Send + Sync
 {
     fn get_local_ext_algebra(
         &self,
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         wire_range: Range<usize>,
-    ) -> AssignedExtensionAlgebra<F> {
-        debug_assert_eq!(wire_range.len(), 2);
+    ) -> AssignedExtensionAlgebra<F, D> {
+        debug_assert_eq!(wire_range.len(), D);
         let arr = local_wires[wire_range].to_vec().try_into().unwrap();
         AssignedExtensionAlgebra(arr)
     }
@@ -61,25 +63,35 @@ pub trait CustomGateConstrainer<F: PrimeField>:
     fn goldilocks_extension_chip(
         &self,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-    ) -> GoldilocksExtensionChip<F> {
+    ) -> GoldilocksExtensionChip<F, D> {
         GoldilocksExtensionChip::new(goldilocks_chip_config)
     }
 
     fn goldilocks_extension_algebra_chip(
         &self,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-    ) -> GoldilocksExtensionAlgebraChip<F> {
+    ) -> GoldilocksExtensionAlgebraChip<F, D> {
         GoldilocksExtensionAlgebraChip::new(goldilocks_chip_config)
     }
 
+    /// Name and parameters from which `CustomGateRegistry` rebuilds this constrainer.
+    ///
+    /// Defaults to the name of the constrainer type, which no registry rebuilds: such gates are
+    /// encoded, but decoding them fails with `GateRegistryError::UnsupportedGates`. The
+    /// constrainers of `CustomGateRegistry::default()` override it with the descriptor their
+    /// constructor parses.
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new(std::any::type_name::<Self>())
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error>;
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error>;
 
     /// In Plonky2, each custom gate's constraint is multiplied by filtering polynomial
     /// `j`th gate's constraint is filtered by f_j(x) = \prod_{k=0, k \neq j}^{n-1}(f(x) - k) where
@@ -88,14 +100,15 @@ pub trait CustomGateConstrainer<F: PrimeField>:
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        mut local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        mut local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         public_inputs_hash: &AssignedHashValues<F>,
         row: usize,
         selector_index: usize,
         group_range: Range<usize>,
         num_selectors: usize,
-        combined_gate_constraints: &mut [AssignedExtensionFieldValue<F, 2>],
+        num_lookup_selectors: usize,
+        combined_gate_constraints: &mut [AssignedExtensionFieldValue<F, D>],
     ) -> Result<(), Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         // f(\zeta)
@@ -105,19 +118,15 @@ pub trait CustomGateConstrainer<F: PrimeField>:
             .filter(|&i| i != row)
             .chain((num_selectors > 1).then_some(UNUSED_SELECTOR))
             .map(|i| {
-                let k = goldilocks_extension_chip.constant_extension(
-                    ctx,
-                    &[
-                        GoldilocksField::from_canonical_u64(i as u64),
-                        GoldilocksField::ZERO,
-                    ],
-                )?;
+                let k = goldilocks_extension_chip
+                    .constant_extension_from_base(ctx, GoldilocksField::from_canonical_usize(i))?;
                 goldilocks_extension_chip.sub_extension(ctx, &k, &f_zeta)
             })
-            .collect::<Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error>>()?;
+            .collect::<Result<Vec<AssignedExtensionFieldValue<F, D>>, Error>>()?;
         let filter = goldilocks_extension_chip.mul_many_extension(ctx, terms)?;
 
-        local_constants = &local_constants[num_selectors..];
+        // The lookup selectors come right after the gate selectors.
+        local_constants = &local_constants[num_selectors + num_lookup_selectors..];
         let gate_constraints = self.eval_unfiltered_constraint(
             ctx,
             goldilocks_chip_config,
@@ -130,93 +139,115 @@ pub trait CustomGateConstrainer<F: PrimeField>:
         }
         Ok(())
     }
+
+    /// Same constraints as `eval_unfiltered_constraint`, evaluated natively. Used by
+    /// `native_verifier`, which rejects the circuits of gates without a native evaluation.
+    fn eval_unfiltered_native(
+        &self,
+        _vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        Err(GateRegistryError::UnsupportedGates(vec![
+            self.descriptor().name,
+        ]))
+    }
+
+    /// Same as `eval_filtered_constraint`, evaluated natively.
+    fn eval_filtered_native(
+        &self,
+        mut vars: EvaluationVars<GoldilocksField, D>,
+        row: usize,
+        selector_index: usize,
+        group_range: Range<usize>,
+        num_selectors: usize,
+        num_lookup_selectors: usize,
+        combined_gate_constraints: &mut [<GoldilocksField as Extendable<D>>::Extension],
+    ) -> Result<(), GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let f_zeta = vars.local_constants[selector_index];
+        let filter = group_range
+            .filter(|&i| i != row)
+            .chain((num_selectors > 1).then_some(UNUSED_SELECTOR))
+            .fold(
+                <GoldilocksField as Extendable<D>>::Extension::ONE,
+                |acc, i| {
+                    acc * (<GoldilocksField as Extendable<D>>::Extension::from_canonical_usize(i)
+                        - f_zeta)
+                },
+            );
+
+        vars.local_constants = &vars.local_constants[num_selectors + num_lookup_selectors..];
+        let gate_constraints = self.eval_unfiltered_native(vars)?;
+        for (acc, c) in combined_gate_constraints.iter_mut().zip(gate_constraints) {
+            *acc += filter * c;
+        }
+        Ok(())
+    }
+}
+
+/// Natively computes `sum_i terms[i] * base^i`, like `GoldilocksExtensionChip::reduce_extension`.
+pub(crate) fn reduce_native<E: Field>(base: E, terms: &[E]) -> E {
+    terms
+        .iter()
+        .rev()
+        .fold(E::ZERO, |acc, &term| acc * base + term)
+}
+
+/// Natively computes `prod_{i < n} (x - i)`, which vanishes iff `x` is in `[0, n)`.
+pub(crate) fn range_check_native<E: Field>(x: E, n: usize) -> E {
+    (0..n).fold(E::ONE, |acc, i| acc * (x - E::from_canonical_usize(i)))
 }
 
 #[derive(Clone)]
-pub struct CustomGateRef<F: PrimeField>(pub Box<dyn CustomGateConstrainer<F>>);
-
-impl<F: PrimeField> From<&GateRef<GoldilocksField, 2>> for CustomGateRef<F> {
-    fn from(value: &GateRef<GoldilocksField, 2>) -> Self {
-        match value.0.id().as_str().trim_end() {
-            "ArithmeticGate { num_ops: 20 }" => Self(Box::new(ArithmeticGateConstrainer {
-                num_ops: value.0.num_ops(),
-            })),
-            "PublicInputGate" => Self(Box::new(PublicInputGateConstrainer)),
-            "NoopGate" => Self(Box::new(NoopGateConstrainer)),
-            "ConstantGate { num_consts: 2 }" => Self(Box::new(ConstantGateConstrainer {
-                num_consts: value.0.num_constants(),
-            })),
-            "BaseSumGate { num_limbs: 63 } + Base: 2" => {
-                Self(Box::new(BaseSumGateConstrainer { num_limbs: 63 }))
-            },
-            "PoseidonGate(PhantomData<plonky2_field::goldilocks_field::GoldilocksField>)<WIDTH=12>" => {
-                Self(Box::new(PoseidonGateConstrainer))
-            },
-            "PoseidonMdsGate(PhantomData<plonky2_field::goldilocks_field::GoldilocksField>)<WIDTH=12>" => {
-                Self(Box::new(PoseidonMDSGateConstrainer))
-            },
-            "RandomAccessGate { bits: 1, num_copies: 20, num_extra_constants: 0, _phantom: PhantomData<plonky2_field::goldilocks_field::GoldilocksField> }<D=2>" => {
-                Self(Box::new(RandomAccessGateConstrainer {
-                    bits: 1,
-                    num_copies: 20,
-                    num_extra_constants: 0,
-                }))
-            },
-            "RandomAccessGate { bits: 4, num_copies: 4, num_extra_constants: 2, _phantom: PhantomData<plonky2_field::goldilocks_field::GoldilocksField> }<D=2>" => {
-                Self(Box::new(RandomAccessGateConstrainer {
-                    bits: 4,
-                    num_copies: 4,
-                    num_extra_constants: 2,
-                }))
-            },
-            "ReducingExtensionGate { num_coeffs: 32 }" => {
-                Self(Box::new(ReducingExtensionGateConstrainer {
-                    num_coeffs: 32,
-                }))
-            },
-            "ReducingGate { num_coeffs: 43 }" => {
-                Self(Box::new(ReducingGateConstrainer {
-                    num_coeffs: 43,
-                }))
-            },
-            "ArithmeticExtensionGate { num_ops: 10 }" => {
-                Self(Box::new(ArithmeticExtensionGateConstrainer {
-                    num_ops: 10
-                }))
-            },
-            "MulExtensionGate { num_ops: 13 }" => {
-                Self(Box::new(MulExtensionGateConstrainer {
-                    num_ops: 13
-                }))
-            },
-            "BaseSumGate { num_limbs: 4 } + Base: 2" => {
-                Self(Box::new(BaseSumGateConstrainer {
-                    num_limbs: 4
-                }))
-            },
-            s => {
-                println!("{s}");
-                unimplemented!()
-            }
-        }
+pub struct CustomGateRef<F: PrimeField, const D: usize>(pub Box<dyn CustomGateConstrainer<F, D>>);
+
+impl<F: PrimeField, const D: usize> TryFrom<&GateRef<GoldilocksField, D>> for CustomGateRef<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    type Error = GateRegistryError;
+
+    fn try_from(value: &GateRef<GoldilocksField, D>) -> Result<Self, Self::Error> {
+        CustomGateRegistry::default().build(value)
+    }
+}
+
+/// Gates are encoded by their descriptor.
+impl<F: PrimeField, const D: usize> Serialize for CustomGateRef<F, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.descriptor().serialize(serializer)
+    }
+}
+
+/// Gates are decoded with `CustomGateRegistry::default()`.
+impl<'de, F: PrimeField, const D: usize> Deserialize<'de> for CustomGateRef<F, D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let descriptor = GateDescriptor::deserialize(deserializer)?;
+        CustomGateRegistry::default()
+            .build_from_descriptor(&descriptor)
+            .map_err(De::Error::custom)
     }
 }
 
 /// This trait is for cloning the boxed trait object.
-pub trait CustomGateConstrainerClone<F: PrimeField> {
-    fn clone_box(&self) -> Box<dyn CustomGateConstrainer<F>>;
+pub trait CustomGateConstrainerClone<F: PrimeField, const D: usize> {
+    fn clone_box(&self) -> Box<dyn CustomGateConstrainer<F, D>>;
 }
 
-impl<T, F: PrimeField> CustomGateConstrainerClone<F> for T
+impl<T, F: PrimeField, const D: usize> CustomGateConstrainerClone<F, D> for T
 where
-    T: CustomGateConstrainer<F> + Clone + 'static,
+    T: CustomGateConstrainer<F, D> + Clone + 'static,
 {
-    fn clone_box(&self) -> Box<dyn CustomGateConstrainer<F>> {
+    fn clone_box(&self) -> Box<dyn CustomGateConstrainer<F, D>> {
         Box::new(self.clone())
     }
 }
 
-impl<F: PrimeField> Clone for Box<dyn CustomGateConstrainer<F>> {
+impl<F: PrimeField, const D: usize> Clone for Box<dyn CustomGateConstrainer<F, D>> {
     fn clone(&self) -> Self {
         self.clone_box()
     }
@@ -2,43 +2,54 @@ use std::ops::Range;
 
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::goldilocks_chip::GoldilocksChipConfig,
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 /// A gate which can perform a weighted multiplication, i.e. `result = c0 x y`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
 #[derive(Debug, Clone)]
-pub struct MulExtensionGateConstrainer {
+pub struct MulExtensionGateConstrainer<const D: usize> {
     /// Number of multiplications performed by the gate.
     pub num_ops: usize,
 }
 
-impl MulExtensionGateConstrainer {
+impl<const D: usize> MulExtensionGateConstrainer<D> {
     pub fn wires_ith_multiplicand_0(i: usize) -> Range<usize> {
-        3 * 2 * i..3 * 2 * i + 2
+        3 * D * i..3 * D * i + D
     }
     pub fn wires_ith_multiplicand_1(i: usize) -> Range<usize> {
-        3 * 2 * i + 2..3 * 2 * i + 2 * 2
+        3 * D * i + D..3 * D * i + 2 * D
     }
     pub fn wires_ith_output(i: usize) -> Range<usize> {
-        3 * 2 * i + 2 * 2..3 * 2 * i + 3 * 2
+        3 * D * i + 2 * D..3 * D * i + 3 * D
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for MulExtensionGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for MulExtensionGateConstrainer<D> {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("MulExtensionGate").with_param("num_ops", self.num_ops)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
         let goldilocks_extension_algebra_chip =
             self.goldilocks_extension_algebra_chip(goldilocks_chip_config);
         let const_0 = &local_constants[0];
@@ -69,6 +80,25 @@ impl<F: PrimeField> CustomGateConstrainer<F> for MulExtensionGateConstrainer {
 
         Ok(constraints)
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let const_0 = vars.local_constants[0];
+        let mut constraints = Vec::new();
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.get_local_ext_algebra(Self::wires_ith_multiplicand_0(i));
+            let multiplicand_1 = vars.get_local_ext_algebra(Self::wires_ith_multiplicand_1(i));
+            let output = vars.get_local_ext_algebra(Self::wires_ith_output(i));
+            let computed_output = (multiplicand_0 * multiplicand_1).scalar_mul(const_0);
+            constraints.extend((output - computed_output).to_basefield_array());
+        }
+        Ok(constraints)
+    }
 }
 
 #[cfg(test)]
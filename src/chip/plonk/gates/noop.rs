@@ -1,25 +1,46 @@
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::goldilocks_chip::GoldilocksChipConfig,
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 #[derive(Clone)]
 pub struct NoopGateConstrainer;
 
-impl<F: PrimeField> CustomGateConstrainer<F> for NoopGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for NoopGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("NoopGate")
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         _ctx: &mut RegionCtx<'_, F>,
         _goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        _local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        _local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        _local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        Ok(vec![])
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        _vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
         Ok(vec![])
     }
 }
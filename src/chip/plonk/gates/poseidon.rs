@@ -1,8 +1,10 @@
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
 use plonky2::{
-    field::{goldilocks_field::GoldilocksField, types::Field},
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    gates::{gate::Gate, poseidon::PoseidonGate},
     hash::poseidon::{HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS, SPONGE_WIDTH},
+    plonk::vars::EvaluationVars,
 };
 
 use crate::{
@@ -14,7 +16,10 @@ const R_F: usize = HALF_N_FULL_ROUNDS * 2;
 const R_F_HALF: usize = R_F / 2;
 const R_P: usize = N_PARTIAL_ROUNDS;
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 /// Note that these work for the GoldilocksField field, but not necessarily others. See
 /// `generate_constants` about how these were generated. We include enough for a WIDTH of 12;
@@ -376,22 +381,19 @@ impl PoseidonGateConstrainer {
         Self::START_FULL_1 + T * round + i
     }
 
-    fn constant_layer<F: PrimeField>(
+    fn constant_layer<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        state: &mut Vec<AssignedExtensionFieldValue<F, 2>>,
+        state: &mut Vec<AssignedExtensionFieldValue<F, D>>,
         round_ctr: usize,
     ) {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         for i in 0..T {
             let c = goldilocks_extension_chip
-                .constant_extension(
+                .constant_extension_from_base(
                     ctx,
-                    &[
-                        GoldilocksField::from_canonical_u64(ALL_ROUND_CONSTANTS[i + T * round_ctr]),
-                        GoldilocksField::ZERO,
-                    ],
+                    GoldilocksField::from_canonical_u64(ALL_ROUND_CONSTANTS[i + T * round_ctr]),
                 )
                 .unwrap();
             state[i] = goldilocks_extension_chip
@@ -400,21 +402,18 @@ impl PoseidonGateConstrainer {
         }
     }
 
-    fn partial_first_constant_layer<F: PrimeField>(
+    fn partial_first_constant_layer<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        state: &mut Vec<AssignedExtensionFieldValue<F, 2>>,
+        state: &mut Vec<AssignedExtensionFieldValue<F, D>>,
     ) {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         for i in 0..T {
             let c = goldilocks_extension_chip
-                .constant_extension(
+                .constant_extension_from_base(
                     ctx,
-                    &[
-                        GoldilocksField::from_canonical_u64(FAST_PARTIAL_FIRST_ROUND_CONSTANT[i]),
-                        GoldilocksField::ZERO,
-                    ],
+                    GoldilocksField::from_canonical_u64(FAST_PARTIAL_FIRST_ROUND_CONSTANT[i]),
                 )
                 .unwrap();
             state[i] = goldilocks_extension_chip
@@ -423,45 +422,42 @@ impl PoseidonGateConstrainer {
         }
     }
 
-    fn sbox<F: PrimeField>(
+    fn sbox<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        element: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        element: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         goldilocks_extension_chip.exp(ctx, element, 7)
     }
 
-    fn sbox_layer<F: PrimeField>(
+    fn sbox_layer<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        state: &mut Vec<AssignedExtensionFieldValue<F, 2>>,
+        state: &mut Vec<AssignedExtensionFieldValue<F, D>>,
     ) {
         for i in 0..T {
             state[i] = self.sbox(ctx, goldilocks_chip_config, &state[i]).unwrap();
         }
     }
 
-    fn mds_row_shf<F: PrimeField>(
+    fn mds_row_shf<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
         row: usize,
-        state: &Vec<AssignedExtensionFieldValue<F, 2>>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
+        state: &Vec<AssignedExtensionFieldValue<F, D>>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
         debug_assert!(row < T);
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         let mut res = goldilocks_extension_chip.zero_extension(ctx)?;
 
         for i in 0..T {
-            let c = goldilocks_extension_chip.constant_extension(
+            let c = goldilocks_extension_chip.constant_extension_from_base(
                 ctx,
-                &[
-                    GoldilocksField::from_canonical_u64(MDS_MATRIX_CIRC[i]),
-                    GoldilocksField::ZERO,
-                ],
+                GoldilocksField::from_canonical_u64(MDS_MATRIX_CIRC[i]),
             )?;
             res = goldilocks_extension_chip.mul_add_extension(
                 ctx,
@@ -470,24 +466,21 @@ impl PoseidonGateConstrainer {
                 &res,
             )?;
         }
-        let c = goldilocks_extension_chip.constant_extension(
+        let c = goldilocks_extension_chip.constant_extension_from_base(
             ctx,
-            &[
-                GoldilocksField::from_canonical_u64(MDS_MATRIX_DIAG[row]),
-                GoldilocksField::ZERO,
-            ],
+            GoldilocksField::from_canonical_u64(MDS_MATRIX_DIAG[row]),
         )?;
         res = goldilocks_extension_chip.mul_add_extension(ctx, &c, &state[row], &res)?;
 
         Ok(res)
     }
 
-    fn mds_layer<F: PrimeField>(
+    fn mds_layer<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        state: &Vec<AssignedExtensionFieldValue<F, 2>>,
-    ) -> Vec<AssignedExtensionFieldValue<F, 2>> {
+        state: &Vec<AssignedExtensionFieldValue<F, D>>,
+    ) -> Vec<AssignedExtensionFieldValue<F, D>> {
         let mut result = vec![];
         for i in 0..T {
             result.push(
@@ -498,12 +491,12 @@ impl PoseidonGateConstrainer {
         result
     }
 
-    fn mds_partial_layer_init<F: PrimeField>(
+    fn mds_partial_layer_init<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        state: &Vec<AssignedExtensionFieldValue<F, 2>>,
-    ) -> Vec<AssignedExtensionFieldValue<F, 2>> {
+        state: &Vec<AssignedExtensionFieldValue<F, D>>,
+    ) -> Vec<AssignedExtensionFieldValue<F, D>> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         let mut result = Vec::with_capacity(T);
         for _ in 0..T {
@@ -515,14 +508,11 @@ impl PoseidonGateConstrainer {
         for r in 1..T {
             for c in 1..T {
                 let t = goldilocks_extension_chip
-                    .constant_extension(
+                    .constant_extension_from_base(
                         ctx,
-                        &[
-                            GoldilocksField::from_canonical_u64(
-                                FAST_PARTIAL_ROUND_INITIAL_MATRIX[r - 1][c - 1],
-                            ),
-                            GoldilocksField::ZERO,
-                        ],
+                        GoldilocksField::from_canonical_u64(
+                            FAST_PARTIAL_ROUND_INITIAL_MATRIX[r - 1][c - 1],
+                        ),
                     )
                     .unwrap();
                 result[c] = goldilocks_extension_chip
@@ -533,13 +523,13 @@ impl PoseidonGateConstrainer {
         result
     }
 
-    fn mds_partial_layer_fast<F: PrimeField>(
+    fn mds_partial_layer_fast<F: PrimeField, const D: usize>(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        state: &Vec<AssignedExtensionFieldValue<F, 2>>,
+        state: &Vec<AssignedExtensionFieldValue<F, D>>,
         r: usize,
-    ) -> Vec<AssignedExtensionFieldValue<F, 2>> {
+    ) -> Vec<AssignedExtensionFieldValue<F, D>> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         let s0 = state[0].clone();
         let mds0to0 = MDS_MATRIX_CIRC[0] + MDS_MATRIX_DIAG[0];
@@ -549,13 +539,7 @@ impl PoseidonGateConstrainer {
         for i in 1..T {
             let t = FAST_PARTIAL_ROUND_W_HATS[r][i - 1];
             let t = goldilocks_extension_chip
-                .constant_extension(
-                    ctx,
-                    &[
-                        GoldilocksField::from_canonical_u64(t),
-                        GoldilocksField::ZERO,
-                    ],
-                )
+                .constant_extension_from_base(ctx, GoldilocksField::from_canonical_u64(t))
                 .unwrap();
             d = goldilocks_extension_chip
                 .mul_add_extension(ctx, &t, &state[i], &d)
@@ -570,13 +554,7 @@ impl PoseidonGateConstrainer {
         for i in 1..T {
             let t = FAST_PARTIAL_ROUND_VS[r][i - 1];
             let t = goldilocks_extension_chip
-                .constant_extension(
-                    ctx,
-                    &[
-                        GoldilocksField::from_canonical_u64(t),
-                        GoldilocksField::ZERO,
-                    ],
-                )
+                .constant_extension_from_base(ctx, GoldilocksField::from_canonical_u64(t))
                 .unwrap();
             result[i] = goldilocks_extension_chip
                 .mul_add_extension(ctx, &t, &state[0], &state[i])
@@ -586,15 +564,19 @@ impl PoseidonGateConstrainer {
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for PoseidonGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for PoseidonGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("PoseidonGate").with_param("WIDTH", 12)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        _local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         let mut constraints = vec![];
 
@@ -653,13 +635,8 @@ impl<F: PrimeField> CustomGateConstrainer<F> for PoseidonGateConstrainer {
             constraints.push(goldilocks_extension_chip.sub_extension(ctx, &state[0], sbox_in)?);
             state[0] = self.sbox(ctx, goldilocks_chip_config, sbox_in)?;
             let c = FAST_PARTIAL_ROUND_CONSTANTS[r];
-            let c = goldilocks_extension_chip.constant_extension(
-                ctx,
-                &[
-                    GoldilocksField::from_canonical_u64(c),
-                    GoldilocksField::ZERO,
-                ],
-            )?;
+            let c = goldilocks_extension_chip
+                .constant_extension_from_base(ctx, GoldilocksField::from_canonical_u64(c))?;
             state[0] = goldilocks_extension_chip.add_extension(ctx, &state[0], &c)?;
             state = self.mds_partial_layer_fast(ctx, goldilocks_chip_config, &state, r);
         }
@@ -692,6 +669,18 @@ impl<F: PrimeField> CustomGateConstrainer<F> for PoseidonGateConstrainer {
 
         Ok(constraints)
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        // The round constants and the MDS layers are plonky2's own, so its gate is the native
+        // reference.
+        Ok(PoseidonGate::<GoldilocksField, D>::new().eval_unfiltered(vars))
+    }
 }
 
 #[cfg(test)]
@@ -3,8 +3,9 @@ use std::ops::Range;
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
 use plonky2::{
-    field::{goldilocks_field::GoldilocksField, types::Field},
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
     hash::poseidon::SPONGE_WIDTH,
+    plonk::vars::EvaluationVars,
 };
 
 use crate::{
@@ -17,20 +18,23 @@ use crate::{
 };
 const T: usize = SPONGE_WIDTH;
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 #[derive(Clone, Debug, Default)]
-pub struct PoseidonMDSGateConstrainer;
+pub struct PoseidonMDSGateConstrainer<const D: usize>;
 
-impl PoseidonMDSGateConstrainer {
+impl<const D: usize> PoseidonMDSGateConstrainer<D> {
     pub fn wires_input(i: usize) -> Range<usize> {
         assert!(i < T);
-        i * 2..(i + 1) * 2
+        i * D..(i + 1) * D
     }
 
     pub fn wires_output(i: usize) -> Range<usize> {
         assert!(i < T);
-        (T + i) * 2..(T + i + 1) * 2
+        (T + i) * D..(T + i + 1) * D
     }
 
     fn mds_row_shf<F: PrimeField>(
@@ -38,8 +42,8 @@ impl PoseidonMDSGateConstrainer {
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
         row: usize,
-        state: &Vec<AssignedExtensionAlgebra<F>>,
-    ) -> Result<AssignedExtensionAlgebra<F>, Error> {
+        state: &Vec<AssignedExtensionAlgebra<F, D>>,
+    ) -> Result<AssignedExtensionAlgebra<F, D>, Error> {
         debug_assert!(row < T);
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         let goldilocks_extension_algebra_chip =
@@ -47,12 +51,9 @@ impl PoseidonMDSGateConstrainer {
         let mut res = goldilocks_extension_algebra_chip.zero_ext_algebra(ctx)?;
 
         for i in 0..T {
-            let c = goldilocks_extension_chip.constant_extension(
+            let c = goldilocks_extension_chip.constant_extension_from_base(
                 ctx,
-                &[
-                    GoldilocksField::from_canonical_u64(MDS_MATRIX_CIRC[i]),
-                    GoldilocksField::ZERO,
-                ],
+                GoldilocksField::from_canonical_u64(MDS_MATRIX_CIRC[i]),
             )?;
             res = goldilocks_extension_algebra_chip.scalar_mul_add_ext_algebra(
                 ctx,
@@ -61,12 +62,9 @@ impl PoseidonMDSGateConstrainer {
                 &res,
             )?;
         }
-        let c = goldilocks_extension_chip.constant_extension(
+        let c = goldilocks_extension_chip.constant_extension_from_base(
             ctx,
-            &[
-                GoldilocksField::from_canonical_u64(MDS_MATRIX_DIAG[row]),
-                GoldilocksField::ZERO,
-            ],
+            GoldilocksField::from_canonical_u64(MDS_MATRIX_DIAG[row]),
         )?;
         res = goldilocks_extension_algebra_chip.scalar_mul_add_ext_algebra(
             ctx,
@@ -82,8 +80,8 @@ impl PoseidonMDSGateConstrainer {
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        state: &Vec<AssignedExtensionAlgebra<F>>,
-    ) -> Vec<AssignedExtensionAlgebra<F>> {
+        state: &Vec<AssignedExtensionAlgebra<F, D>>,
+    ) -> Vec<AssignedExtensionAlgebra<F, D>> {
         let mut result = vec![];
         for i in 0..T {
             result.push(
@@ -95,15 +93,19 @@ impl PoseidonMDSGateConstrainer {
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for PoseidonMDSGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for PoseidonMDSGateConstrainer<D> {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("PoseidonMdsGate").with_param("WIDTH", 12)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        _local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
         let goldilocks_extension_algebra_chip =
             self.goldilocks_extension_algebra_chip(goldilocks_chip_config);
         let inputs = (0..T)
@@ -123,6 +125,38 @@ impl<F: PrimeField> CustomGateConstrainer<F> for PoseidonMDSGateConstrainer {
             .collect();
         Ok(constraints)
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let inputs = (0..T)
+            .map(|i| vars.get_local_ext_algebra(Self::wires_input(i)))
+            .collect::<Vec<_>>();
+        Ok((0..T)
+            .flat_map(|row| {
+                let computed_output = (0..T).fold(
+                    inputs[row].scalar_mul(
+                        <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(
+                            MDS_MATRIX_DIAG[row],
+                        ),
+                    ),
+                    |acc, i| {
+                        acc + inputs[(i + row) % T].scalar_mul(
+                            <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(
+                                MDS_MATRIX_CIRC[i],
+                            ),
+                        )
+                    },
+                );
+                (vars.get_local_ext_algebra(Self::wires_output(row)) - computed_output)
+                    .to_basefield_array()
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -2,13 +2,23 @@ use std::ops::Range;
 
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        goldilocks_field::GoldilocksField,
+    },
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::goldilocks_chip::GoldilocksChipConfig,
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 #[derive(Debug, Clone)]
 pub struct PublicInputGateConstrainer;
@@ -19,15 +29,19 @@ impl PublicInputGateConstrainer {
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for PublicInputGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for PublicInputGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("PublicInputGate")
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        _local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         Self::wires_public_inputs_hash()
             .zip(public_inputs_hash.elements.clone())
@@ -38,6 +52,22 @@ impl<F: PrimeField> CustomGateConstrainer<F> for PublicInputGateConstrainer {
             })
             .collect()
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        Ok(Self::wires_public_inputs_hash()
+            .zip(vars.public_inputs_hash.elements)
+            .map(|(wire, hash_part)| {
+                vars.local_wires[wire]
+                    - <GoldilocksField as Extendable<D>>::Extension::from_basefield(hash_part)
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -1,13 +1,21 @@
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
 use itertools::Itertools;
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::goldilocks_chip::GoldilocksChipConfig,
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    reduce_native,
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 /// A gate for checking that a particular element of a list matches a given value.
 #[derive(Clone, Debug)]
@@ -75,15 +83,23 @@ impl RandomAccessGateConstrainer {
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for RandomAccessGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for RandomAccessGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("RandomAccessGate")
+            .with_param("bits", self.bits)
+            .with_param("num_copies", self.num_copies)
+            .with_param("num_extra_constants", self.num_extra_constants)
+            .with_param("D", D)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, halo2_proofs::plonk::Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, halo2_proofs::plonk::Error> {
         let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
         let two = goldilocks_extension_chip.two_extension(ctx)?;
         let mut constraints = Vec::with_capacity(self.num_constraints());
@@ -146,6 +162,48 @@ impl<F: PrimeField> CustomGateConstrainer<F> for RandomAccessGateConstrainer {
 
         Ok(constraints)
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let two = <GoldilocksField as Extendable<D>>::Extension::TWO;
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        for copy in 0..self.num_copies {
+            let access_index = vars.local_wires[self.wire_access_index(copy)];
+            let mut list_items = (0..self.vec_size())
+                .map(|i| vars.local_wires[self.wire_list_item(i, copy)])
+                .collect::<Vec<_>>();
+            let claimed_element = vars.local_wires[self.wire_claimed_element(copy)];
+            let bits = (0..self.bits)
+                .map(|i| vars.local_wires[self.wire_bit(i, copy)])
+                .collect::<Vec<_>>();
+
+            for &b in &bits {
+                constraints.push(b * b - b);
+            }
+            constraints.push(reduce_native(two, &bits) - access_index);
+            for b in bits {
+                list_items = list_items
+                    .iter()
+                    .tuples()
+                    .map(|(&x, &y)| b * (y - x) + x)
+                    .collect();
+            }
+            debug_assert_eq!(list_items.len(), 1);
+            constraints.push(list_items[0] - claimed_element);
+        }
+
+        constraints.extend(
+            (0..self.num_extra_constants)
+                .map(|i| vars.local_constants[i] - vars.local_wires[self.wire_extra_constant(i)]),
+        );
+        Ok(constraints)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,181 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+};
+
+use super::{
+    range_check_native, reduce_native,
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// A gate which checks that the input limbs are 32-bit values, by decomposing them into base-4
+/// auxiliary limbs. The plonky2 gate is `U32RangeCheckGate` of plonky2-u32.
+#[derive(Clone, Debug)]
+pub struct U32RangeCheckGateConstrainer {
+    pub num_input_limbs: usize,
+}
+
+impl U32RangeCheckGateConstrainer {
+    pub const AUX_LIMB_BITS: usize = 2;
+    pub const BASE: usize = 1 << Self::AUX_LIMB_BITS;
+
+    pub fn aux_limbs_per_input_limb() -> usize {
+        32usize.div_ceil(Self::AUX_LIMB_BITS)
+    }
+
+    pub fn wire_ith_input_limb(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_input_limbs);
+        i
+    }
+
+    /// The `j`th auxiliary limb of the `i`th input limb, in little-endian order.
+    pub fn wire_ith_input_limb_jth_aux_limb(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_input_limbs);
+        debug_assert!(j < Self::aux_limbs_per_input_limb());
+        self.num_input_limbs + Self::aux_limbs_per_input_limb() * i + j
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.num_input_limbs * (1 + Self::aux_limbs_per_input_limb())
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for U32RangeCheckGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("U32RangeCheckGate").with_param("num_input_limbs", self.num_input_limbs)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+        let base = goldilocks_extension_chip
+            .constant_extension_from_base(ctx, GoldilocksField::from_canonical_usize(Self::BASE))?;
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_input_limbs {
+            let input_limb = &local_wires[self.wire_ith_input_limb(i)];
+            let aux_limbs = (0..Self::aux_limbs_per_input_limb())
+                .map(|j| local_wires[self.wire_ith_input_limb_jth_aux_limb(i, j)].clone())
+                .collect::<Vec<_>>();
+            let computed_sum =
+                goldilocks_extension_chip.reduce_extension(ctx, &base, &aux_limbs)?;
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &computed_sum,
+                input_limb,
+            )?);
+
+            for aux_limb in &aux_limbs {
+                constraints.push(goldilocks_extension_chip.range_check_extension(
+                    ctx,
+                    aux_limb,
+                    Self::BASE,
+                )?);
+            }
+        }
+
+        Ok(constraints)
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let base = <GoldilocksField as Extendable<D>>::Extension::from_canonical_usize(Self::BASE);
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_input_limbs {
+            let input_limb = vars.local_wires[self.wire_ith_input_limb(i)];
+            let aux_limbs = (0..Self::aux_limbs_per_input_limb())
+                .map(|j| vars.local_wires[self.wire_ith_input_limb_jth_aux_limb(i, j)])
+                .collect::<Vec<_>>();
+            constraints.push(reduce_native(base, &aux_limbs) - input_limb);
+            constraints.extend(
+                aux_limbs
+                    .iter()
+                    .map(|&aux_limb| range_check_native(aux_limb, Self::BASE)),
+            );
+        }
+        Ok(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::U32RangeCheckGateConstrainer;
+    use crate::chip::plonk::gates::gate_test::{custom_gate_satisfied, test_custom_gate};
+    use plonky2::field::{
+        extension::quadratic::QuadraticExtension,
+        goldilocks_field::GoldilocksField,
+        types::{Field, Sample},
+    };
+    use plonky2_u32::gates::range_check_u32::U32RangeCheckGate;
+    use rand::Rng;
+
+    type FE = QuadraticExtension<GoldilocksField>;
+
+    fn generate_wires(gate: &U32RangeCheckGateConstrainer, limbs: &[u64]) -> Vec<FE> {
+        let mut wires = FE::rand_vec(
+            gate.num_input_limbs * (1 + U32RangeCheckGateConstrainer::aux_limbs_per_input_limb()),
+        );
+        for (i, &limb) in limbs.iter().enumerate() {
+            wires[gate.wire_ith_input_limb(i)] = FE::from_canonical_u64(limb);
+            for j in 0..U32RangeCheckGateConstrainer::aux_limbs_per_input_limb() {
+                wires[gate.wire_ith_input_limb_jth_aux_limb(i, j)] =
+                    FE::from_canonical_u64((limb >> (2 * j)) & 3);
+            }
+        }
+        wires
+    }
+
+    #[test]
+    fn test_range_check_u32_gate() {
+        let gate = U32RangeCheckGateConstrainer { num_input_limbs: 4 };
+        let mut rng = rand::thread_rng();
+        let mut limbs = (0..gate.num_input_limbs)
+            .map(|_| rng.gen::<u32>() as u64)
+            .collect::<Vec<_>>();
+        limbs[0] = u32::MAX as u64;
+        let num_constraints = gate.num_constraints();
+
+        assert!(custom_gate_satisfied(
+            gate.clone(),
+            &generate_wires(&gate, &limbs),
+            num_constraints,
+            17
+        ));
+
+        // The decomposition drops the bits above 32, so the sum no longer matches.
+        limbs[1] = 1 << 32;
+        assert!(!custom_gate_satisfied(
+            gate.clone(),
+            &generate_wires(&gate, &limbs),
+            num_constraints,
+            17
+        ));
+    }
+
+    #[test]
+    fn test_range_check_u32_gate_against_plonky2() {
+        let plonky2_gate = U32RangeCheckGate::new(4);
+        let halo2_gate = U32RangeCheckGateConstrainer {
+            num_input_limbs: plonky2_gate.num_input_limbs,
+        };
+        test_custom_gate(plonky2_gate, halo2_gate, 17);
+    }
+}
@@ -2,31 +2,38 @@ use std::ops::Range;
 
 use crate::context::RegionCtx;
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::vars::EvaluationVars,
+};
 
 use crate::{
     chip::goldilocks_chip::GoldilocksChipConfig,
     types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
 };
 
-use super::CustomGateConstrainer;
+use super::{
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
 
 /// Computes `sum alpha^i c_i` for a vector `c_i` of `num_coeffs` elements of the base field.
 #[derive(Debug, Clone)]
-pub struct ReducingGateConstrainer {
+pub struct ReducingGateConstrainer<const D: usize> {
     pub num_coeffs: usize,
 }
 
-impl ReducingGateConstrainer {
+impl<const D: usize> ReducingGateConstrainer<D> {
     pub fn wires_output() -> Range<usize> {
-        0..2
+        0..D
     }
     pub fn wires_alpha() -> Range<usize> {
-        2..2 * 2
+        D..2 * D
     }
     pub fn wires_old_acc() -> Range<usize> {
-        2 * 2..3 * 2
+        2 * D..3 * D
     }
-    const START_COEFFS: usize = 3 * 2;
+    const START_COEFFS: usize = 3 * D;
     pub fn wires_coeffs(&self) -> Range<usize> {
         Self::START_COEFFS..Self::START_COEFFS + self.num_coeffs
     }
@@ -38,23 +45,27 @@ impl ReducingGateConstrainer {
             // The last accumulator is the output.
             return Self::wires_output();
         }
-        self.start_accs() + 2 * i..self.start_accs() + 2 * (i + 1)
+        self.start_accs() + D * i..self.start_accs() + D * (i + 1)
     }
 
     fn num_constraints(&self) -> usize {
-        2 * self.num_coeffs
+        D * self.num_coeffs
     }
 }
 
-impl<F: PrimeField> CustomGateConstrainer<F> for ReducingGateConstrainer {
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for ReducingGateConstrainer<D> {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("ReducingGate").with_param("num_coeffs", self.num_coeffs)
+    }
+
     fn eval_unfiltered_constraint(
         &self,
         ctx: &mut RegionCtx<'_, F>,
         goldilocks_chip_config: &GoldilocksChipConfig<F>,
-        _local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         _public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
         let goldilocks_extension_algebra_chip =
             self.goldilocks_extension_algebra_chip(goldilocks_chip_config);
         let alpha = self.get_local_ext_algebra(local_wires, Self::wires_alpha());
@@ -84,6 +95,32 @@ impl<F: PrimeField> CustomGateConstrainer<F> for ReducingGateConstrainer {
             .flat_map(|alg| alg.to_ext_array())
             .collect())
     }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let alpha = vars.get_local_ext_algebra(Self::wires_alpha());
+        let old_acc = vars.get_local_ext_algebra(Self::wires_old_acc());
+        let coeffs = self
+            .wires_coeffs()
+            .map(|i| vars.local_wires[i])
+            .collect::<Vec<_>>();
+        let accs = (0..self.num_coeffs)
+            .map(|i| vars.get_local_ext_algebra(self.wires_accs(i)))
+            .collect::<Vec<_>>();
+
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        let mut acc = old_acc;
+        for i in 0..self.num_coeffs {
+            constraints.extend((acc * alpha + coeffs[i].into() - accs[i]).to_basefield_array());
+            acc = accs[i];
+        }
+        Ok(constraints)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,537 @@
+use std::{collections::BTreeMap, collections::HashMap, fmt, str::FromStr};
+
+use halo2_proofs::halo2curves::ff::PrimeField;
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    gates::gate::GateRef,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    add_many_u32::U32AddManyGateConstrainer, arithmetic::ArithmeticGateConstrainer,
+    arithmetic_extension::ArithmeticExtensionGateConstrainer,
+    arithmetic_u32::U32ArithmeticGateConstrainer, base_sum::BaseSumGateConstrainer,
+    comparison::ComparisonGateConstrainer, constant::ConstantGateConstrainer,
+    coset_interpolation::CosetInterpolationGateConstrainer,
+    exponentiation::ExponentiationGateConstrainer, lookup::LookupGateConstrainer,
+    lookup_table::LookupTableGateConstrainer,
+    multiplication_extension::MulExtensionGateConstrainer, noop::NoopGateConstrainer,
+    poseidon::PoseidonGateConstrainer, poseidon_mds::PoseidonMDSGateConstrainer,
+    public_input::PublicInputGateConstrainer, random_access::RandomAccessGateConstrainer,
+    range_check_u32::U32RangeCheckGateConstrainer, reducing::ReducingGateConstrainer,
+    reducing_extension::ReducingExtensionGateConstrainer,
+    subtraction_u32::U32SubtractionGateConstrainer, CustomGateRef,
+};
+
+/// Name and parameters of a plonky2 gate, parsed from its `Gate::id()`.
+///
+/// Plonky2 derives gate ids from the `Debug` output of the gate, e.g.
+/// `RandomAccessGate { bits: 4, num_copies: 4, num_extra_constants: 2, _phantom: .. }<D=2>` or
+/// `BaseSumGate { num_limbs: 63 } + Base: 2`. Struct fields, `<KEY=VALUE>` suffixes and
+/// `+ Key: value` suffixes all end up in `params`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GateDescriptor {
+    pub name: String,
+    pub params: BTreeMap<String, String>,
+}
+
+impl GateDescriptor {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Fails if a delimiter of `id` is not closed.
+    pub fn parse(id: &str) -> Result<Self, GateRegistryError> {
+        let id = id.trim();
+        let name_end = id
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(id.len());
+        let name = id[..name_end].to_string();
+        let mut params = BTreeMap::new();
+
+        let mut rest = id[name_end..].trim_start();
+        while let Some(c) = rest.chars().next() {
+            match c {
+                '{' | '(' | '<' => {
+                    let end = matching_delimiter(rest).ok_or_else(|| {
+                        GateRegistryError::InvalidParameters {
+                            gate: name.clone(),
+                            reason: format!("unbalanced delimiters in `{id}`"),
+                        }
+                    })?;
+                    let body = &rest[1..end];
+                    match c {
+                        '{' => {
+                            for field in split_top_level(body) {
+                                if let Some((key, value)) = field.split_once(':') {
+                                    params.insert(key.trim().to_string(), value.trim().to_string());
+                                }
+                            }
+                        }
+                        '<' => {
+                            for field in split_top_level(body) {
+                                if let Some((key, value)) = field.split_once('=') {
+                                    params.insert(key.trim().to_string(), value.trim().to_string());
+                                }
+                            }
+                        }
+                        // Tuple structs only carry `PhantomData` in plonky2.
+                        _ => {}
+                    }
+                    rest = rest[end + 1..].trim_start();
+                }
+                '+' => {
+                    let field = rest[1..].trim_start();
+                    let end = field.find('+').unwrap_or(field.len());
+                    if let Some((key, value)) = field[..end].split_once(':') {
+                        params.insert(key.trim().to_string(), value.trim().to_string());
+                    }
+                    rest = field[end..].trim_start();
+                }
+                _ => break,
+            }
+        }
+
+        Ok(Self { name, params })
+    }
+
+    /// Returns the parameter `key` parsed as `T`.
+    pub fn param<T: FromStr>(&self, key: &str) -> Result<T, GateRegistryError> {
+        let value = self
+            .params
+            .get(key)
+            .ok_or_else(|| self.invalid(format!("missing parameter `{key}`")))?;
+        value
+            .parse()
+            .map_err(|_| self.invalid(format!("cannot parse parameter `{key}` = `{value}`")))
+    }
+
+    /// Fails unless the parameter `key` is present and equal to `expected`.
+    pub fn expect_param(&self, key: &str, expected: usize) -> Result<(), GateRegistryError> {
+        let value: usize = self.param(key)?;
+        if value != expected {
+            return Err(self.invalid(format!(
+                "parameter `{key}` = {value} is not supported, expected {expected}"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn invalid(&self, reason: impl Into<String>) -> GateRegistryError {
+        GateRegistryError::InvalidParameters {
+            gate: self.name.clone(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Returns the index of the delimiter closing the one `s` starts with.
+fn matching_delimiter(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' | '(' | '[' | '<' => depth += 1,
+            '}' | ')' | ']' | '>' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits `s` on commas which are not nested inside any delimiter.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut fields = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' | '(' | '[' | '<' => depth += 1,
+            '}' | ')' | ']' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                fields.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(s[start..].trim());
+    fields.into_iter().filter(|f| !f.is_empty()).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GateRegistryError {
+    /// No constrainer is registered for these gate ids.
+    UnsupportedGates(Vec<String>),
+    /// A constrainer is registered for the gate, but not for these parameters.
+    InvalidParameters { gate: String, reason: String },
+}
+
+impl fmt::Display for GateRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedGates(ids) => {
+                write!(f, "unsupported gates: ")?;
+                for (i, id) in ids.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "`{id}`")?;
+                }
+                Ok(())
+            }
+            Self::InvalidParameters { gate, reason } => {
+                write!(f, "invalid parameters for {gate}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GateRegistryError {}
+
+/// Builds a constrainer from the parameters of a plonky2 gate.
+pub type GateConstructor<F, const D: usize> =
+    Box<dyn Fn(&GateDescriptor) -> Result<CustomGateRef<F, D>, GateRegistryError> + Send + Sync>;
+
+/// Maps plonky2 gate names to the constrainers evaluating them inside the circuit.
+///
+/// `CustomGateRegistry::default()` knows every gate implemented in this crate. Downstream crates
+/// can `register` constrainers for their own plonky2 gates, or replace the built-in ones.
+pub struct CustomGateRegistry<F: PrimeField, const D: usize> {
+    constructors: HashMap<String, GateConstructor<F, D>>,
+}
+
+impl<F: PrimeField, const D: usize> CustomGateRegistry<F, D> {
+    /// Creates a registry without any gate.
+    pub fn empty() -> Self {
+        Self {
+            constructors: HashMap::new(),
+        }
+    }
+
+    /// Registers `constructor` for the gates named `name`, i.e. the type name in `Gate::id()`.
+    /// Any constructor previously registered under the same name is replaced.
+    pub fn register(
+        &mut self,
+        name: &str,
+        constructor: impl Fn(&GateDescriptor) -> Result<CustomGateRef<F, D>, GateRegistryError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> &mut Self {
+        self.constructors
+            .insert(name.to_string(), Box::new(constructor));
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.constructors.contains_key(name)
+    }
+
+    /// Builds the constrainer of the gate with the given `Gate::id()`.
+    pub fn build_from_id(&self, id: &str) -> Result<CustomGateRef<F, D>, GateRegistryError> {
+        match self.build_from_descriptor(&GateDescriptor::parse(id)?) {
+            Err(GateRegistryError::UnsupportedGates(_)) => {
+                Err(GateRegistryError::UnsupportedGates(vec![id
+                    .trim()
+                    .to_string()]))
+            }
+            result => result,
+        }
+    }
+
+    pub fn build_from_descriptor(
+        &self,
+        descriptor: &GateDescriptor,
+    ) -> Result<CustomGateRef<F, D>, GateRegistryError> {
+        match self.constructors.get(&descriptor.name) {
+            Some(constructor) => constructor(descriptor),
+            None => Err(GateRegistryError::UnsupportedGates(vec![descriptor
+                .name
+                .clone()])),
+        }
+    }
+
+    pub fn build(
+        &self,
+        gate: &GateRef<GoldilocksField, D>,
+    ) -> Result<CustomGateRef<F, D>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        self.build_from_id(&gate.0.id())
+    }
+
+    /// Builds the constrainers of all `gates`. Every unsupported gate is reported at once.
+    pub fn build_all(
+        &self,
+        gates: &[GateRef<GoldilocksField, D>],
+    ) -> Result<Vec<CustomGateRef<F, D>>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let mut constrainers = vec![];
+        let mut unsupported = vec![];
+        for gate in gates {
+            match self.build(gate) {
+                Ok(constrainer) => constrainers.push(constrainer),
+                Err(GateRegistryError::UnsupportedGates(ids)) => unsupported.extend(ids),
+                Err(e) => return Err(e),
+            }
+        }
+        if !unsupported.is_empty() {
+            return Err(GateRegistryError::UnsupportedGates(unsupported));
+        }
+        Ok(constrainers)
+    }
+}
+
+impl<F: PrimeField, const D: usize> Default for CustomGateRegistry<F, D> {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register("ArithmeticGate", |d| {
+                Ok(CustomGateRef(Box::new(ArithmeticGateConstrainer {
+                    num_ops: d.param("num_ops")?,
+                })))
+            })
+            .register("ArithmeticExtensionGate", |d| {
+                Ok(CustomGateRef(Box::new(
+                    ArithmeticExtensionGateConstrainer::<D> {
+                        num_ops: d.param("num_ops")?,
+                    },
+                )))
+            })
+            .register("CosetInterpolationGate", |d| {
+                d.expect_param("D", D)?;
+                let subgroup_bits: usize = d.param("subgroup_bits")?;
+                let degree: usize = d.param("degree")?;
+                if degree < 2 || degree > 1 << subgroup_bits {
+                    return Err(d.invalid(format!(
+                        "degree {degree} is not in 2..={}",
+                        1 << subgroup_bits
+                    )));
+                }
+                Ok(CustomGateRef(Box::new(
+                    CosetInterpolationGateConstrainer::<D>::new(subgroup_bits, degree),
+                )))
+            })
+            .register("ExponentiationGate", |d| {
+                d.expect_param("D", D)?;
+                let num_power_bits: usize = d.param("num_power_bits")?;
+                if num_power_bits == 0 {
+                    return Err(d.invalid("num_power_bits is zero"));
+                }
+                Ok(CustomGateRef(Box::new(ExponentiationGateConstrainer {
+                    num_power_bits,
+                })))
+            })
+            .register("LookupGate", |d| {
+                Ok(CustomGateRef(Box::new(LookupGateConstrainer {
+                    num_slots: d.param("num_slots")?,
+                })))
+            })
+            .register("LookupTableGate", |d| {
+                Ok(CustomGateRef(Box::new(LookupTableGateConstrainer {
+                    num_slots: d.param("num_slots")?,
+                    last_lut_row: d.param("last_lut_row")?,
+                })))
+            })
+            .register("MulExtensionGate", |d| {
+                Ok(CustomGateRef(Box::new(MulExtensionGateConstrainer::<D> {
+                    num_ops: d.param("num_ops")?,
+                })))
+            })
+            .register("BaseSumGate", |d| {
+                d.expect_param("Base", 2)?;
+                Ok(CustomGateRef(Box::new(BaseSumGateConstrainer {
+                    num_limbs: d.param("num_limbs")?,
+                })))
+            })
+            .register("ConstantGate", |d| {
+                Ok(CustomGateRef(Box::new(ConstantGateConstrainer {
+                    num_consts: d.param("num_consts")?,
+                })))
+            })
+            .register("NoopGate", |_| {
+                Ok(CustomGateRef(Box::new(NoopGateConstrainer)))
+            })
+            .register("PublicInputGate", |_| {
+                Ok(CustomGateRef(Box::new(PublicInputGateConstrainer)))
+            })
+            .register("PoseidonGate", |d| {
+                d.expect_param("WIDTH", 12)?;
+                Ok(CustomGateRef(Box::new(PoseidonGateConstrainer)))
+            })
+            .register("PoseidonMdsGate", |d| {
+                d.expect_param("WIDTH", 12)?;
+                Ok(CustomGateRef(Box::new(PoseidonMDSGateConstrainer::<D>)))
+            })
+            .register("RandomAccessGate", |d| {
+                d.expect_param("D", D)?;
+                Ok(CustomGateRef(Box::new(RandomAccessGateConstrainer {
+                    bits: d.param("bits")?,
+                    num_copies: d.param("num_copies")?,
+                    num_extra_constants: d.param("num_extra_constants")?,
+                })))
+            })
+            .register("ReducingGate", |d| {
+                Ok(CustomGateRef(Box::new(ReducingGateConstrainer::<D> {
+                    num_coeffs: d.param("num_coeffs")?,
+                })))
+            })
+            .register("ReducingExtensionGate", |d| {
+                Ok(CustomGateRef(Box::new(ReducingExtensionGateConstrainer::<
+                    D,
+                > {
+                    num_coeffs: d.param("num_coeffs")?,
+                })))
+            })
+            .register("U32AddManyGate", |d| {
+                Ok(CustomGateRef(Box::new(U32AddManyGateConstrainer {
+                    num_addends: d.param("num_addends")?,
+                    num_ops: d.param("num_ops")?,
+                })))
+            })
+            .register("U32ArithmeticGate", |d| {
+                Ok(CustomGateRef(Box::new(U32ArithmeticGateConstrainer {
+                    num_ops: d.param("num_ops")?,
+                })))
+            })
+            .register("U32SubtractionGate", |d| {
+                Ok(CustomGateRef(Box::new(U32SubtractionGateConstrainer {
+                    num_ops: d.param("num_ops")?,
+                })))
+            })
+            .register("U32RangeCheckGate", |d| {
+                Ok(CustomGateRef(Box::new(U32RangeCheckGateConstrainer {
+                    num_input_limbs: d.param("num_input_limbs")?,
+                })))
+            })
+            .register("ComparisonGate", |d| {
+                Ok(CustomGateRef(Box::new(ComparisonGateConstrainer {
+                    num_bits: d.param("num_bits")?,
+                    num_chunks: d.param("num_chunks")?,
+                })))
+            });
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CustomGateRegistry, GateDescriptor, GateRegistryError};
+    use crate::chip::plonk::gates::{noop::NoopGateConstrainer, CustomGateRef};
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use plonky2::{
+        field::goldilocks_field::GoldilocksField,
+        gates::{
+            arithmetic_base::ArithmeticGate, base_sum::BaseSumGate, gate::GateRef,
+            poseidon::PoseidonGate, random_access::RandomAccessGate,
+        },
+        plonk::circuit_data::CircuitConfig,
+    };
+
+    #[test]
+    fn test_parse_gate_id() {
+        let descriptor = GateDescriptor::parse(
+            "RandomAccessGate { bits: 4, num_copies: 4, num_extra_constants: 2, _phantom: PhantomData<plonky2_field::goldilocks_field::GoldilocksField> }<D=2>",
+        )
+        .unwrap();
+        assert_eq!(descriptor.name, "RandomAccessGate");
+        assert_eq!(descriptor.param::<usize>("bits").unwrap(), 4);
+        assert_eq!(descriptor.param::<usize>("num_copies").unwrap(), 4);
+        assert_eq!(descriptor.param::<usize>("num_extra_constants").unwrap(), 2);
+        assert_eq!(descriptor.param::<usize>("D").unwrap(), 2);
+
+        let descriptor = GateDescriptor::parse("BaseSumGate { num_limbs: 63 } + Base: 2").unwrap();
+        assert_eq!(descriptor.name, "BaseSumGate");
+        assert_eq!(descriptor.param::<usize>("num_limbs").unwrap(), 63);
+        assert_eq!(descriptor.param::<usize>("Base").unwrap(), 2);
+
+        let descriptor = GateDescriptor::parse(
+            "PoseidonGate(PhantomData<plonky2_field::goldilocks_field::GoldilocksField>)<WIDTH=12>",
+        )
+        .unwrap();
+        assert_eq!(descriptor.name, "PoseidonGate");
+        assert_eq!(descriptor.param::<usize>("WIDTH").unwrap(), 12);
+
+        let descriptor = GateDescriptor::parse("NoopGate").unwrap();
+        assert_eq!(descriptor.name, "NoopGate");
+        assert!(descriptor.params.is_empty());
+
+        for id in ["Foo{", "Foo { bar: Baz<2 }", "Foo(PhantomData<F>"] {
+            assert!(matches!(
+                GateDescriptor::parse(id),
+                Err(GateRegistryError::InvalidParameters { gate, .. }) if gate == "Foo"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_build_gates_with_any_config() {
+        let config = CircuitConfig {
+            num_wires: 100,
+            num_routed_wires: 60,
+            ..CircuitConfig::standard_recursion_config()
+        };
+        let gates: Vec<GateRef<GoldilocksField, 2>> = vec![
+            GateRef::new(ArithmeticGate::new_from_config(&config)),
+            GateRef::new(RandomAccessGate::new_from_config(&config, 3)),
+            GateRef::new(BaseSumGate::<2>::new(32)),
+            GateRef::new(PoseidonGate::<GoldilocksField, 2>::new()),
+        ];
+        let registry = CustomGateRegistry::<Fr, 2>::default();
+        let constrainers = registry.build_all(&gates).unwrap();
+        assert_eq!(constrainers.len(), gates.len());
+
+        for constrainer in constrainers {
+            let descriptor = constrainer.0.descriptor();
+            let rebuilt = registry.build_from_descriptor(&descriptor).unwrap();
+            assert_eq!(rebuilt.0.descriptor(), descriptor);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_gates() {
+        let registry = CustomGateRegistry::<Fr, 2>::default();
+        let result = registry.build_from_id("BaseSumGate { num_limbs: 16 } + Base: 4");
+        assert!(matches!(
+            result,
+            Err(GateRegistryError::InvalidParameters { .. })
+        ));
+        let result = registry.build_from_id(
+            "ExponentiationGate { num_power_bits: 0, _phantom: PhantomData<plonky2_field::goldilocks_field::GoldilocksField> }<D=2>",
+        );
+        assert!(matches!(
+            result,
+            Err(GateRegistryError::InvalidParameters { .. })
+        ));
+
+        let mut registry = CustomGateRegistry::<Fr, 2>::empty();
+        let result = registry.build_from_id("MyGate { foo: 1 }");
+        assert_eq!(
+            result.err(),
+            Some(GateRegistryError::UnsupportedGates(vec![
+                "MyGate { foo: 1 }".to_string()
+            ]))
+        );
+
+        registry.register("MyGate", |_| {
+            Ok(CustomGateRef(Box::new(NoopGateConstrainer)))
+        });
+        assert!(registry.build_from_id("MyGate { foo: 1 }").is_ok());
+    }
+}
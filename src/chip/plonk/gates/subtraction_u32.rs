@@ -0,0 +1,275 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::goldilocks_chip::GoldilocksChipConfig,
+    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues},
+};
+
+use super::{
+    range_check_native,
+    registry::{GateDescriptor, GateRegistryError},
+    CustomGateConstrainer,
+};
+
+/// A gate to perform a subtraction on 32-bit limbs: given `x`, `y`, and `borrow`, it returns
+/// the result `x - y - borrow` and, if this underflows, a new `borrow`. Inputs are not range-checked.
+/// The plonky2 gate is `U32SubtractionGate` of plonky2-u32.
+#[derive(Clone, Debug)]
+pub struct U32SubtractionGateConstrainer {
+    pub num_ops: usize,
+}
+
+impl U32SubtractionGateConstrainer {
+    pub fn wire_ith_input_x(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        5 * i
+    }
+
+    pub fn wire_ith_input_y(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        5 * i + 1
+    }
+
+    pub fn wire_ith_input_borrow(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        5 * i + 2
+    }
+
+    pub fn wire_ith_output_result(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        5 * i + 3
+    }
+
+    pub fn wire_ith_output_borrow(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        5 * i + 4
+    }
+
+    pub fn limb_bits() -> usize {
+        2
+    }
+
+    pub fn num_limbs() -> usize {
+        32 / Self::limb_bits()
+    }
+
+    /// The `j`th limb of the `i`th result, in little-endian order.
+    pub fn wire_ith_output_jth_limb(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < Self::num_limbs());
+        5 * self.num_ops + Self::num_limbs() * i + j
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.num_ops * (3 + Self::num_limbs())
+    }
+}
+
+impl<F: PrimeField, const D: usize> CustomGateConstrainer<F, D> for U32SubtractionGateConstrainer {
+    fn descriptor(&self) -> GateDescriptor {
+        GateDescriptor::new("U32SubtractionGate").with_param("num_ops", self.num_ops)
+    }
+
+    fn eval_unfiltered_constraint(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        _local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        _public_inputs_hash: &AssignedHashValues<F>,
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+        let one = goldilocks_extension_chip.one_extension(ctx)?;
+        let base = goldilocks_extension_chip
+            .constant_extension_from_base(ctx, GoldilocksField::from_canonical_u64(1 << 32))?;
+        let limb_base = goldilocks_extension_chip.constant_extension_from_base(
+            ctx,
+            GoldilocksField::from_canonical_u64(1 << Self::limb_bits()),
+        )?;
+
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let input_x = &local_wires[self.wire_ith_input_x(i)];
+            let input_y = &local_wires[self.wire_ith_input_y(i)];
+            let input_borrow = &local_wires[self.wire_ith_input_borrow(i)];
+
+            let result_initial = goldilocks_extension_chip.sub_extension(ctx, input_x, input_y)?;
+            let result_initial =
+                goldilocks_extension_chip.sub_extension(ctx, &result_initial, input_borrow)?;
+
+            let output_result = &local_wires[self.wire_ith_output_result(i)];
+            let output_borrow = &local_wires[self.wire_ith_output_borrow(i)];
+
+            let computed_output = goldilocks_extension_chip.mul_add_extension(
+                ctx,
+                &base,
+                output_borrow,
+                &result_initial,
+            )?;
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                output_result,
+                &computed_output,
+            )?);
+
+            // Range-check output_result to be at most 32 bits.
+            let mut combined_limbs = goldilocks_extension_chip.zero_extension(ctx)?;
+            for j in (0..Self::num_limbs()).rev() {
+                let this_limb = &local_wires[self.wire_ith_output_jth_limb(i, j)];
+                constraints.push(goldilocks_extension_chip.range_check_extension(
+                    ctx,
+                    this_limb,
+                    1 << Self::limb_bits(),
+                )?);
+                combined_limbs = goldilocks_extension_chip.mul_add_extension(
+                    ctx,
+                    &combined_limbs,
+                    &limb_base,
+                    this_limb,
+                )?;
+            }
+            constraints.push(goldilocks_extension_chip.sub_extension(
+                ctx,
+                &combined_limbs,
+                output_result,
+            )?);
+
+            // Range-check output_borrow to be one bit.
+            let not_borrow = goldilocks_extension_chip.sub_extension(ctx, &one, output_borrow)?;
+            constraints.push(goldilocks_extension_chip.mul_extension(
+                ctx,
+                output_borrow,
+                &not_borrow,
+            )?);
+        }
+
+        Ok(constraints)
+    }
+
+    fn eval_unfiltered_native(
+        &self,
+        vars: EvaluationVars<GoldilocksField, D>,
+    ) -> Result<Vec<<GoldilocksField as Extendable<D>>::Extension>, GateRegistryError>
+    where
+        GoldilocksField: Extendable<D>,
+    {
+        let one = <GoldilocksField as Extendable<D>>::Extension::ONE;
+        let base = <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(1 << 32);
+        let limb_base = <GoldilocksField as Extendable<D>>::Extension::from_canonical_u64(
+            1 << Self::limb_bits(),
+        );
+
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let input_x = vars.local_wires[self.wire_ith_input_x(i)];
+            let input_y = vars.local_wires[self.wire_ith_input_y(i)];
+            let input_borrow = vars.local_wires[self.wire_ith_input_borrow(i)];
+            let result_initial = input_x - input_y - input_borrow;
+
+            let output_result = vars.local_wires[self.wire_ith_output_result(i)];
+            let output_borrow = vars.local_wires[self.wire_ith_output_borrow(i)];
+            constraints.push(output_result - (base * output_borrow + result_initial));
+
+            let mut combined_limbs = <GoldilocksField as Extendable<D>>::Extension::ZERO;
+            for j in (0..Self::num_limbs()).rev() {
+                let this_limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                constraints.push(range_check_native(this_limb, 1 << Self::limb_bits()));
+                combined_limbs = combined_limbs * limb_base + this_limb;
+            }
+            constraints.push(combined_limbs - output_result);
+            constraints.push(output_borrow * (one - output_borrow));
+        }
+        Ok(constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::U32SubtractionGateConstrainer;
+    use crate::chip::plonk::gates::gate_test::{custom_gate_satisfied, test_custom_gate};
+    use plonky2::{
+        field::{
+            extension::quadratic::QuadraticExtension,
+            goldilocks_field::GoldilocksField,
+            types::{Field, Sample},
+        },
+        plonk::circuit_data::CircuitConfig,
+    };
+    use plonky2_u32::gates::subtraction_u32::U32SubtractionGate;
+    use rand::Rng;
+
+    type FE = QuadraticExtension<GoldilocksField>;
+
+    fn generate_wires(
+        gate: &U32SubtractionGateConstrainer,
+        inputs: &[(u32, u32, bool)],
+    ) -> Vec<FE> {
+        let mut wires =
+            FE::rand_vec((5 + U32SubtractionGateConstrainer::num_limbs()) * gate.num_ops);
+        let mut set = |wire: usize, value: u64| {
+            wires[wire] = FE::from_canonical_u64(value);
+        };
+        for (i, &(x, y, borrow)) in inputs.iter().enumerate() {
+            set(gate.wire_ith_input_x(i), x as u64);
+            set(gate.wire_ith_input_y(i), y as u64);
+            set(gate.wire_ith_input_borrow(i), borrow as u64);
+            let (result, underflow_0) = x.overflowing_sub(y);
+            let (result, underflow_1) = result.overflowing_sub(borrow as u32);
+            set(gate.wire_ith_output_result(i), result as u64);
+            set(
+                gate.wire_ith_output_borrow(i),
+                (underflow_0 || underflow_1) as u64,
+            );
+            for j in 0..U32SubtractionGateConstrainer::num_limbs() {
+                set(
+                    gate.wire_ith_output_jth_limb(i, j),
+                    (result as u64 >> (2 * j)) & 3,
+                );
+            }
+        }
+        wires
+    }
+
+    #[test]
+    fn test_subtraction_u32_gate() {
+        let gate = U32SubtractionGateConstrainer { num_ops: 3 };
+        let mut rng = rand::thread_rng();
+        let mut inputs = (0..gate.num_ops)
+            .map(|_| rng.gen())
+            .collect::<Vec<(u32, u32, bool)>>();
+        inputs[0] = (0, 0, true);
+        let num_constraints = gate.num_constraints();
+
+        let wires = generate_wires(&gate, &inputs);
+        assert!(custom_gate_satisfied(
+            gate.clone(),
+            &wires,
+            num_constraints,
+            17
+        ));
+
+        let mut wrong_wires = wires;
+        wrong_wires[gate.wire_ith_output_borrow(0)] = FE::ZERO;
+        assert!(!custom_gate_satisfied(
+            gate,
+            &wrong_wires,
+            num_constraints,
+            17
+        ));
+    }
+
+    #[test]
+    fn test_subtraction_u32_gate_against_plonky2() {
+        let plonky2_gate =
+            U32SubtractionGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        let halo2_gate = U32SubtractionGateConstrainer {
+            num_ops: plonky2_gate.num_ops,
+        };
+        test_custom_gate(plonky2_gate, halo2_gate, 17);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use halo2wrong_maingate::AssignedValue;
+use plonky2::field::{goldilocks_field::GoldilocksField, types::Field};
+
+use crate::{
+    chip::{
+        goldilocks_extension_chip::GoldilocksExtensionChip,
+        hasher_chip::HasherInstructions,
+        plonk::{
+            gates::{lookup::LookupGateConstrainer, lookup_table::LookupTableGateConstrainer},
+            plonk_verifier_chip::PlonkVerifierChip,
+        },
+    },
+    types::{assigned::AssignedExtensionFieldValue, common_data::CommonData},
+};
+
+/// Number of lookup challenges drawn for each challenge round.
+pub const NUM_COINS_LOOKUP: usize = 4;
+
+/// Position of each lookup challenge within the `NUM_COINS_LOOKUP` challenges of a round.
+pub enum LookupChallenges {
+    /// Combines the input and output of looking and looked pairs.
+    ChallengeA = 0,
+    /// Combines the input and output of the pairs of a lookup table.
+    ChallengeB = 1,
+    /// Point at which the logarithmic derivatives are evaluated.
+    ChallengeAlpha = 2,
+    /// Point at which the lookup tables are evaluated.
+    ChallengeDelta = 3,
+}
+
+/// Position of each lookup selector among the lookup selectors.
+pub enum LookupSelectors {
+    /// Rows of `LookupTableGate`s, where the RE and Sum transitions are checked.
+    TransSre = 0,
+    /// Rows of `LookupGate`s, where the LDC transitions are checked.
+    TransLdc,
+    /// First row of the lookup tables, where RE and Sum start at zero.
+    InitSre,
+    /// Last row of the lookups, where the combined SLDC polynomial ends at zero.
+    LastLdc,
+    /// One selector per lookup table, for the row where its RE polynomial ends.
+    StartEnd,
+}
+
+impl<F: PrimeField, const D: usize, H: HasherInstructions<F>> PlonkVerifierChip<F, D, H> {
+    /// Evaluates the lookup constraints of one challenge round, following plonky2's logarithmic
+    /// derivative lookup argument.
+    ///
+    /// There are three polynomials to check:
+    /// - RE ensures the lookup tables are well formed;
+    /// - Sum is a running sum of `m_i / (alpha - (input_i + a * output_i))` over the pairs of the
+    ///   lookup tables, where `m_i` is the number of times the pair is looked up;
+    /// - LDC is a running sum of `1 / (alpha - (input_i + a * output_i))` over the looked up pairs.
+    ///
+    /// Sum and LDC share the same partial SLDC polynomials, which hold `Sum - LDC`, so that the
+    /// final check `Sum(end) = LDC(end)` becomes `SLDC(end) = 0`.
+    pub(crate) fn check_lookup_constraints(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        common_data: &CommonData<F, D>,
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
+        local_lookup_zs: &[AssignedExtensionFieldValue<F, D>],
+        next_lookup_zs: &[AssignedExtensionFieldValue<F, D>],
+        lookup_selectors: &[AssignedExtensionFieldValue<F, D>],
+        deltas: &[AssignedValue<F>],
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
+        let num_lu_slots = LookupGateConstrainer::num_slots(&common_data.config);
+        let num_lut_slots = LookupTableGateConstrainer::num_slots(&common_data.config);
+        let lu_degree = common_data.quotient_degree_factor - 1;
+        let num_sldc_polys = local_lookup_zs.len() - 1;
+        let lut_degree = num_lut_slots.div_ceil(num_sldc_polys);
+
+        let mut constraints = Vec::new();
+
+        // RE is the first polynomial stored.
+        let z_re = &local_lookup_zs[0];
+        let next_z_re = &next_lookup_zs[0];
+
+        // Partial Sums and LDCs are both stored in the remaining SLDC polynomials.
+        let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
+        let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
+
+        let challenge_a = goldilocks_extension_chip
+            .convert_to_extension(ctx, &deltas[LookupChallenges::ChallengeA as usize])?;
+        let challenge_b = goldilocks_extension_chip
+            .convert_to_extension(ctx, &deltas[LookupChallenges::ChallengeB as usize])?;
+        let challenge_alpha = goldilocks_extension_chip
+            .convert_to_extension(ctx, &deltas[LookupChallenges::ChallengeAlpha as usize])?;
+        let challenge_delta = goldilocks_extension_chip
+            .convert_to_extension(ctx, &deltas[LookupChallenges::ChallengeDelta as usize])?;
+
+        // `alpha - (input + a * output)` for the looked pairs, i.e. the pairs of the lookup table.
+        let looked_denominators = (0..num_lut_slots)
+            .map(|s| {
+                let combo = goldilocks_extension_chip.mul_add_extension(
+                    ctx,
+                    &challenge_a,
+                    &local_wires[LookupTableGateConstrainer::wire_ith_looked_out(s)],
+                    &local_wires[LookupTableGateConstrainer::wire_ith_looked_inp(s)],
+                )?;
+                goldilocks_extension_chip.sub_extension(ctx, &challenge_alpha, &combo)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // `alpha - (input + a * output)` for the looking pairs.
+        let looking_denominators = (0..num_lu_slots)
+            .map(|s| {
+                let combo = goldilocks_extension_chip.mul_add_extension(
+                    ctx,
+                    &challenge_a,
+                    &local_wires[LookupGateConstrainer::wire_ith_looking_out(s)],
+                    &local_wires[LookupGateConstrainer::wire_ith_looking_inp(s)],
+                )?;
+                goldilocks_extension_chip.sub_extension(ctx, &challenge_alpha, &combo)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // `input + b * output` for the pairs of the lookup table, used to check the RE polynomial.
+        let lookup_combos = (0..num_lut_slots)
+            .map(|s| {
+                goldilocks_extension_chip.mul_add_extension(
+                    ctx,
+                    &challenge_b,
+                    &local_wires[LookupTableGateConstrainer::wire_ith_looked_out(s)],
+                    &local_wires[LookupTableGateConstrainer::wire_ith_looked_inp(s)],
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // Check last LDC constraint.
+        constraints.push(goldilocks_extension_chip.mul_extension(
+            ctx,
+            &lookup_selectors[LookupSelectors::LastLdc as usize],
+            &z_x_lookup_sldcs[num_sldc_polys - 1],
+        )?);
+
+        // Check initial Sum constraint.
+        constraints.push(goldilocks_extension_chip.mul_extension(
+            ctx,
+            &lookup_selectors[LookupSelectors::InitSre as usize],
+            &z_x_lookup_sldcs[0],
+        )?);
+
+        // Check initial RE constraint.
+        constraints.push(goldilocks_extension_chip.mul_extension(
+            ctx,
+            &lookup_selectors[LookupSelectors::InitSre as usize],
+            z_re,
+        )?);
+
+        // Check final RE constraints for each different LUT.
+        for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
+            let lut = &common_data.luts[r - LookupSelectors::StartEnd as usize];
+            let lut_row_number = lut.len().div_ceil(num_lut_slots);
+            let lut_eval = self.eval_lut_poly(
+                ctx,
+                lut,
+                &deltas[LookupChallenges::ChallengeB as usize],
+                &deltas[LookupChallenges::ChallengeDelta as usize],
+                num_lut_slots * lut_row_number,
+            )?;
+            let lut_eval = goldilocks_extension_chip.convert_to_extension(ctx, &lut_eval)?;
+            let diff = goldilocks_extension_chip.sub_extension(ctx, z_re, &lut_eval)?;
+            constraints.push(goldilocks_extension_chip.mul_extension(
+                ctx,
+                &lookup_selectors[r],
+                &diff,
+            )?);
+        }
+
+        // Check RE row transition constraint.
+        let mut cur_sum = next_z_re.clone();
+        for combo in &lookup_combos {
+            cur_sum = goldilocks_extension_chip.mul_add_extension(
+                ctx,
+                &cur_sum,
+                &challenge_delta,
+                combo,
+            )?;
+        }
+        let unfiltered_re_line = goldilocks_extension_chip.sub_extension(ctx, z_re, &cur_sum)?;
+        constraints.push(goldilocks_extension_chip.mul_extension(
+            ctx,
+            &lookup_selectors[LookupSelectors::TransSre as usize],
+            &unfiltered_re_line,
+        )?);
+
+        let zero = goldilocks_extension_chip.zero_extension(ctx)?;
+        for poly in 0..num_sldc_polys {
+            let lut_range = (poly * lut_degree).min(num_lut_slots)
+                ..((poly + 1) * lut_degree).min(num_lut_slots);
+            let lu_range =
+                (poly * lu_degree).min(num_lu_slots)..((poly + 1) * lu_degree).min(num_lu_slots);
+
+            let lut_prod = goldilocks_extension_chip
+                .mul_many_extension(ctx, looked_denominators[lut_range.clone()].to_vec())?;
+            let lu_prod = goldilocks_extension_chip
+                .mul_many_extension(ctx, looking_denominators[lu_range.clone()].to_vec())?;
+
+            // `\sum_i m_i \prod_{j \neq i} (alpha - looked_combo_j)`.
+            let mut sum_prod_lut = zero.clone();
+            for i in lut_range.clone() {
+                let prod_i = goldilocks_extension_chip.mul_many_extension(
+                    ctx,
+                    lut_range
+                        .clone()
+                        .filter(|&j| j != i)
+                        .map(|j| looked_denominators[j].clone())
+                        .collect(),
+                )?;
+                let multiplicity =
+                    &local_wires[LookupTableGateConstrainer::wire_ith_multiplicity(i)];
+                sum_prod_lut = goldilocks_extension_chip.mul_add_extension(
+                    ctx,
+                    &prod_i,
+                    multiplicity,
+                    &sum_prod_lut,
+                )?;
+            }
+
+            // `\sum_i \prod_{j \neq i} (alpha - looking_combo_j)`.
+            let mut sum_prod_lu = zero.clone();
+            for i in lu_range.clone() {
+                let prod_i = goldilocks_extension_chip.mul_many_extension(
+                    ctx,
+                    lu_range
+                        .clone()
+                        .filter(|&j| j != i)
+                        .map(|j| looking_denominators[j].clone())
+                        .collect(),
+                )?;
+                sum_prod_lu =
+                    goldilocks_extension_chip.add_extension(ctx, &sum_prod_lu, &prod_i)?;
+            }
+
+            // The previous element is the previous poly of the current row or the last poly of the
+            // next row.
+            let prev = if poly == 0 {
+                &z_gx_lookup_sldcs[num_sldc_polys - 1]
+            } else {
+                &z_x_lookup_sldcs[poly - 1]
+            };
+            let diff =
+                goldilocks_extension_chip.sub_extension(ctx, &z_x_lookup_sldcs[poly], prev)?;
+
+            // Check Sum row and col transitions. It's the same constraint, with a row transition
+            // happening for slot == 0.
+            let unfiltered_sum_transition = goldilocks_extension_chip.mul_sub_extension(
+                ctx,
+                &lut_prod,
+                &diff,
+                &sum_prod_lut,
+            )?;
+            constraints.push(goldilocks_extension_chip.mul_extension(
+                ctx,
+                &lookup_selectors[LookupSelectors::TransSre as usize],
+                &unfiltered_sum_transition,
+            )?);
+
+            // Check LDC row and col transitions. It's the same constraint, with a row transition
+            // happening for slot == 0.
+            let unfiltered_ldc_transition =
+                goldilocks_extension_chip.mul_add_extension(ctx, &lu_prod, &diff, &sum_prod_lu)?;
+            constraints.push(goldilocks_extension_chip.mul_extension(
+                ctx,
+                &lookup_selectors[LookupSelectors::TransLdc as usize],
+                &unfiltered_ldc_transition,
+            )?);
+        }
+
+        Ok(constraints)
+    }
+
+    /// Evaluates at `delta` the polynomial whose coefficients, from the highest degree down, are
+    /// `input_i + b * output_i` for the pairs of `lut`, padded with zeros up to `degree`
+    /// coefficients. The input and output parts are evaluated separately, so that every
+    /// coefficient is a constant.
+    fn eval_lut_poly(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lut: &[(u16, u16)],
+        b: &AssignedValue<F>,
+        delta: &AssignedValue<F>,
+        degree: usize,
+    ) -> Result<AssignedValue<F>, Error> {
+        let goldilocks_chip = self.goldilocks_chip();
+        let mut inputs_eval = goldilocks_chip.assign_constant(ctx, GoldilocksField::ZERO)?;
+        let mut outputs_eval = inputs_eval.clone();
+        for i in 0..degree {
+            let (input, output) = lut.get(i).copied().unwrap_or_default();
+            inputs_eval = goldilocks_chip.mul_add_constant(
+                ctx,
+                &inputs_eval,
+                delta,
+                GoldilocksField::from_canonical_u16(input),
+            )?;
+            outputs_eval = goldilocks_chip.mul_add_constant(
+                ctx,
+                &outputs_eval,
+                delta,
+                GoldilocksField::from_canonical_u16(output),
+            )?;
+        }
+        goldilocks_chip.mul_add(ctx, b, &outputs_eval, &inputs_eval)
+    }
+}
@@ -1,3 +1,4 @@
 pub mod gates;
+pub mod lookup;
 pub mod plonk_verifier_chip;
 pub mod vanishing_poly;
@@ -4,12 +4,13 @@ use crate::{
         fri_chip::FriVerifierChip,
         goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
         goldilocks_extension_chip::GoldilocksExtensionChip,
+        hasher_chip::{HasherChip, HasherInstructions},
+        plonk::lookup::NUM_COINS_LOOKUP,
         public_inputs_hasher_chip::PublicInputsHasherChip,
         transcript_chip::TranscriptChip,
     },
     types::{
         assigned::{
-            AssignedExtensionFieldValue, AssignedFriChallenges, AssignedFriProofValues,
             AssignedHashValues, AssignedProofChallenges, AssignedProofValues,
             AssignedVerificationKeyValues,
         },
@@ -23,15 +24,20 @@ use plonky2::field::{
     goldilocks_field::GoldilocksField,
     types::{Field, PrimeField64},
 };
+use std::marker::PhantomData;
 
-pub struct PlonkVerifierChip<F: PrimeField> {
+/// Verifies plonky2 proofs whose Merkle trees and challenger use the hasher `H`.
+pub struct PlonkVerifierChip<F: PrimeField, const D: usize, H: HasherInstructions<F> = HasherChip<F>>
+{
     pub goldilocks_chip_config: GoldilocksChipConfig<F>,
+    _marker: PhantomData<H>,
 }
 
-impl<F: PrimeField> PlonkVerifierChip<F> {
+impl<F: PrimeField, const D: usize, H: HasherInstructions<F>> PlonkVerifierChip<F, D, H> {
     pub fn construct(goldilocks_chip_config: &GoldilocksChipConfig<F>) -> Self {
         Self {
             goldilocks_chip_config: goldilocks_chip_config.clone(),
+            _marker: PhantomData,
         }
     }
 
@@ -57,11 +63,11 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
         ctx: &mut RegionCtx<'_, F>,
         public_inputs_hash: &AssignedHashValues<F>,
         circuit_digest: &AssignedHashValues<F>,
-        common_data: &CommonData<F>,
-        assigned_proof: &AssignedProofValues<F, 2>,
+        common_data: &CommonData<F, D>,
+        assigned_proof: &AssignedProofValues<F, D>,
         num_challenges: usize,
-    ) -> Result<AssignedProofChallenges<F, 2>, Error> {
-        let mut transcript_chip = TranscriptChip::<F>::new(ctx, &self.goldilocks_chip_config)?;
+    ) -> Result<AssignedProofChallenges<F, D>, Error> {
+        let mut transcript_chip = TranscriptChip::<F, H>::new(ctx, &self.goldilocks_chip_config)?;
         for e in circuit_digest.elements.iter() {
             transcript_chip.write_scalar(ctx, &e)?;
         }
@@ -75,94 +81,85 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
             plonk_zs_partial_products_cap,
             quotient_polys_cap,
             openings,
-            opening_proof:
-                AssignedFriProofValues {
-                    commit_phase_merkle_cap_values,
-                    final_poly,
-                    pow_witness,
-                    ..
-                },
+            opening_proof,
         } = assigned_proof;
         for hash in wires_cap.0.iter() {
             for e in hash.elements.iter() {
                 transcript_chip.write_scalar(ctx, &e)?;
             }
         }
-        let plonk_betas = transcript_chip.squeeze(ctx, num_challenges)?;
-        let plonk_gammas = transcript_chip.squeeze(ctx, num_challenges)?;
+        let plonk_betas = ctx.namespaced(
+            || "transcript squeeze plonk_betas",
+            |ctx| transcript_chip.squeeze(ctx, num_challenges),
+        )?;
+        let plonk_gammas = ctx.namespaced(
+            || "transcript squeeze plonk_gammas",
+            |ctx| transcript_chip.squeeze(ctx, num_challenges),
+        )?;
+
+        // With lookups, the betas and gammas are reused as the first lookup challenges, and only
+        // the remaining ones are squeezed.
+        let plonk_deltas = if common_data.has_lookup() {
+            let num_lookup_challenges = NUM_COINS_LOOKUP * num_challenges;
+            let additional = ctx.namespaced(
+                || "transcript squeeze plonk_deltas",
+                |ctx| transcript_chip.squeeze(ctx, num_lookup_challenges - 2 * num_challenges),
+            )?;
+            [plonk_betas.clone(), plonk_gammas.clone(), additional].concat()
+        } else {
+            vec![]
+        };
 
+        // `plonk_zs_partial_products_cap` also commits to the lookup polynomials.
         for hash in plonk_zs_partial_products_cap.0.iter() {
             for e in hash.elements.iter() {
                 transcript_chip.write_scalar(ctx, &e)?;
             }
         }
-        let plonk_alphas = transcript_chip.squeeze(ctx, num_challenges)?;
+        let plonk_alphas = ctx.namespaced(
+            || "transcript squeeze plonk_alphas",
+            |ctx| transcript_chip.squeeze(ctx, num_challenges),
+        )?;
 
         for hash in quotient_polys_cap.0.iter() {
             for e in hash.elements.iter() {
                 transcript_chip.write_scalar(ctx, &e)?;
             }
         }
-        let plonk_zeta = transcript_chip.squeeze(ctx, 2)?;
-
-        let fri_openings = openings.to_fri_openings();
-
-        for v in fri_openings.batches {
-            for ext in v.values {
-                transcript_chip.write_extension(ctx, &ext)?;
-            }
-        }
-
-        // Scaling factor to combine polynomials.
-        let fri_alpha =
-            AssignedExtensionFieldValue(transcript_chip.squeeze(ctx, 2)?.try_into().unwrap());
-
-        // Recover the random betas used in the FRI reductions.
-        let fri_betas = commit_phase_merkle_cap_values
-            .iter()
-            .map(|cap| {
-                transcript_chip.write_cap(ctx, cap)?;
-                let fri_beta = transcript_chip.squeeze(ctx, 2)?;
-                Ok(AssignedExtensionFieldValue(fri_beta.try_into().unwrap()))
-            })
-            .collect::<Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error>>()?;
-
-        for ext in final_poly.0.iter() {
-            for e in ext.0.iter() {
-                transcript_chip.write_scalar(ctx, &e)?;
-            }
-        }
+        let plonk_zeta = ctx.namespaced(
+            || "transcript squeeze plonk_zeta",
+            |ctx| transcript_chip.squeeze_extension(ctx),
+        )?;
 
-        transcript_chip.write_scalar(ctx, pow_witness)?;
-        let fri_pow_response = transcript_chip.squeeze(ctx, 1)?[0].clone();
+        transcript_chip.write_fri_openings(ctx, &openings.to_fri_openings())?;
 
-        let num_fri_queries = common_data.config.fri_config.num_query_rounds;
-        let fri_query_indices = transcript_chip.squeeze(ctx, num_fri_queries)?;
+        let fri_challenges = transcript_chip.fri_challenges(
+            ctx,
+            opening_proof,
+            common_data.config.fri_config.num_query_rounds,
+        )?;
 
         Ok(AssignedProofChallenges {
             plonk_betas,
             plonk_gammas,
+            plonk_deltas,
             plonk_alphas,
-            plonk_zeta: AssignedExtensionFieldValue(plonk_zeta.try_into().unwrap()),
-            fri_challenges: AssignedFriChallenges {
-                fri_alpha,
-                fri_betas,
-                fri_pow_response,
-                fri_query_indices,
-            },
+            plonk_zeta,
+            fri_challenges,
         })
     }
 
     pub fn verify_proof_with_challenges(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        proof: &AssignedProofValues<F, 2>,
+        proof: &AssignedProofValues<F, D>,
         public_inputs_hash: &AssignedHashValues<F>,
-        challenges: &AssignedProofChallenges<F, 2>,
+        challenges: &AssignedProofChallenges<F, D>,
         vk: &AssignedVerificationKeyValues<F>,
-        common_data: &CommonData<F>,
+        common_data: &CommonData<F, D>,
     ) -> Result<(), Error> {
-        let goldilocks_extension_chip = GoldilocksExtensionChip::new(&self.goldilocks_chip_config);
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
         let one = goldilocks_extension_chip.one_extension(ctx)?;
         let local_constants = &proof.openings.constants.clone();
         let local_wires = &proof.openings.wires;
@@ -170,6 +167,8 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
         let next_zs = &proof.openings.plonk_zs_next;
         let s_sigmas = &proof.openings.plonk_sigmas;
         let partial_products = &proof.openings.partial_products;
+        let local_lookup_zs = &proof.openings.lookup_zs;
+        let next_lookup_zs = &proof.openings.lookup_zs_next;
 
         let zeta_pow_deg = goldilocks_extension_chip.exp_power_of_2_extension(
             ctx,
@@ -186,11 +185,14 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
             public_inputs_hash,
             local_zs,
             next_zs,
+            local_lookup_zs,
+            next_lookup_zs,
             partial_products,
             s_sigmas,
             &challenges.plonk_betas,
             &challenges.plonk_gammas,
             &challenges.plonk_alphas,
+            &challenges.plonk_deltas,
         )?;
         let quotient_polys_zeta = &proof.openings.quotient_polys;
         let z_h_zeta = goldilocks_extension_chip.sub_extension(ctx, &zeta_pow_deg, &one)?;
@@ -200,12 +202,20 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
         {
             let recombined_quotient =
                 goldilocks_extension_chip.reduce_extension(ctx, &zeta_pow_deg, &chunk.to_vec())?;
-            let computed_vanishing_poly =
-                goldilocks_extension_chip.mul_extension(ctx, &z_h_zeta, &recombined_quotient)?;
-            goldilocks_extension_chip.assert_equal_extension(
-                ctx,
-                &vanishing_poly_zeta[i],
-                &computed_vanishing_poly,
+            ctx.check(
+                || format!("vanishing polynomial mismatch for challenge {i}"),
+                |ctx| {
+                    let computed_vanishing_poly = goldilocks_extension_chip.mul_extension(
+                        ctx,
+                        &z_h_zeta,
+                        &recombined_quotient,
+                    )?;
+                    goldilocks_extension_chip.assert_equal_extension(
+                        ctx,
+                        &vanishing_poly_zeta[i],
+                        &computed_vanishing_poly,
+                    )
+                },
             )?;
         }
 
@@ -225,7 +235,7 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
         let offset = self
             .goldilocks_chip()
             .assign_constant(ctx, GoldilocksField::MULTIPLICATIVE_GROUP_GENERATOR)?;
-        let fri_chip = FriVerifierChip::construct(
+        let fri_chip = FriVerifierChip::<F, D, H>::construct(
             &self.goldilocks_chip_config,
             &offset,
             common_data.fri_params.clone(),
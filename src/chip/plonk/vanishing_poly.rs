@@ -7,32 +7,37 @@ use plonky2::field::{goldilocks_field::GoldilocksField, types::Field};
 
 use crate::{
     chip::goldilocks_extension_chip::GoldilocksExtensionChip,
-    chip::plonk::plonk_verifier_chip::PlonkVerifierChip,
+    chip::hasher_chip::HasherInstructions,
+    chip::plonk::{lookup::NUM_COINS_LOOKUP, plonk_verifier_chip::PlonkVerifierChip},
     types::{
         assigned::{AssignedExtensionFieldValue, AssignedHashValues},
         common_data::CommonData,
     },
 };
 
-impl<F: PrimeField> PlonkVerifierChip<F> {
+impl<F: PrimeField, const D: usize, H: HasherInstructions<F>> PlonkVerifierChip<F, D, H> {
     pub fn eval_vanishing_poly(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        common_data: &CommonData<F>,
-        x: &AssignedExtensionFieldValue<F, 2>,
-        x_pow_deg: &AssignedExtensionFieldValue<F, 2>,
-        local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        common_data: &CommonData<F, D>,
+        x: &AssignedExtensionFieldValue<F, D>,
+        x_pow_deg: &AssignedExtensionFieldValue<F, D>,
+        local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         public_inputs_hash: &AssignedHashValues<F>,
-        local_zs: &[AssignedExtensionFieldValue<F, 2>],
-        next_zs: &[AssignedExtensionFieldValue<F, 2>],
-        partial_products: &[AssignedExtensionFieldValue<F, 2>],
-        s_sigmas: &[AssignedExtensionFieldValue<F, 2>],
+        local_zs: &[AssignedExtensionFieldValue<F, D>],
+        next_zs: &[AssignedExtensionFieldValue<F, D>],
+        local_lookup_zs: &[AssignedExtensionFieldValue<F, D>],
+        next_lookup_zs: &[AssignedExtensionFieldValue<F, D>],
+        partial_products: &[AssignedExtensionFieldValue<F, D>],
+        s_sigmas: &[AssignedExtensionFieldValue<F, D>],
         betas: &[AssignedValue<F>],
         gammas: &[AssignedValue<F>],
         alphas: &[AssignedValue<F>],
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
-        let goldilocks_extension_chip = GoldilocksExtensionChip::new(&self.goldilocks_chip_config);
+        deltas: &[AssignedValue<F>],
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
         let max_degree = common_data.quotient_degree_factor;
         let num_prods = common_data.num_partial_products;
 
@@ -44,8 +49,14 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
             public_inputs_hash,
         )?;
 
+        let num_selectors = common_data.selectors_info.num_selectors();
+        let lookup_selectors =
+            &local_constants[num_selectors..num_selectors + common_data.num_lookup_selectors];
+
         // The L_0(x) (Z(x) - 1) vanishing terms.
         let mut vanishing_z_1_terms = Vec::new();
+        // The terms checking the lookup constraints, if any.
+        let mut vanishing_all_lookup_terms = Vec::new();
         // The terms checking the partial products.
         let mut vanishing_partial_products_terms = Vec::new();
 
@@ -64,6 +75,21 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
             vanishing_z_1_terms
                 .push(goldilocks_extension_chip.mul_sub_extension(ctx, &l_0_x, z_x, &l_0_x)?);
 
+            if common_data.has_lookup() {
+                let lookup_polys_range =
+                    common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1);
+                let lookup_constraints = self.check_lookup_constraints(
+                    ctx,
+                    common_data,
+                    local_wires,
+                    &local_lookup_zs[lookup_polys_range.clone()],
+                    &next_lookup_zs[lookup_polys_range],
+                    lookup_selectors,
+                    &deltas[NUM_COINS_LOOKUP * i..NUM_COINS_LOOKUP * (i + 1)],
+                )?;
+                vanishing_all_lookup_terms.extend(lookup_constraints);
+            }
+
             let mut numerator_values = vec![];
             let mut denominator_values = vec![];
 
@@ -110,6 +136,7 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
         let vanishing_terms = [
             vanishing_z_1_terms,
             vanishing_partial_products_terms,
+            vanishing_all_lookup_terms,
             constraint_terms,
         ]
         .concat();
@@ -126,27 +153,34 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
     fn eval_gate_constraints(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        common_data: &CommonData<F>,
-        local_constants: &[AssignedExtensionFieldValue<F, 2>],
-        local_wires: &[AssignedExtensionFieldValue<F, 2>],
+        common_data: &CommonData<F, D>,
+        local_constants: &[AssignedExtensionFieldValue<F, D>],
+        local_wires: &[AssignedExtensionFieldValue<F, D>],
         public_inputs_hash: &AssignedHashValues<F>,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
-        let goldilocks_extension_chip = GoldilocksExtensionChip::new(&self.goldilocks_chip_config);
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
         let zero_extension = goldilocks_extension_chip.zero_extension(ctx)?;
         let mut all_gate_constraints = vec![zero_extension; common_data.num_gate_constraints];
         for (i, gate) in common_data.gates.iter().enumerate() {
             let selector_index = common_data.selectors_info.selector_indices[i];
-            gate.0.eval_filtered_constraint(
-                ctx,
-                &self.goldilocks_chip_config,
-                local_constants,
-                local_wires,
-                public_inputs_hash,
-                i,
-                selector_index,
-                common_data.selectors_info.groups[selector_index].clone(),
-                common_data.selectors_info.num_selectors(),
-                &mut all_gate_constraints,
+            ctx.namespaced(
+                || format!("gate {i} ({})", gate.0.descriptor().name),
+                |ctx| {
+                    gate.0.eval_filtered_constraint(
+                        ctx,
+                        &self.goldilocks_chip_config,
+                        local_constants,
+                        local_wires,
+                        public_inputs_hash,
+                        i,
+                        selector_index,
+                        common_data.selectors_info.groups[selector_index].clone(),
+                        common_data.selectors_info.num_selectors(),
+                        common_data.num_lookup_selectors,
+                        &mut all_gate_constraints,
+                    )
+                },
             )?;
         }
         Ok(all_gate_constraints)
@@ -156,15 +190,16 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
         &self,
         ctx: &mut RegionCtx<'_, F>,
         n: usize,
-        x: &AssignedExtensionFieldValue<F, 2>,
-        x_pow_n: &AssignedExtensionFieldValue<F, 2>,
-    ) -> Result<AssignedExtensionFieldValue<F, 2>, Error> {
-        let goldilocks_extension_chip = GoldilocksExtensionChip::new(&self.goldilocks_chip_config);
+        x: &AssignedExtensionFieldValue<F, D>,
+        x_pow_n: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<AssignedExtensionFieldValue<F, D>, Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
         // L_0(x) = (x^n - 1) / (n * (x - 1))
         //        = (x_pow_deg - 1) / (n * (x - 1))
         let one_extension = goldilocks_extension_chip.one_extension(ctx)?;
-        let neg_one_extension = goldilocks_extension_chip
-            .constant_extension(ctx, &[-GoldilocksField::ONE, GoldilocksField::ZERO])?;
+        let neg_one_extension =
+            goldilocks_extension_chip.constant_extension_from_base(ctx, -GoldilocksField::ONE)?;
         let zero_poly = goldilocks_extension_chip.sub_extension(ctx, &x_pow_n, &one_extension)?;
         let denominator = goldilocks_extension_chip.arithmetic_extension(
             ctx,
@@ -183,14 +218,15 @@ impl<F: PrimeField> PlonkVerifierChip<F> {
     fn check_partial_products(
         &self,
         ctx: &mut RegionCtx<'_, F>,
-        numerators: &[AssignedExtensionFieldValue<F, 2>],
-        denominators: &[AssignedExtensionFieldValue<F, 2>],
-        partials: &[AssignedExtensionFieldValue<F, 2>],
-        z_x: &AssignedExtensionFieldValue<F, 2>,
-        z_gx: &AssignedExtensionFieldValue<F, 2>,
+        numerators: &[AssignedExtensionFieldValue<F, D>],
+        denominators: &[AssignedExtensionFieldValue<F, D>],
+        partials: &[AssignedExtensionFieldValue<F, D>],
+        z_x: &AssignedExtensionFieldValue<F, D>,
+        z_gx: &AssignedExtensionFieldValue<F, D>,
         max_degree: usize,
-    ) -> Result<Vec<AssignedExtensionFieldValue<F, 2>>, Error> {
-        let goldilocks_extension_chip = GoldilocksExtensionChip::new(&self.goldilocks_chip_config);
+    ) -> Result<Vec<AssignedExtensionFieldValue<F, D>>, Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
         let product_accs = iter::once(z_x)
             .chain(partials.iter())
             .chain(iter::once(z_gx));
@@ -0,0 +1,203 @@
+use halo2_proofs::{
+    halo2curves::ff::PrimeField,
+    plonk::{ConstraintSystem, Error},
+};
+use halo2wrong_maingate::AssignedValue;
+use plonky2::{
+    field::{goldilocks_field::GoldilocksField, types::Field},
+    hash::poseidon::{SPONGE_RATE, SPONGE_WIDTH},
+};
+
+use crate::{context::RegionCtx, types::assigned::AssignedHashValues};
+
+use super::{
+    goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+    hasher_chip::HasherInstructions,
+    native_chip::all_chip::AllChipConfig,
+};
+
+/// Constrains the hasher of `Bn254Poseidon2GoldilocksConfig`, with `AllChip::permute_poseidon2`.
+#[derive(Debug, Clone)]
+pub struct Poseidon2HasherChip<F: PrimeField> {
+    goldilocks_chip_config: GoldilocksChipConfig<F>,
+}
+
+impl<F: PrimeField> Poseidon2HasherChip<F> {
+    pub fn goldilocks_chip(&self) -> GoldilocksChip<F> {
+        GoldilocksChip::new(&self.goldilocks_chip_config)
+    }
+
+    fn zero_state(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+    ) -> Result<[AssignedValue<F>; SPONGE_WIDTH], Error> {
+        let zero = self
+            .goldilocks_chip()
+            .assign_constant(ctx, GoldilocksField::ZERO)?;
+        Ok([(); SPONGE_WIDTH].map(|_| zero.clone()))
+    }
+}
+
+impl<F: PrimeField> HasherInstructions<F> for Poseidon2HasherChip<F> {
+    fn configure(meta: &mut ConstraintSystem<F>) -> GoldilocksChipConfig<F> {
+        GoldilocksChip::configure(&AllChipConfig::configure_with_poseidon2(meta))
+    }
+
+    fn new(
+        _ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            goldilocks_chip_config: goldilocks_chip_config.clone(),
+        })
+    }
+
+    fn permute_state(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedValue<F>; SPONGE_WIDTH],
+    ) -> Result<[AssignedValue<F>; SPONGE_WIDTH], Error> {
+        self.goldilocks_chip()
+            .all_chip()
+            .permute_poseidon2(ctx, state)
+    }
+
+    fn hash_or_noop(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        inputs: &[AssignedValue<F>],
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let elements = if inputs.len() <= 4 {
+            // Short inputs are zero-padded instead of hashed.
+            let zero = self
+                .goldilocks_chip()
+                .assign_constant(ctx, GoldilocksField::ZERO)?;
+            let mut elements = inputs.to_vec();
+            elements.resize(4, zero);
+            elements
+        } else {
+            // `hash_n_to_hash_no_pad`: each chunk overwrites the rate, then the state is permuted.
+            let mut state = self.zero_state(ctx)?;
+            for chunk in inputs.chunks(SPONGE_RATE) {
+                state[..chunk.len()].clone_from_slice(chunk);
+                state = self.permute_state(ctx, state)?;
+            }
+            state[..4].to_vec()
+        };
+        Ok(AssignedHashValues {
+            elements: elements.try_into().unwrap(),
+        })
+    }
+
+    fn two_to_one(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        left: &AssignedHashValues<F>,
+        right: &AssignedHashValues<F>,
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let mut state = self.zero_state(ctx)?;
+        state[..4].clone_from_slice(&left.elements);
+        state[4..8].clone_from_slice(&right.elements);
+        let state = self.permute_state(ctx, state)?;
+        Ok(AssignedHashValues {
+            elements: state[..4].to_vec().try_into().unwrap(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Sample},
+        hash::hashing::PlonkyPermutation as _,
+    };
+
+    use crate::{
+        bn254_poseidon::plonky2_config::Bn254Poseidon2Permutation,
+        chip::{
+            goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+            hasher_chip::HasherInstructions,
+            native_chip::utils::{fe_to_goldilocks, goldilocks_to_fe},
+        },
+        context::RegionCtx,
+    };
+
+    use super::Poseidon2HasherChip;
+
+    #[derive(Clone, Default)]
+    pub struct TestCircuit {
+        input: [GoldilocksField; 12],
+        expected_output: [GoldilocksField; 8],
+    }
+
+    impl Circuit<Fr> for TestCircuit {
+        type Config = GoldilocksChipConfig<Fr>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            Poseidon2HasherChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let goldilocks_chip = GoldilocksChip::new(&config);
+            goldilocks_chip.load_table(&mut layouter)?;
+            layouter.assign_region(
+                || "poseidon2 hasher chip",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+
+                    let input_assigned = self
+                        .input
+                        .iter()
+                        .map(|x| {
+                            goldilocks_chip.assign_value(ctx, Value::known(goldilocks_to_fe(*x)))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+
+                    let mut hasher_chip = Poseidon2HasherChip::<Fr>::new(ctx, &config)?;
+                    let output =
+                        hasher_chip.permute_state(ctx, input_assigned.try_into().unwrap())?;
+                    output
+                        .iter()
+                        .zip(self.expected_output.iter())
+                        .for_each(|(x, e)| {
+                            x.value().map(|x| assert_eq!(fe_to_goldilocks(*x), *e));
+                        });
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_poseidon2_hasher_chip_mock() {
+        let input = [(); 12].map(|_| GoldilocksField::rand());
+        let mut permutation = Bn254Poseidon2Permutation::<GoldilocksField>::new(input);
+        permutation.permute();
+        let expected_output = permutation.squeeze();
+
+        const DEGREE: u32 = 17;
+        let circuit = TestCircuit {
+            input,
+            expected_output: expected_output.to_vec().try_into().unwrap(),
+        };
+        let instance: Vec<Fr> = vec![];
+        let mock_prover = MockProver::run(DEGREE, &circuit, vec![instance.clone()]).unwrap();
+        mock_prover.assert_satisfied();
+    }
+}
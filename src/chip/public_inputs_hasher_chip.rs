@@ -5,10 +5,11 @@ use plonky2::{
     hash::poseidon::SPONGE_WIDTH,
 };
 
-use crate::context::RegionCtx;
+use crate::{context::RegionCtx, types::assigned::AssignedHashValues};
 
 use super::{
     goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+    hasher_chip::HasherInstructions,
     native_chip::utils::goldilocks_to_fe,
     poseidon_spec::spec::{SparseMDSMatrix, Spec, State},
 };
@@ -21,8 +22,9 @@ const RATE: usize = 8;
 #[derive(Debug, Clone)]
 pub struct AssignedState<F: PrimeField, const T: usize>(pub(super) [AssignedValue<F>; T]);
 
-/// `HasherChip` is basically responsible for contraining permutation part of
-/// transcript pipeline
+/// Constrains plonky2's Goldilocks `PoseidonPermutation` with the arithmetic of `GoldilocksChip`.
+/// It hashes the public inputs of every proof, and is the hasher of `PoseidonGoldilocksConfig`
+/// proofs.
 #[derive(Debug, Clone)]
 pub struct PublicInputsHasherChip<F: PrimeField> {
     state: AssignedState<F, T>,
@@ -363,3 +365,157 @@ impl<F: PrimeField> PublicInputsHasherChip<F> {
         }
     }
 }
+
+impl<F: PrimeField> HasherInstructions<F> for PublicInputsHasherChip<F> {
+    fn new(
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+    ) -> Result<Self, Error> {
+        PublicInputsHasherChip::new(ctx, goldilocks_chip_config)
+    }
+
+    fn permute_state(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        state: [AssignedValue<F>; SPONGE_WIDTH],
+    ) -> Result<[AssignedValue<F>; SPONGE_WIDTH], Error> {
+        self.state.0 = state;
+        self.permutation(ctx)?;
+        Ok(self.state.0.clone())
+    }
+
+    fn hash_or_noop(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        inputs: &[AssignedValue<F>],
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let elements = if inputs.len() <= 4 {
+            // Short inputs are zero-padded instead of hashed.
+            let zero = self
+                .goldilocks_chip()
+                .assign_constant(ctx, GoldilocksField::ZERO)?;
+            let mut elements = inputs.to_vec();
+            elements.resize(4, zero);
+            elements
+        } else {
+            PublicInputsHasherChip::new(ctx, &self.goldilocks_chip_config)?.hash(
+                ctx,
+                inputs.to_vec(),
+                4,
+            )?
+        };
+        Ok(AssignedHashValues {
+            elements: elements.try_into().unwrap(),
+        })
+    }
+
+    fn two_to_one(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        left: &AssignedHashValues<F>,
+        right: &AssignedHashValues<F>,
+    ) -> Result<AssignedHashValues<F>, Error> {
+        let inputs = [left.elements.clone(), right.elements.clone()].concat();
+        let elements = PublicInputsHasherChip::new(ctx, &self.goldilocks_chip_config)?
+            .permute(ctx, inputs, 4)?;
+        Ok(AssignedHashValues {
+            elements: elements.try_into().unwrap(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{floor_planner::V1, Layouter, Value},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Sample},
+        hash::{hashing::PlonkyPermutation as _, poseidon::PoseidonPermutation},
+    };
+
+    use crate::{
+        chip::{
+            goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+            hasher_chip::HasherInstructions,
+            native_chip::utils::{fe_to_goldilocks, goldilocks_to_fe},
+        },
+        context::RegionCtx,
+    };
+
+    use super::PublicInputsHasherChip;
+
+    #[derive(Clone, Default)]
+    pub struct TestCircuit {
+        input: [GoldilocksField; 12],
+        expected_output: [GoldilocksField; 8],
+    }
+
+    impl Circuit<Fr> for TestCircuit {
+        type Config = GoldilocksChipConfig<Fr>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            PublicInputsHasherChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let goldilocks_chip = GoldilocksChip::new(&config);
+            goldilocks_chip.load_table(&mut layouter)?;
+            layouter.assign_region(
+                || "goldilocks poseidon hasher chip",
+                |region| {
+                    let ctx = &mut RegionCtx::new(region, 0);
+
+                    let input_assigned = self
+                        .input
+                        .iter()
+                        .map(|x| {
+                            goldilocks_chip.assign_value(ctx, Value::known(goldilocks_to_fe(*x)))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+
+                    let mut hasher_chip = PublicInputsHasherChip::<Fr>::new(ctx, &config)?;
+                    let output =
+                        hasher_chip.permute_state(ctx, input_assigned.try_into().unwrap())?;
+                    output
+                        .iter()
+                        .zip(self.expected_output.iter())
+                        .for_each(|(x, e)| {
+                            x.value().map(|x| assert_eq!(fe_to_goldilocks(*x), *e));
+                        });
+                    Ok(())
+                },
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_goldilocks_poseidon_hasher_chip_mock() {
+        let input = [(); 12].map(|_| GoldilocksField::rand());
+        let mut permutation = PoseidonPermutation::<GoldilocksField>::new(input);
+        permutation.permute();
+        let expected_output = permutation.squeeze();
+
+        const DEGREE: u32 = 17;
+        let circuit = TestCircuit {
+            input,
+            expected_output: expected_output.to_vec().try_into().unwrap(),
+        };
+        let instance: Vec<Fr> = vec![];
+        let mock_prover = MockProver::run(DEGREE, &circuit, vec![instance.clone()]).unwrap();
+        mock_prover.assert_satisfied();
+    }
+}
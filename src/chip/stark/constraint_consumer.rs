@@ -0,0 +1,123 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use halo2wrong_maingate::AssignedValue;
+
+use crate::{
+    chip::{
+        goldilocks_chip::GoldilocksChipConfig, goldilocks_extension_chip::GoldilocksExtensionChip,
+    },
+    types::assigned::AssignedExtensionFieldValue,
+};
+
+/// Combines the constraints of a Starky AIR into one accumulator per challenge round.
+/// The counterpart of starky's `RecursiveConstraintConsumer`.
+pub struct ConstraintConsumer<F: PrimeField, const D: usize> {
+    goldilocks_chip_config: GoldilocksChipConfig<F>,
+
+    /// A random value used to combine multiple constraints into one, for each challenge round.
+    alphas: Vec<AssignedExtensionFieldValue<F, D>>,
+
+    /// Running sums of constraints that have been emitted so far, scaled by powers of alpha.
+    constraint_accs: Vec<AssignedExtensionFieldValue<F, D>>,
+
+    /// The evaluation of `X - g^(n-1)`.
+    z_last: AssignedExtensionFieldValue<F, D>,
+
+    /// The evaluation of the Lagrange basis polynomial which is nonzero at the point associated
+    /// with the first trace row, and zero at other points in the subgroup.
+    lagrange_basis_first: AssignedExtensionFieldValue<F, D>,
+
+    /// The evaluation of the Lagrange basis polynomial which is nonzero at the point associated
+    /// with the last trace row, and zero at other points in the subgroup.
+    lagrange_basis_last: AssignedExtensionFieldValue<F, D>,
+}
+
+impl<F: PrimeField, const D: usize> ConstraintConsumer<F, D> {
+    pub fn new(
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        alphas: &[AssignedValue<F>],
+        z_last: AssignedExtensionFieldValue<F, D>,
+        lagrange_basis_first: AssignedExtensionFieldValue<F, D>,
+        lagrange_basis_last: AssignedExtensionFieldValue<F, D>,
+    ) -> Result<Self, Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(goldilocks_chip_config);
+        let alphas = alphas
+            .iter()
+            .map(|alpha| goldilocks_extension_chip.convert_to_extension(ctx, alpha))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let zero = goldilocks_extension_chip.zero_extension(ctx)?;
+        Ok(Self {
+            goldilocks_chip_config: goldilocks_chip_config.clone(),
+            constraint_accs: vec![zero; alphas.len()],
+            alphas,
+            z_last,
+            lagrange_basis_first,
+            lagrange_basis_last,
+        })
+    }
+
+    fn goldilocks_extension_chip(&self) -> GoldilocksExtensionChip<F, D> {
+        GoldilocksExtensionChip::new(&self.goldilocks_chip_config)
+    }
+
+    pub fn accumulators(self) -> Vec<AssignedExtensionFieldValue<F, D>> {
+        self.constraint_accs
+    }
+
+    /// Add one constraint valid on all rows except the last.
+    pub fn constraint_transition(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        constraint: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<(), Error> {
+        let filtered_constraint =
+            self.goldilocks_extension_chip()
+                .mul_extension(ctx, constraint, &self.z_last)?;
+        self.constraint(ctx, &filtered_constraint)
+    }
+
+    /// Add one constraint valid on all rows.
+    pub fn constraint(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        constraint: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<(), Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip();
+        for (alpha, acc) in self.alphas.iter().zip(self.constraint_accs.iter_mut()) {
+            *acc = goldilocks_extension_chip.mul_add_extension(ctx, alpha, acc, constraint)?;
+        }
+        Ok(())
+    }
+
+    /// Add one constraint, but first multiply it by a filter such that it will only apply to the
+    /// first row of the trace.
+    pub fn constraint_first_row(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        constraint: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<(), Error> {
+        let filtered_constraint = self.goldilocks_extension_chip().mul_extension(
+            ctx,
+            constraint,
+            &self.lagrange_basis_first,
+        )?;
+        self.constraint(ctx, &filtered_constraint)
+    }
+
+    /// Add one constraint, but first multiply it by a filter such that it will only apply to the
+    /// last row of the trace.
+    pub fn constraint_last_row(
+        &mut self,
+        ctx: &mut RegionCtx<'_, F>,
+        constraint: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<(), Error> {
+        let filtered_constraint = self.goldilocks_extension_chip().mul_extension(
+            ctx,
+            constraint,
+            &self.lagrange_basis_last,
+        )?;
+        self.constraint(ctx, &filtered_constraint)
+    }
+}
@@ -0,0 +1,176 @@
+use crate::context::RegionCtx;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::plonk::Error;
+use starky::permutation::PermutationPair;
+
+use self::constraint_consumer::ConstraintConsumer;
+use crate::chip::goldilocks_chip::GoldilocksChipConfig;
+use crate::chip::goldilocks_extension_chip::GoldilocksExtensionChip;
+use crate::error::VerifierError;
+use crate::types::assigned::AssignedExtensionFieldValue;
+
+pub mod constraint_consumer;
+pub mod permutation;
+pub mod stark_verifier_chip;
+
+/// Openings of the trace at `stark_zeta` and `g * stark_zeta`, along with the public inputs.
+pub struct StarkEvaluationVars<'a, F: PrimeField, const D: usize> {
+    pub local_values: &'a [AssignedExtensionFieldValue<F, D>],
+    pub next_values: &'a [AssignedExtensionFieldValue<F, D>],
+    pub public_inputs: &'a [AssignedExtensionFieldValue<F, D>],
+}
+
+/// Evaluate the constraints of a Starky AIR in `stark_zeta` inside maingate.
+/// This mirrors starky's `Stark` trait, with `eval_ext_circuit` written against the Halo2 chips.
+/// Only single-table AIRs are supported: the trace and the permutation `Z`s are the only
+/// committed columns, see `check_stark`.
+pub trait StarkConstrainer<F: PrimeField, const D: usize> {
+    /// The total number of columns in the trace.
+    fn columns(&self) -> usize;
+
+    /// The maximum constraint degree.
+    fn constraint_degree(&self) -> usize;
+
+    /// Pairs of lists of columns that should be permutations of one another.
+    fn permutation_pairs(&self) -> Vec<PermutationPair> {
+        vec![]
+    }
+
+    /// Whether the trace takes part in cross-table lookups with the traces of other STARKs, like
+    /// the tables of a multi-STARK system. Not supported.
+    fn requires_ctls(&self) -> bool {
+        false
+    }
+
+    /// The number of auxiliary columns committed besides the permutation `Z`s, e.g. the helper
+    /// columns and `Z`s of lookups. Not supported.
+    fn num_auxiliary_columns(&self) -> usize {
+        0
+    }
+
+    fn goldilocks_extension_chip(
+        &self,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+    ) -> GoldilocksExtensionChip<F, D> {
+        GoldilocksExtensionChip::new(goldilocks_chip_config)
+    }
+
+    /// Evaluates the constraints at `stark_zeta`, passing them to `yield_constr`.
+    fn eval_ext_circuit(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        goldilocks_chip_config: &GoldilocksChipConfig<F>,
+        vars: &StarkEvaluationVars<'_, F, D>,
+        yield_constr: &mut ConstraintConsumer<F, D>,
+    ) -> Result<(), Error>;
+
+    /// The maximum constraint degree, minus one.
+    fn quotient_degree_factor(&self) -> usize {
+        1.max(self.constraint_degree() - 1)
+    }
+
+    fn num_quotient_polys(&self, num_challenges: usize) -> usize {
+        self.quotient_degree_factor() * num_challenges
+    }
+
+    fn uses_permutation_args(&self) -> bool {
+        !self.permutation_pairs().is_empty()
+    }
+
+    /// The number of permutation argument instances that can be combined into a single
+    /// constraint.
+    fn permutation_batch_size(&self) -> usize {
+        // The permutation argument constraints look like
+        //     Z(x) \prod(...) = Z(g x) \prod(...)
+        // where each product has a number of terms equal to the batch size. So our batch size
+        // should be one less than our constraint degree, which accounts for the Z polynomial.
+        self.constraint_degree() - 1
+    }
+
+    fn num_permutation_instances(&self, num_challenges: usize) -> usize {
+        self.permutation_pairs().len() * num_challenges
+    }
+
+    fn num_permutation_batches(&self, num_challenges: usize) -> usize {
+        self.num_permutation_instances(num_challenges)
+            .div_ceil(self.permutation_batch_size())
+    }
+}
+
+/// Checks that `stark` only uses the arguments verified by `StarkVerifierChip`, returning
+/// `VerifierError::UnsupportedStark` for cross-table lookups and auxiliary columns.
+pub fn check_stark<F: PrimeField, S: StarkConstrainer<F, D>, const D: usize>(
+    stark: &S,
+) -> Result<(), VerifierError> {
+    if stark.requires_ctls() {
+        return Err(VerifierError::UnsupportedStark(
+            "cross-table lookups are not supported".to_string(),
+        ));
+    }
+    if stark.num_auxiliary_columns() != 0 {
+        return Err(VerifierError::UnsupportedStark(format!(
+            "{} auxiliary columns, only the permutation zs are supported",
+            stark.num_auxiliary_columns()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_stark, ConstraintConsumer, StarkConstrainer, StarkEvaluationVars};
+    use crate::{
+        chip::goldilocks_chip::GoldilocksChipConfig, context::RegionCtx, error::VerifierError,
+    };
+    use halo2_proofs::{halo2curves::bn256::Fr, plonk::Error};
+
+    struct MultiTableStark {
+        requires_ctls: bool,
+        num_auxiliary_columns: usize,
+    }
+
+    impl StarkConstrainer<Fr, 2> for MultiTableStark {
+        fn columns(&self) -> usize {
+            1
+        }
+
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+
+        fn requires_ctls(&self) -> bool {
+            self.requires_ctls
+        }
+
+        fn num_auxiliary_columns(&self) -> usize {
+            self.num_auxiliary_columns
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            _ctx: &mut RegionCtx<'_, Fr>,
+            _goldilocks_chip_config: &GoldilocksChipConfig<Fr>,
+            _vars: &StarkEvaluationVars<'_, Fr, 2>,
+            _yield_constr: &mut ConstraintConsumer<Fr, 2>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_check_stark() {
+        let stark = |requires_ctls, num_auxiliary_columns| MultiTableStark {
+            requires_ctls,
+            num_auxiliary_columns,
+        };
+        assert!(check_stark(&stark(false, 0)).is_ok());
+        assert!(matches!(
+            check_stark(&stark(true, 0)),
+            Err(VerifierError::UnsupportedStark(_))
+        ));
+        assert!(matches!(
+            check_stark(&stark(false, 2)),
+            Err(VerifierError::UnsupportedStark(_))
+        ));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::context::RegionCtx;
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
+use itertools::Itertools;
+use starky::permutation::PermutationPair;
+
+use crate::{
+    chip::{
+        goldilocks_extension_chip::GoldilocksExtensionChip,
+        stark::{
+            constraint_consumer::ConstraintConsumer, stark_verifier_chip::StarkVerifierChip,
+            StarkConstrainer, StarkEvaluationVars,
+        },
+    },
+    types::assigned::{
+        AssignedExtensionFieldValue, AssignedGrandProductChallenge,
+        AssignedGrandProductChallengeSet,
+    },
+};
+
+/// A single instance of a permutation check protocol.
+struct PermutationInstance<'a, F: PrimeField> {
+    pair: &'a PermutationPair,
+    challenge: &'a AssignedGrandProductChallenge<F>,
+}
+
+/// Groups the instances of the permutation argument by `Z` polynomial, following starky's
+/// `get_permutation_batches`.
+fn get_permutation_batches<'a, F: PrimeField>(
+    permutation_pairs: &'a [PermutationPair],
+    permutation_challenge_sets: &'a [AssignedGrandProductChallengeSet<F>],
+    num_challenges: usize,
+    batch_size: usize,
+) -> Vec<Vec<PermutationInstance<'a, F>>> {
+    permutation_pairs
+        .iter()
+        .cartesian_product(0..num_challenges)
+        .chunks(batch_size)
+        .into_iter()
+        .map(|batch| {
+            batch
+                .enumerate()
+                .map(|(i, (pair, challenge))| PermutationInstance {
+                    pair,
+                    challenge: &permutation_challenge_sets[i].challenges[challenge],
+                })
+                .collect_vec()
+        })
+        .collect()
+}
+
+impl<F: PrimeField, S: StarkConstrainer<F, D>, const D: usize> StarkVerifierChip<F, S, D> {
+    /// Evaluates the constraints of the permutation argument, where each `Z` polynomial checks a
+    /// batch of permutation instances:
+    /// - `Z(1) = 1`;
+    /// - `Z(g x) \prod(gamma + reduced_rhs) = Z(x) \prod(gamma + reduced_lhs)`, where the columns
+    ///   of each side are reduced with powers of `beta`.
+    pub(crate) fn eval_permutation_checks(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        vars: &StarkEvaluationVars<'_, F, D>,
+        local_zs: &[AssignedExtensionFieldValue<F, D>],
+        next_zs: &[AssignedExtensionFieldValue<F, D>],
+        permutation_challenge_sets: &[AssignedGrandProductChallengeSet<F>],
+        num_challenges: usize,
+        consumer: &mut ConstraintConsumer<F, D>,
+    ) -> Result<(), Error> {
+        let goldilocks_extension_chip =
+            GoldilocksExtensionChip::<F, D>::new(&self.goldilocks_chip_config);
+        let one = goldilocks_extension_chip.one_extension(ctx)?;
+
+        // Check that Z(1) = 1.
+        for z in local_zs {
+            let z_1 = goldilocks_extension_chip.sub_extension(ctx, z, &one)?;
+            consumer.constraint_first_row(ctx, &z_1)?;
+        }
+
+        let permutation_pairs = self.stark.permutation_pairs();
+        let permutation_batches = get_permutation_batches(
+            &permutation_pairs,
+            permutation_challenge_sets,
+            num_challenges,
+            self.stark.permutation_batch_size(),
+        );
+
+        // Each Z value corresponds to a permutation batch.
+        for (i, instances) in permutation_batches.iter().enumerate() {
+            let mut reduced_lhs = Vec::with_capacity(instances.len());
+            let mut reduced_rhs = Vec::with_capacity(instances.len());
+            for PermutationInstance { pair, challenge } in instances {
+                let beta = goldilocks_extension_chip.convert_to_extension(ctx, &challenge.beta)?;
+                let gamma =
+                    goldilocks_extension_chip.convert_to_extension(ctx, &challenge.gamma)?;
+                let (lhs, rhs): (Vec<_>, Vec<_>) = pair
+                    .column_pairs
+                    .iter()
+                    .map(|&(i, j)| (vars.local_values[i].clone(), vars.local_values[j].clone()))
+                    .unzip();
+                let lhs = goldilocks_extension_chip.reduce_extension(ctx, &beta, &lhs)?;
+                let rhs = goldilocks_extension_chip.reduce_extension(ctx, &beta, &rhs)?;
+                reduced_lhs.push(goldilocks_extension_chip.add_extension(ctx, &lhs, &gamma)?);
+                reduced_rhs.push(goldilocks_extension_chip.add_extension(ctx, &rhs, &gamma)?);
+            }
+            let reduced_lhs_product =
+                goldilocks_extension_chip.mul_many_extension(ctx, reduced_lhs)?;
+            let reduced_rhs_product =
+                goldilocks_extension_chip.mul_many_extension(ctx, reduced_rhs)?;
+            // constraint = next_zs[i] * reduced_rhs_product - local_zs[i] * reduced_lhs_product
+            let local_term =
+                goldilocks_extension_chip.mul_extension(ctx, &local_zs[i], &reduced_lhs_product)?;
+            let constraint = goldilocks_extension_chip.mul_sub_extension(
+                ctx,
+                &next_zs[i],
+                &reduced_rhs_product,
+                &local_term,
+            )?;
+            consumer.constraint(ctx, &constraint)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+use std::iter;
+
+use crate::context::RegionCtx;
+use crate::{
+    chip::{
+        fri_chip::FriVerifierChip,
+        goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
+        goldilocks_extension_chip::GoldilocksExtensionChip,
+        hasher_chip::HasherChip,
+        stark::{constraint_consumer::ConstraintConsumer, StarkConstrainer, StarkEvaluationVars},
+        transcript_chip::TranscriptChip,
+    },
+    types::{
+        assigned::{
+            AssignedExtensionFieldValue, AssignedGrandProductChallenge,
+            AssignedGrandProductChallengeSet, AssignedStarkProofChallenges,
+            AssignedStarkProofValues,
+        },
+        fri::{FriBatchInfo, FriInstanceInfo, FriOracleInfo, FriPolynomialInfo},
+        stark::StarkCommonData,
+    },
+};
+use halo2_proofs::{halo2curves::ff::PrimeField, plonk::*};
+use halo2wrong_maingate::AssignedValue;
+use itertools::Itertools;
+use plonky2::field::{goldilocks_field::GoldilocksField, types::Field};
+
+/// Verifies a Starky proof of the AIR described by `stark`.
+pub struct StarkVerifierChip<F: PrimeField, S: StarkConstrainer<F, D>, const D: usize> {
+    pub goldilocks_chip_config: GoldilocksChipConfig<F>,
+    pub stark: S,
+}
+
+impl<F: PrimeField, S: StarkConstrainer<F, D>, const D: usize> StarkVerifierChip<F, S, D> {
+    pub fn construct(goldilocks_chip_config: &GoldilocksChipConfig<F>, stark: S) -> Self {
+        Self {
+            goldilocks_chip_config: goldilocks_chip_config.clone(),
+            stark,
+        }
+    }
+
+    pub fn goldilocks_chip(&self) -> GoldilocksChip<F> {
+        GoldilocksChip::<F>::new(&self.goldilocks_chip_config)
+    }
+
+    pub fn goldilocks_extension_chip(&self) -> GoldilocksExtensionChip<F, D> {
+        GoldilocksExtensionChip::new(&self.goldilocks_chip_config)
+    }
+
+    fn get_grand_product_challenge_set(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        transcript_chip: &mut TranscriptChip<F>,
+        num_challenges: usize,
+    ) -> Result<AssignedGrandProductChallengeSet<F>, Error> {
+        let challenges = (0..num_challenges)
+            .map(|_| {
+                let beta = transcript_chip.squeeze(ctx, 1)?[0].clone();
+                let gamma = transcript_chip.squeeze(ctx, 1)?[0].clone();
+                Ok(AssignedGrandProductChallenge { beta, gamma })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(AssignedGrandProductChallengeSet { challenges })
+    }
+
+    /// Replays starky's transcript. Unlike plonky2 proofs, the public inputs are not observed.
+    pub fn get_challenges(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        proof: &AssignedStarkProofValues<F, D>,
+        common_data: &StarkCommonData<D>,
+    ) -> Result<AssignedStarkProofChallenges<F, D>, Error> {
+        let num_challenges = common_data.config.num_challenges;
+        let mut transcript_chip = TranscriptChip::<F>::new(ctx, &self.goldilocks_chip_config)?;
+
+        transcript_chip.write_cap(ctx, &proof.trace_cap)?;
+
+        let permutation_challenge_sets = match &proof.permutation_zs_cap {
+            Some(permutation_zs_cap) => {
+                let challenge_sets = (0..self.stark.permutation_batch_size())
+                    .map(|_| {
+                        self.get_grand_product_challenge_set(
+                            ctx,
+                            &mut transcript_chip,
+                            num_challenges,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                transcript_chip.write_cap(ctx, permutation_zs_cap)?;
+                Some(challenge_sets)
+            }
+            None => None,
+        };
+
+        let stark_alphas = transcript_chip.squeeze(ctx, num_challenges)?;
+
+        transcript_chip.write_cap(ctx, &proof.quotient_polys_cap)?;
+        let stark_zeta = transcript_chip.squeeze_extension(ctx)?;
+
+        transcript_chip.write_fri_openings(ctx, &proof.openings.to_fri_openings())?;
+
+        let fri_challenges = transcript_chip.fri_challenges(
+            ctx,
+            &proof.opening_proof,
+            common_data.config.fri_config.num_query_rounds,
+        )?;
+
+        Ok(AssignedStarkProofChallenges {
+            permutation_challenge_sets,
+            stark_alphas,
+            stark_zeta,
+            fri_challenges,
+        })
+    }
+
+    /// Evaluates the Lagrange basis polynomials of the first and last rows at `x`, where
+    /// `z_x = x^n - 1`.
+    fn eval_l_0_and_l_last(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        log_n: usize,
+        x: &AssignedExtensionFieldValue<F, D>,
+        z_x: &AssignedExtensionFieldValue<F, D>,
+    ) -> Result<
+        (
+            AssignedExtensionFieldValue<F, D>,
+            AssignedExtensionFieldValue<F, D>,
+        ),
+        Error,
+    > {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip();
+        let n = GoldilocksField::from_canonical_usize(1 << log_n);
+        let g = GoldilocksField::primitive_root_of_unity(log_n);
+        let one = goldilocks_extension_chip.one_extension(ctx)?;
+        // L_0(x) = (x^n - 1) / (n * (x - 1))
+        let l_0_deno = goldilocks_extension_chip.arithmetic_extension(ctx, n, -n, x, &one, &one)?;
+        // L_last(x) = (x^n - 1) / (n * (g * x - 1))
+        let l_last_deno =
+            goldilocks_extension_chip.arithmetic_extension(ctx, n * g, -n, x, &one, &one)?;
+        Ok((
+            goldilocks_extension_chip.div_extension(ctx, z_x, &l_0_deno)?,
+            goldilocks_extension_chip.div_extension(ctx, z_x, &l_last_deno)?,
+        ))
+    }
+
+    /// Describes the openings of the trace, the permutation `Z`s and the quotient polynomials, as
+    /// starky's `Stark::fri_instance`.
+    fn fri_instance(
+        &self,
+        zeta: &AssignedExtensionFieldValue<F, D>,
+        zeta_next: &AssignedExtensionFieldValue<F, D>,
+        num_challenges: usize,
+    ) -> FriInstanceInfo<F, D> {
+        let mut oracles = vec![];
+
+        let num_columns = self.stark.columns();
+        let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_columns);
+        oracles.push(FriOracleInfo {
+            num_polys: num_columns,
+            blinding: false,
+        });
+
+        let permutation_zs_info = if self.stark.uses_permutation_args() {
+            let num_z_polys = self.stark.num_permutation_batches(num_challenges);
+            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_z_polys);
+            oracles.push(FriOracleInfo {
+                num_polys: num_z_polys,
+                blinding: false,
+            });
+            polys
+        } else {
+            vec![]
+        };
+
+        let num_quotient_polys = self.stark.num_quotient_polys(num_challenges);
+        let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
+        oracles.push(FriOracleInfo {
+            num_polys: num_quotient_polys,
+            blinding: false,
+        });
+
+        let zeta_batch = FriBatchInfo {
+            point: zeta.clone(),
+            polynomials: [
+                trace_info.clone(),
+                permutation_zs_info.clone(),
+                quotient_info,
+            ]
+            .concat(),
+        };
+        let zeta_next_batch = FriBatchInfo {
+            point: zeta_next.clone(),
+            polynomials: [trace_info, permutation_zs_info].concat(),
+        };
+        FriInstanceInfo {
+            oracles,
+            batches: vec![zeta_batch, zeta_next_batch],
+        }
+    }
+
+    pub fn verify_proof_with_challenges(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        proof: &AssignedStarkProofValues<F, D>,
+        public_inputs: &[AssignedValue<F>],
+        challenges: &AssignedStarkProofChallenges<F, D>,
+        common_data: &StarkCommonData<D>,
+    ) -> Result<(), Error> {
+        let goldilocks_extension_chip = self.goldilocks_extension_chip();
+        let one = goldilocks_extension_chip.one_extension(ctx)?;
+        let openings = &proof.openings;
+        let public_inputs = public_inputs
+            .iter()
+            .map(|pi| goldilocks_extension_chip.convert_to_extension(ctx, pi))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let vars = StarkEvaluationVars {
+            local_values: &openings.local_values,
+            next_values: &openings.next_values,
+            public_inputs: &public_inputs,
+        };
+
+        let degree_bits = common_data.degree_bits();
+        let zeta = &challenges.stark_zeta;
+        let zeta_pow_deg =
+            goldilocks_extension_chip.exp_power_of_2_extension(ctx, zeta.clone(), degree_bits)?;
+        let z_h_zeta = goldilocks_extension_chip.sub_extension(ctx, &zeta_pow_deg, &one)?;
+        let (l_0, l_last) = self.eval_l_0_and_l_last(ctx, degree_bits, zeta, &z_h_zeta)?;
+        let last = goldilocks_extension_chip.constant_extension_from_base(
+            ctx,
+            GoldilocksField::primitive_root_of_unity(degree_bits).inverse(),
+        )?;
+        let z_last = goldilocks_extension_chip.sub_extension(ctx, zeta, &last)?;
+        let mut consumer = ConstraintConsumer::new(
+            ctx,
+            &self.goldilocks_chip_config,
+            &challenges.stark_alphas,
+            z_last,
+            l_0,
+            l_last,
+        )?;
+        self.stark
+            .eval_ext_circuit(ctx, &self.goldilocks_chip_config, &vars, &mut consumer)?;
+        if let Some(permutation_challenge_sets) = &challenges.permutation_challenge_sets {
+            self.eval_permutation_checks(
+                ctx,
+                &vars,
+                &openings.permutation_zs,
+                &openings.permutation_zs_next,
+                permutation_challenge_sets,
+                common_data.config.num_challenges,
+                &mut consumer,
+            )?;
+        }
+        let vanishing_polys_zeta = consumer.accumulators();
+
+        // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
+        for (i, chunk) in openings
+            .quotient_polys
+            .chunks(self.stark.quotient_degree_factor())
+            .enumerate()
+        {
+            let recombined_quotient =
+                goldilocks_extension_chip.reduce_extension(ctx, &zeta_pow_deg, &chunk.to_vec())?;
+            let computed_vanishing_poly =
+                goldilocks_extension_chip.mul_extension(ctx, &z_h_zeta, &recombined_quotient)?;
+            goldilocks_extension_chip.assert_equal_extension(
+                ctx,
+                &vanishing_polys_zeta[i],
+                &computed_vanishing_poly,
+            )?;
+        }
+
+        let merkle_caps = iter::once(proof.trace_cap.clone())
+            .chain(proof.permutation_zs_cap.clone())
+            .chain(iter::once(proof.quotient_polys_cap.clone()))
+            .collect_vec();
+
+        let g = GoldilocksField::primitive_root_of_unity(degree_bits);
+        let zeta_next = goldilocks_extension_chip.scalar_mul(ctx, zeta, g)?;
+        let fri_instance_info =
+            self.fri_instance(zeta, &zeta_next, common_data.config.num_challenges);
+        let offset = self
+            .goldilocks_chip()
+            .assign_constant(ctx, GoldilocksField::MULTIPLICATIVE_GROUP_GENERATOR)?;
+        let fri_chip = FriVerifierChip::<F, D, HasherChip<F>>::construct(
+            &self.goldilocks_chip_config,
+            &offset,
+            common_data.fri_params.clone(),
+        );
+        fri_chip.verify_fri_proof(
+            ctx,
+            &merkle_caps,
+            &challenges.fri_challenges,
+            &openings.to_fri_openings(),
+            &proof.opening_proof,
+            &fri_instance_info,
+        )
+    }
+
+    /// Verifies `proof`, whose public inputs are `public_inputs`.
+    pub fn verify_proof(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        proof: &AssignedStarkProofValues<F, D>,
+        public_inputs: &[AssignedValue<F>],
+        common_data: &StarkCommonData<D>,
+    ) -> Result<(), Error> {
+        let challenges = self.get_challenges(ctx, proof, common_data)?;
+        self.verify_proof_with_challenges(ctx, proof, public_inputs, &challenges, common_data)
+    }
+}
@@ -1,34 +1,58 @@
 use crate::{
-    chip::hasher_chip::HasherChip,
+    chip::hasher_chip::{HasherChip, HasherInstructions},
     context::RegionCtx,
-    types::assigned::{AssignedExtensionFieldValue, AssignedHashValues, AssignedMerkleCapValues},
+    types::assigned::{
+        AssignedExtensionFieldValue, AssignedFriChallenges, AssignedFriOpenings,
+        AssignedFriProofValues, AssignedHashValues, AssignedMerkleCapValues,
+    },
 };
 use halo2_proofs::{halo2curves::ff::PrimeField, plonk::Error};
 use halo2wrong_maingate::AssignedValue;
+use plonky2::{
+    field::{goldilocks_field::GoldilocksField, types::Field},
+    hash::poseidon::{SPONGE_RATE, SPONGE_WIDTH},
+};
 
-use super::goldilocks_chip::GoldilocksChipConfig;
+use super::goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig};
 
-pub struct TranscriptChip<N: PrimeField> {
-    hasher_chip: HasherChip<N>,
+/// The duplex sponge of plonky2's `Challenger`, over the permutation of `H`.
+pub struct TranscriptChip<N: PrimeField, H: HasherInstructions<N> = HasherChip<N>> {
+    hasher_chip: H,
+    state: [AssignedValue<N>; SPONGE_WIDTH],
+    absorbing: Vec<AssignedValue<N>>,
+    output_buffer: Vec<AssignedValue<N>>,
 }
 
-impl<N: PrimeField> TranscriptChip<N> {
+impl<N: PrimeField, H: HasherInstructions<N>> TranscriptChip<N, H> {
     /// Constructs the transcript chip
     pub fn new(
         ctx: &mut RegionCtx<'_, N>,
         goldilocks_chip_config: &GoldilocksChipConfig<N>,
     ) -> Result<Self, Error> {
-        let hasher_chip = HasherChip::new(ctx, goldilocks_chip_config)?;
-        Ok(Self { hasher_chip })
+        let goldilocks_chip = GoldilocksChip::new(goldilocks_chip_config);
+        let initial_state = [(); SPONGE_WIDTH]
+            .iter()
+            .map(|_| goldilocks_chip.assign_constant(ctx, GoldilocksField::ZERO))
+            .collect::<Result<Vec<AssignedValue<N>>, Error>>()?;
+        let hasher_chip = H::new(ctx, goldilocks_chip_config)?;
+        Ok(Self {
+            hasher_chip,
+            state: initial_state.try_into().unwrap(),
+            absorbing: vec![],
+            output_buffer: vec![],
+        })
     }
 
     /// Write scalar to the transcript
     pub fn write_scalar(
         &mut self,
-        ctx: &mut RegionCtx<'_, N>,
+        _ctx: &mut RegionCtx<'_, N>,
         scalar: &AssignedValue<N>,
     ) -> Result<(), Error> {
-        self.hasher_chip.update(ctx, scalar)
+        // Absorbing is deferred to the next squeeze, which invalidates the outputs.
+        self.output_buffer.clear();
+        self.absorbing.push(scalar.clone());
+        Ok(())
     }
 
     pub fn write_extension<const D: usize>(
@@ -70,6 +94,117 @@ impl<N: PrimeField> TranscriptChip<N> {
         ctx: &mut RegionCtx<'_, N>,
         num_outputs: usize,
     ) -> Result<Vec<AssignedValue<N>>, Error> {
-        self.hasher_chip.squeeze(ctx, num_outputs)
+        let mut output = vec![];
+        for _i in 0..num_outputs {
+            self.absorb_buffered_inputs(ctx)?;
+
+            if self.output_buffer.is_empty() {
+                self.state = self.hasher_chip.permute_state(ctx, self.state.clone())?;
+                self.output_buffer = self.state[0..SPONGE_RATE].to_vec();
+            }
+            output.push(self.output_buffer.pop().unwrap())
+        }
+        Ok(output)
+    }
+
+    fn absorb_buffered_inputs(&mut self, ctx: &mut RegionCtx<'_, N>) -> Result<(), Error> {
+        if self.absorbing.is_empty() {
+            return Ok(());
+        }
+        let buffered_inputs = self.absorbing.clone();
+        for input_chunk in buffered_inputs.chunks(SPONGE_RATE) {
+            self.duplexing(ctx, input_chunk)?;
+        }
+        self.absorbing.clear();
+        Ok(())
+    }
+
+    fn duplexing(
+        &mut self,
+        ctx: &mut RegionCtx<'_, N>,
+        input: &[AssignedValue<N>],
+    ) -> Result<(), Error> {
+        for (word, input) in self.state.iter_mut().zip(input.iter()) {
+            *word = input.clone();
+        }
+        self.state = self.hasher_chip.permute_state(ctx, self.state.clone())?;
+
+        self.output_buffer.clear();
+        self.output_buffer.extend_from_slice(&self.state[0..SPONGE_RATE]);
+        Ok(())
+    }
+
+    /// Constrain squeezing a new challenge in the extension field
+    pub fn squeeze_extension<const D: usize>(
+        &mut self,
+        ctx: &mut RegionCtx<'_, N>,
+    ) -> Result<AssignedExtensionFieldValue<N, D>, Error> {
+        let elements = self.squeeze(ctx, D)?;
+        Ok(AssignedExtensionFieldValue(elements.try_into().unwrap()))
+    }
+
+    pub fn write_fri_openings<const D: usize>(
+        &mut self,
+        ctx: &mut RegionCtx<'_, N>,
+        fri_openings: &AssignedFriOpenings<N, D>,
+    ) -> Result<(), Error> {
+        for batch in fri_openings.batches.iter() {
+            for ext in batch.values.iter() {
+                self.write_extension(ctx, ext)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the FRI challenges, following plonky2's `Challenger::fri_challenges`. The openings
+    /// must have been written to the transcript beforehand.
+    pub fn fri_challenges<const D: usize>(
+        &mut self,
+        ctx: &mut RegionCtx<'_, N>,
+        fri_proof: &AssignedFriProofValues<N, D>,
+        num_fri_queries: usize,
+    ) -> Result<AssignedFriChallenges<N, D>, Error> {
+        // Scaling factor to combine polynomials.
+        let fri_alpha = ctx.namespaced(
+            || "transcript squeeze fri_alpha",
+            |ctx| self.squeeze_extension(ctx),
+        )?;
+
+        // Recover the random betas used in the FRI reductions.
+        let fri_betas = fri_proof
+            .commit_phase_merkle_cap_values
+            .iter()
+            .enumerate()
+            .map(|(i, cap)| {
+                self.write_cap(ctx, cap)?;
+                ctx.namespaced(
+                    || format!("transcript squeeze fri_betas[{i}]"),
+                    |ctx| self.squeeze_extension(ctx),
+                )
+            })
+            .collect::<Result<Vec<AssignedExtensionFieldValue<N, D>>, Error>>()?;
+
+        for ext in fri_proof.final_poly.0.iter() {
+            self.write_extension(ctx, ext)?;
+        }
+
+        self.write_scalar(ctx, &fri_proof.pow_witness)?;
+        let fri_pow_response = ctx.namespaced(
+            || "transcript squeeze fri_pow_response",
+            |ctx| self.squeeze(ctx, 1),
+        )?[0]
+            .clone();
+
+        let fri_query_indices = ctx.namespaced(
+            || "transcript squeeze fri_query_indices",
+            |ctx| self.squeeze(ctx, num_fri_queries),
+        )?;
+
+        Ok(AssignedFriChallenges {
+            fri_alpha,
+            fri_betas,
+            fri_pow_response,
+            fri_query_indices,
+        })
     }
 }
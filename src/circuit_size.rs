@@ -0,0 +1,184 @@
+//! Measures circuits by counting the cells assigned by a synthesis, to pick the smallest `k` that
+//! fits them.
+//!
+//! The synthesis runs in full, like the one of the key generation: only the closures passed to
+//! the assignments are not evaluated.
+
+use halo2_proofs::{
+    circuit::Value,
+    halo2curves::ff::Field,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Challenge, Circuit, Column, ConstraintSystem, Error,
+        Fixed, FloorPlanner, Instance, Selector,
+    },
+};
+
+use crate::error::VerifierError;
+
+/// The size of a circuit, as laid out by its floor planner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CircuitSize {
+    /// Rows assigned by the regions and the lookup tables, or the rows of the instances if they
+    /// are more.
+    pub rows: usize,
+    /// Rows at the end of the domain that cannot be assigned, for the blinding factors and the
+    /// last row.
+    pub unusable_rows: usize,
+    pub advice_cells: usize,
+    /// Fixed cells, including the constants and the lookup tables.
+    pub fixed_cells: usize,
+    pub enabled_selectors: usize,
+    pub copies: usize,
+    /// Lookup arguments of the constraint system, each checked on every usable row.
+    pub lookups: usize,
+    /// The smallest `k` such that the circuit fits in `2^k` rows.
+    pub k: u32,
+}
+
+/// Counts the assignments of a synthesis, like the key generation does, without computing any
+/// value.
+#[derive(Default)]
+struct AssignmentCounter {
+    rows: usize,
+    advice_cells: usize,
+    fixed_cells: usize,
+    enabled_selectors: usize,
+    copies: usize,
+}
+
+impl AssignmentCounter {
+    fn use_row(&mut self, row: usize) {
+        self.rows = self.rows.max(row + 1);
+    }
+}
+
+impl<F: Field> Assignment<F> for AssignmentCounter {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn annotate_column<A, AR>(&mut self, _: A, _: Column<Any>)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.use_row(row);
+        self.enabled_selectors += 1;
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.use_row(row);
+        self.advice_cells += 1;
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.use_row(row);
+        self.fixed_cells += 1;
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        _: Column<Any>,
+        left_row: usize,
+        _: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.use_row(left_row.max(right_row));
+        self.copies += 1;
+        Ok(())
+    }
+
+    // Only pads the lookup tables up to the usable rows.
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn get_challenge(&self, _: Challenge) -> Value<F> {
+        Value::unknown()
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+/// Synthesizes `circuit` and measures it, with `instance_rows` rows in its longest instance
+/// column. The layout does not depend on the witness, so the size only depends on the shape of
+/// the inputs of the circuit, e.g. on the `CommonData` and the verification key for `Verifier`.
+pub fn measure_circuit<F: Field, C: Circuit<F>>(
+    circuit: &C,
+    instance_rows: usize,
+) -> Result<CircuitSize, VerifierError> {
+    let mut cs = ConstraintSystem::default();
+    let config = C::configure(&mut cs);
+    let mut counter = AssignmentCounter::default();
+    C::FloorPlanner::synthesize(&mut counter, circuit, config, cs.constants().clone())
+        .map_err(|e| VerifierError::Halo2(e.to_string()))?;
+
+    let rows = counter.rows.max(instance_rows);
+    let unusable_rows = cs.blinding_factors() + 1;
+    let n = (rows + unusable_rows).max(cs.minimum_rows());
+    Ok(CircuitSize {
+        rows,
+        unusable_rows,
+        advice_cells: counter.advice_cells,
+        fixed_cells: counter.fixed_cells,
+        enabled_selectors: counter.enabled_selectors,
+        copies: counter.copies,
+        lookups: cs.lookups().len(),
+        k: n.next_power_of_two().trailing_zeros(),
+    })
+}
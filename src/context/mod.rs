@@ -8,11 +8,16 @@ use halo2_proofs::{
 use halo2wrong_maingate::fe_to_big;
 use num_bigint::BigUint;
 
+use crate::diagnostics::{RowAnnotations, RowSpan};
+
 #[derive(Debug)]
 pub struct RegionCtx<'a, F: PrimeField> {
     region: Region<'a, F>,
     offset: usize,
     contants: HashMap<BigUint, AssignedCell<F, F>>,
+    // the open namespaces, with the offset at which each was entered
+    namespaces: Vec<(String, usize)>,
+    spans: Vec<RowSpan>,
 }
 
 impl<'a, F: PrimeField> RegionCtx<'a, F> {
@@ -21,6 +26,8 @@ impl<'a, F: PrimeField> RegionCtx<'a, F> {
             region,
             offset,
             contants: HashMap::new(),
+            namespaces: vec![],
+            spans: vec![],
         }
     }
 
@@ -87,4 +94,55 @@ impl<'a, F: PrimeField> RegionCtx<'a, F> {
     pub fn next(&mut self) {
         self.offset += 1
     }
+
+    /// Runs `f` inside the namespace `name`, so that failures in the rows it assigns are reported
+    /// under that name by `diagnostics::explain_failures`.
+    pub fn namespaced<T, N: Into<String>>(
+        &mut self,
+        name: impl FnOnce() -> N,
+        f: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        self.namespaces.push((name().into(), self.offset));
+        let result = f(self);
+        self.close_span(None);
+        result
+    }
+
+    /// Like `namespaced`, for the rows of a check: failures in them are reported as `failure`.
+    pub fn check<T, N: Into<String>>(
+        &mut self,
+        failure: impl FnOnce() -> N,
+        f: impl FnOnce(&mut Self) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let failure = failure().into();
+        self.namespaces.push((failure.clone(), self.offset));
+        let result = f(self);
+        self.close_span(Some(failure));
+        result
+    }
+
+    fn close_span(&mut self, failure: Option<String>) {
+        let (name, start) = self.namespaces.pop().unwrap();
+        if start == self.offset {
+            return;
+        }
+        let mut path = self
+            .namespaces
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        if failure.is_none() {
+            path.push(name);
+        }
+        self.spans.push(RowSpan {
+            rows: start..self.offset,
+            path,
+            failure,
+        });
+    }
+
+    /// Returns the namespaces recorded so far, as the annotations of the region named `region`.
+    pub fn take_annotations(&mut self, region: &str) -> RowAnnotations {
+        RowAnnotations::new(region, std::mem::take(&mut self.spans))
+    }
 }
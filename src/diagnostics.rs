@@ -0,0 +1,177 @@
+//! Explains the failures of the mock prover with the namespaces recorded by `RegionCtx`.
+//!
+//! The verifier circuits assign everything in a single region of millions of rows, so a raw
+//! `VerifyFailure` only points at an offset. The chips wrap their assignments in
+//! `RegionCtx::namespaced` (FRI query, reduction step, Merkle level, gate, transcript squeeze)
+//! and their assertions in `RegionCtx::check`, which maps that offset back to what was checked.
+
+use std::ops::Range;
+
+use halo2_proofs::dev::{FailureLocation, VerifyFailure};
+use itertools::Itertools;
+
+/// Rows of a region assigned inside the namespaces `path`, outermost first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowSpan {
+    pub rows: Range<usize>,
+    pub path: Vec<String>,
+    /// What a failure in these rows means, for the rows of `RegionCtx::check`.
+    pub failure: Option<String>,
+}
+
+/// The namespaces of the rows of a region, recorded while it is synthesized.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RowAnnotations {
+    region: String,
+    spans: Vec<RowSpan>,
+}
+
+impl RowAnnotations {
+    pub fn new(region: &str, spans: Vec<RowSpan>) -> Self {
+        Self {
+            region: region.to_string(),
+            spans,
+        }
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    pub fn spans(&self) -> &[RowSpan] {
+        &self.spans
+    }
+
+    /// Returns the innermost span containing the row at `offset` of the region.
+    pub fn span_at(&self, offset: usize) -> Option<&RowSpan> {
+        // Spans are recorded when their namespace is closed, so inner spans come first.
+        self.spans.iter().find(|span| span.rows.contains(&offset))
+    }
+
+    /// Describes `failure` by the namespaces of its row, e.g.
+    /// "FRI query 7, reduction step 2: folded eval mismatch". Failures outside the annotated
+    /// rows are described as the mock prover does.
+    pub fn describe(&self, failure: &VerifyFailure) -> String {
+        let message = failure.to_string();
+        let Some(span) =
+            failure_offset(failure, &self.region).and_then(|offset| self.span_at(offset))
+        else {
+            return message;
+        };
+        let description = match &span.failure {
+            Some(check) => check.as_str(),
+            // the first line, without the values of the cells
+            None => message.lines().next().unwrap_or_default(),
+        };
+        if span.path.is_empty() {
+            description.to_string()
+        } else {
+            format!("{}: {description}", span.path.join(", "))
+        }
+    }
+}
+
+// The offset of a failure located in the region `region`. `metadata::Region` only exposes its
+// name through its `Display`, e.g. "Region 1 ('Verify proof')".
+fn failure_offset(failure: &VerifyFailure, region: &str) -> Option<usize> {
+    let (failure_region, offset) = match failure {
+        VerifyFailure::CellNotAssigned {
+            region,
+            gate_offset,
+            ..
+        } => (region, *gate_offset),
+        VerifyFailure::ConstraintNotSatisfied { location, .. }
+        | VerifyFailure::Lookup { location, .. }
+        | VerifyFailure::Permutation { location, .. } => match location {
+            FailureLocation::InRegion { region, offset } => (region, *offset),
+            FailureLocation::OutsideRegion { .. } => return None,
+        },
+        _ => return None,
+    };
+    failure_region
+        .to_string()
+        .ends_with(&format!("('{region}')"))
+        .then_some(offset)
+}
+
+/// Describes each failure with `RowAnnotations::describe`, without repeating identical messages.
+pub fn explain_failures(failures: &[VerifyFailure], annotations: &RowAnnotations) -> Vec<String> {
+    failures
+        .iter()
+        .map(|failure| annotations.describe(failure))
+        .unique()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{failure_offset, RowAnnotations, RowSpan};
+    use halo2_proofs::{
+        dev::{FailureLocation, VerifyFailure},
+        plonk::Any,
+    };
+
+    fn span(rows: std::ops::Range<usize>, path: &[&str], failure: Option<&str>) -> RowSpan {
+        RowSpan {
+            rows,
+            path: path.iter().map(|name| name.to_string()).collect(),
+            failure: failure.map(str::to_string),
+        }
+    }
+
+    fn failure(region: &str, offset: usize) -> VerifyFailure {
+        VerifyFailure::Permutation {
+            column: (Any::advice(), 0).into(),
+            location: FailureLocation::InRegion {
+                region: (1, region).into(),
+                offset,
+            },
+        }
+    }
+
+    #[test]
+    fn test_describe_failures() {
+        let annotations = RowAnnotations::new(
+            "Verify proof",
+            vec![
+                span(
+                    10..20,
+                    &["FRI query 7", "reduction step 2"],
+                    Some("folded eval mismatch"),
+                ),
+                span(0..30, &["FRI query 7", "reduction step 2"], None),
+                span(0..40, &["FRI query 7"], None),
+            ],
+        );
+        assert_eq!(
+            failure_offset(&failure("Verify proof", 42), "Verify proof"),
+            Some(42)
+        );
+        let outside = VerifyFailure::Permutation {
+            column: (Any::advice(), 0).into(),
+            location: FailureLocation::OutsideRegion { row: 42 },
+        };
+        assert_eq!(failure_offset(&outside, "Verify proof"), None);
+        assert_eq!(annotations.describe(&outside), outside.to_string());
+
+        assert_eq!(
+            annotations.describe(&failure("Verify proof", 15)),
+            "FRI query 7, reduction step 2: folded eval mismatch"
+        );
+        let unchecked = failure("Verify proof", 35);
+        assert_eq!(
+            annotations.describe(&unchecked),
+            format!(
+                "FRI query 7: {}",
+                unchecked.to_string().lines().next().unwrap()
+            )
+        );
+        let unannotated = failure("Verify proof", 50);
+        assert_eq!(annotations.describe(&unannotated), unannotated.to_string());
+        let other_region = failure("Load table", 15);
+        assert_eq!(
+            annotations.describe(&other_region),
+            other_region.to_string()
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fmt, io};
+
+use crate::{
+    chip::plonk::gates::registry::GateRegistryError, native_verifier::FailedCheck,
+    types::common_data::MAX_REDUCTION_ARITY_BITS,
+};
+
+/// Errors returned when a plonky2 circuit cannot be verified by this crate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifierError {
+    /// Some gates of the circuit have no constrainer.
+    Gates(GateRegistryError),
+    /// Only the quadratic and quartic extensions of Goldilocks are supported.
+    UnsupportedExtensionDegree(usize),
+    /// FRI reduction arity of the given reduction round is not supported.
+    UnsupportedReductionArity { round: usize, arity_bits: usize },
+    /// The circuit must draw at least one challenge per round.
+    InvalidNumChallenges(usize),
+    /// The circuit data is inconsistent.
+    InvalidCommonData(String),
+    /// The proof does not have the shape expected by the verifier.
+    InvalidProof(String),
+    /// The Starky AIR uses arguments that `StarkVerifierChip` does not verify.
+    UnsupportedStark(String),
+    /// `PublicInputsMode::Keccak` needs a hasher configuring the Keccak chip, e.g.
+    /// `WithKeccakChip`.
+    KeccakChipNotConfigured,
+    /// The proof failed a check of the native verifier.
+    Rejected(FailedCheck),
+    /// Halo2 failed to synthesize the circuit, generate keys or prove.
+    Halo2(String),
+    /// The mock prover found unsatisfied constraints.
+    ConstraintsNotSatisfied(Vec<String>),
+    /// The Solidity verifier could not be rendered.
+    SolidityGeneration(String),
+    /// Compiling, deploying or calling the Solidity verifier failed.
+    Evm(String),
+    /// Reading or writing parameters or keys failed.
+    Io(String),
+    /// The powers of tau file is malformed.
+    InvalidPowersOfTau(String),
+    /// The setup supports circuits of at most `2^available` rows.
+    SetupTooSmall { available: u32, required: u32 },
+    /// A value could not be encoded or decoded.
+    Encoding(String),
+    /// The encoded value was written by an incompatible version of this crate.
+    UnsupportedEncodingVersion(u32),
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Gates(e) => write!(f, "{e}"),
+            Self::UnsupportedExtensionDegree(d) => {
+                write!(f, "unsupported extension degree {d}, only D = 2 and D = 4 are supported")
+            }
+            Self::UnsupportedReductionArity { round, arity_bits } => write!(
+                f,
+                "unsupported FRI reduction arity 2^{arity_bits} in round {round}, arities 2 to 2^{MAX_REDUCTION_ARITY_BITS} are supported"
+            ),
+            Self::InvalidNumChallenges(n) => write!(f, "invalid number of challenges {n}"),
+            Self::InvalidCommonData(reason) => write!(f, "invalid common circuit data: {reason}"),
+            Self::InvalidProof(reason) => write!(f, "invalid proof: {reason}"),
+            Self::UnsupportedStark(reason) => write!(f, "unsupported stark: {reason}"),
+            Self::KeccakChipNotConfigured => write!(
+                f,
+                "the Keccak public inputs mode needs a hasher configuring the Keccak chip"
+            ),
+            Self::Rejected(check) => write!(f, "proof rejected: {check}"),
+            Self::Halo2(reason) => write!(f, "halo2 error: {reason}"),
+            Self::ConstraintsNotSatisfied(failures) => write!(
+                f,
+                "{} constraints are not satisfied, first failure: {}",
+                failures.len(),
+                failures.first().map(String::as_str).unwrap_or_default()
+            ),
+            Self::SolidityGeneration(reason) => {
+                write!(f, "failed to render solidity verifier: {reason}")
+            }
+            Self::Evm(reason) => write!(f, "EVM verification failed: {reason}"),
+            Self::Io(reason) => write!(f, "io error: {reason}"),
+            Self::InvalidPowersOfTau(reason) => write!(f, "invalid powers of tau: {reason}"),
+            Self::SetupTooSmall {
+                available,
+                required,
+            } => write!(
+                f,
+                "setup of degree {available} is too small for a circuit of degree {required}"
+            ),
+            Self::Encoding(reason) => write!(f, "encoding error: {reason}"),
+            Self::UnsupportedEncodingVersion(version) => {
+                write!(f, "unsupported encoding version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifierError {}
+
+impl From<GateRegistryError> for VerifierError {
+    fn from(value: GateRegistryError) -> Self {
+        Self::Gates(value)
+    }
+}
+
+impl From<io::Error> for VerifierError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value.to_string())
+    }
+}
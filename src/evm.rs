@@ -0,0 +1,149 @@
+//! Compiles and runs the Solidity verifier in a local EVM. Unlike the `Evm` and
+//! `compile_solidity` of `halo2_solidity_verifier`, which panic, failures are returned as
+//! `VerifierError::Evm`.
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use revm::{
+    primitives::{Address, CreateScheme, ExecutionResult, Output, TransactTo, TxEnv},
+    InMemoryDB, EVM,
+};
+
+use crate::error::VerifierError;
+
+/// Compiles `code` with the `solc` of the `PATH`, returning the bytecode of its last contract.
+pub fn compile_solidity(code: &str) -> Result<Vec<u8>, VerifierError> {
+    let mut solc = Command::new("solc")
+        .args(["--bin", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| VerifierError::Evm(format!("failed to run solc: {e}")))?;
+    solc.stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(code.as_bytes())
+        .map_err(|e| VerifierError::Evm(format!("failed to write to solc: {e}")))?;
+    let output = solc
+        .wait_with_output()
+        .map_err(|e| VerifierError::Evm(format!("failed to run solc: {e}")))?;
+    if !output.status.success() {
+        return Err(VerifierError::Evm(format!(
+            "solc failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    // The output ends with "Binary:" followed by the hex bytecode of the last contract.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let binary = stdout
+        .split_ascii_whitespace()
+        .last()
+        .ok_or_else(|| VerifierError::Evm("solc returned no bytecode".to_string()))?;
+    hex::decode(binary).map_err(|e| VerifierError::Evm(format!("invalid solc bytecode: {e}")))
+}
+
+/// An EVM with an in-memory state, deploying contracts and calling them without gas limit.
+pub struct Evm {
+    evm: EVM<InMemoryDB>,
+}
+
+impl Default for Evm {
+    fn default() -> Self {
+        let mut evm = EVM::new();
+        evm.database(InMemoryDB::default());
+        Self { evm }
+    }
+}
+
+impl Evm {
+    /// Deploys `bytecode`, returning the address of the contract.
+    pub fn create(&mut self, bytecode: Vec<u8>) -> Result<Address, VerifierError> {
+        let (_, output) = self.transact(TxEnv {
+            gas_limit: u64::MAX,
+            transact_to: TransactTo::Create(CreateScheme::Create),
+            data: bytecode.into(),
+            ..Default::default()
+        })?;
+        match output {
+            Output::Create(_, Some(address)) => Ok(address),
+            _ => Err(VerifierError::Evm(
+                "contract creation returned no address".to_string(),
+            )),
+        }
+    }
+
+    /// Calls the contract at `address`, returning the gas used and the output of the call.
+    pub fn call(
+        &mut self,
+        address: Address,
+        calldata: Vec<u8>,
+    ) -> Result<(u64, Vec<u8>), VerifierError> {
+        let (gas_used, output) = self.transact(TxEnv {
+            gas_limit: u64::MAX,
+            transact_to: TransactTo::Call(address),
+            data: calldata.into(),
+            ..Default::default()
+        })?;
+        Ok((gas_used, output.into_data().to_vec()))
+    }
+
+    // Commits `tx`, returning an error if it reverts or halts.
+    fn transact(&mut self, tx: TxEnv) -> Result<(u64, Output), VerifierError> {
+        self.evm.env.tx = tx;
+        let result = self.evm.transact_commit();
+        self.evm.env.tx = TxEnv::default();
+        match result.map_err(|e| VerifierError::Evm(format!("{e:?}")))? {
+            ExecutionResult::Success {
+                gas_used, output, ..
+            } => Ok((gas_used, output)),
+            ExecutionResult::Revert { gas_used, output } => Err(VerifierError::Evm(format!(
+                "transaction reverted with gas used {gas_used} and output 0x{}",
+                hex::encode(output)
+            ))),
+            ExecutionResult::Halt { reason, gas_used } => Err(VerifierError::Evm(format!(
+                "transaction halted with gas used {gas_used}: {reason:?}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile_solidity, Evm};
+    use crate::error::VerifierError;
+
+    const REVERTING_SOLIDITY: &str = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract Reverting {
+    fallback(bytes calldata input) external returns (bytes memory) {
+        require(input.length == 0, "unexpected calldata");
+        return input;
+    }
+}
+"#;
+
+    #[test]
+    fn test_evm_failures() {
+        assert!(matches!(
+            compile_solidity("contract {"),
+            Err(VerifierError::Evm(_))
+        ));
+
+        let mut evm = Evm::default();
+        let address = evm
+            .create(compile_solidity(REVERTING_SOLIDITY).unwrap())
+            .unwrap();
+        assert!(evm.call(address, vec![]).is_ok());
+        assert!(matches!(
+            evm.call(address, vec![1]),
+            Err(VerifierError::Evm(_))
+        ));
+        assert!(matches!(evm.create(vec![0xfe]), Err(VerifierError::Evm(_))));
+    }
+}
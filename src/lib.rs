@@ -1,6 +1,17 @@
+// The starky AIR of the tests is written against `starky::stark::Stark`, whose trace width is a
+// const generic expression.
+#![cfg_attr(test, allow(incomplete_features))]
+#![cfg_attr(test, feature(generic_const_exprs))]
+
 pub mod bn254_poseidon;
 pub mod chip;
+pub mod circuit_size;
 pub mod context;
+pub mod diagnostics;
+pub mod error;
+pub mod evm;
+pub mod native_verifier;
+pub mod setup;
 pub mod types;
 pub mod verifier_api;
 pub mod verifier_circuit;
@@ -0,0 +1,403 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::plonk::{ProvingKey, VerifyingKey};
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2_solidity_verifier::{encode_calldata, BatchOpenScheme::Bdfg21, SolidityGenerator};
+use log::{info, warn};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use plonky2::plonk::proof::ProofWithPublicInputs;
+use plonky2::util::serialization::DefaultGateSerializer;
+use stark_verifier::bn254_poseidon::plonky2_config::Bn254PoseidonGoldilocksConfig;
+use stark_verifier::error::VerifierError;
+use stark_verifier::setup::{
+    read_params, read_proving_key, read_ptau, read_verifying_key, write_params, write_proving_key,
+    write_verifying_key,
+};
+use stark_verifier::verifier_api::{
+    generate_proving_key, mock_prove_wrapper, prove_wrapper, verifier_circuit_size, verify_wrapper,
+    wrapper_instances,
+};
+use stark_verifier::verifier_circuit::{
+    KeccakCommitmentVerifier, ProofTuple, PublicInputsMode, Verifier,
+};
+
+type Tuple = ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, 2>;
+
+/// Wraps plonky2 proofs into Halo2 proofs verifiable on the EVM
+#[derive(Parser)]
+#[command(name = "stark-verifier", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Loads or generates KZG parameters and generates the keys of the wrapper circuit
+    Setup {
+        #[command(flatten)]
+        plonky2: Plonky2Files,
+        /// log2 of the number of rows of the wrapper circuit, the smallest one fitting it by
+        /// default
+        #[arg(long)]
+        degree: Option<u32>,
+        /// snarkjs powers of tau file, e.g. from the perpetual powers of tau ceremony
+        #[arg(long, conflicts_with = "srs")]
+        ptau: Option<PathBuf>,
+        /// KZG parameters in the halo2 format
+        #[arg(long)]
+        srs: Option<PathBuf>,
+        #[command(flatten)]
+        keys: KeyFiles,
+        /// how the public inputs are exposed as instances
+        #[arg(long, value_enum, default_value_t)]
+        public_inputs: PublicInputs,
+    },
+    /// Generates a Halo2 proof of the verification of a plonky2 proof
+    Prove {
+        #[command(flatten)]
+        plonky2: Plonky2Files,
+        #[command(flatten)]
+        keys: KeyFiles,
+        /// output file of the Halo2 proof
+        #[arg(long)]
+        out: PathBuf,
+        /// output file of the public instances
+        #[arg(long)]
+        instances: PathBuf,
+        /// must be the one given to `setup`
+        #[arg(long, value_enum, default_value_t)]
+        public_inputs: PublicInputs,
+    },
+    /// Verifies a Halo2 proof natively
+    Verify {
+        #[command(flatten)]
+        keys: KeyFiles,
+        #[arg(long)]
+        proof: PathBuf,
+        #[arg(long)]
+        instances: PathBuf,
+        /// must be the one given to `setup`
+        #[arg(long, value_enum, default_value_t)]
+        public_inputs: PublicInputs,
+    },
+    /// Checks the constraints of the wrapper circuit with the mock prover
+    Mock {
+        #[command(flatten)]
+        plonky2: Plonky2Files,
+        /// the smallest one fitting the wrapper circuit by default
+        #[arg(long)]
+        degree: Option<u32>,
+        /// how the public inputs are exposed as instances
+        #[arg(long, value_enum, default_value_t)]
+        public_inputs: PublicInputs,
+    },
+    /// Renders the Solidity verifier and verifying key contracts
+    GenSolidity {
+        #[command(flatten)]
+        keys: KeyFiles,
+        /// `CommonCircuitData` file of the plonky2 circuit, which gives its number of public
+        /// inputs
+        #[arg(long)]
+        common_data: PathBuf,
+        /// must be the one given to `setup`
+        #[arg(long, value_enum, default_value_t)]
+        public_inputs: PublicInputs,
+        /// output directory of the contracts
+        #[arg(long)]
+        out_dir: PathBuf,
+    },
+    /// Encodes the calldata of the Solidity verifier
+    Calldata {
+        #[arg(long)]
+        proof: PathBuf,
+        #[arg(long)]
+        instances: PathBuf,
+        /// address of the verifying key contract, as 0x-prefixed hex
+        #[arg(long)]
+        vk_address: Option<String>,
+    },
+}
+
+/// How the public inputs of the plonky2 proof are exposed as instances of the wrapper circuit
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum PublicInputs {
+    /// one instance per public input
+    #[default]
+    Raw,
+    /// three public inputs per instance
+    Packed,
+    /// the 4 elements of the plonky2 hash of the public inputs
+    Digest,
+    /// the high and low 128 bits of the Keccak-256 of the public inputs as big-endian `uint64`s
+    Keccak,
+}
+
+impl PublicInputs {
+    /// The Keccak chip is only configured in `PublicInputsMode::Keccak`, which changes the keys.
+    fn read_proving_key(self, path: &Path) -> Result<ProvingKey<G1Affine>> {
+        Ok(match self {
+            PublicInputs::Keccak => read_proving_key::<KeccakCommitmentVerifier<2>>(path)?,
+            _ => read_proving_key::<Verifier<2>>(path)?,
+        })
+    }
+
+    fn read_verifying_key(self, path: &Path) -> Result<VerifyingKey<G1Affine>> {
+        Ok(match self {
+            PublicInputs::Keccak => read_verifying_key::<KeccakCommitmentVerifier<2>>(path)?,
+            _ => read_verifying_key::<Verifier<2>>(path)?,
+        })
+    }
+}
+
+impl From<PublicInputs> for PublicInputsMode {
+    fn from(public_inputs: PublicInputs) -> Self {
+        match public_inputs {
+            PublicInputs::Raw => PublicInputsMode::Raw,
+            PublicInputs::Packed => PublicInputsMode::Packed,
+            PublicInputs::Digest => PublicInputsMode::Digest,
+            PublicInputs::Keccak => PublicInputsMode::Keccak,
+        }
+    }
+}
+
+/// plonky2 proof with its circuit data, serialized with the plonky2 byte encoding
+#[derive(Args)]
+struct Plonky2Files {
+    /// `ProofWithPublicInputs` file
+    #[arg(long)]
+    proof: PathBuf,
+    /// `VerifierOnlyCircuitData` file
+    #[arg(long)]
+    verifier_data: PathBuf,
+    /// `CommonCircuitData` file
+    #[arg(long)]
+    common_data: PathBuf,
+}
+
+#[derive(Args)]
+struct KeyFiles {
+    /// KZG parameters of the wrapper circuit
+    #[arg(long)]
+    params: PathBuf,
+    #[arg(long)]
+    pk: Option<PathBuf>,
+    #[arg(long)]
+    vk: Option<PathBuf>,
+}
+
+impl Plonky2Files {
+    fn read(&self) -> Result<Tuple> {
+        let common_data = read_common_data(&self.common_data)?;
+        let verifier_data =
+            VerifierOnlyCircuitData::<Bn254PoseidonGoldilocksConfig, 2>::from_bytes(read(
+                &self.verifier_data,
+            )?)
+            .map_err(|e| anyhow!("invalid verifier data: {e:?}"))?;
+        let proof = ProofWithPublicInputs::from_bytes(read(&self.proof)?, &common_data)
+            .map_err(|e| anyhow!("invalid proof: {e}"))?;
+        Ok((proof, verifier_data, common_data))
+    }
+}
+
+impl KeyFiles {
+    fn pk(&self) -> Result<&Path> {
+        self.pk
+            .as_deref()
+            .ok_or_else(|| anyhow!("--pk is required"))
+    }
+
+    fn vk(&self) -> Result<&Path> {
+        self.vk
+            .as_deref()
+            .ok_or_else(|| anyhow!("--vk is required"))
+    }
+}
+
+fn read_common_data(path: &Path) -> Result<CommonCircuitData<GoldilocksField, 2>> {
+    CommonCircuitData::from_bytes(read(path)?, &DefaultGateSerializer)
+        .map_err(|e| anyhow!("invalid common circuit data: {e:?}"))
+}
+
+fn read(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+fn write(path: &Path, bytes: impl AsRef<[u8]>) -> Result<()> {
+    fs::write(path, bytes).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Instances are stored as a JSON array of big endian hex strings
+fn write_instances(path: &Path, instances: &[Fr]) -> Result<()> {
+    let hex = instances
+        .iter()
+        .map(|x| {
+            let mut bytes = x.to_repr();
+            bytes.reverse();
+            format!("0x{}", hex::encode(bytes))
+        })
+        .collect::<Vec<_>>();
+    write(path, serde_json::to_string_pretty(&hex)?)
+}
+
+fn read_instances(path: &Path) -> Result<Vec<Fr>> {
+    let hex: Vec<String> = serde_json::from_slice(&read(path)?)?;
+    hex.iter()
+        .map(|s| {
+            let mut bytes: [u8; 32] = hex::decode(s.trim_start_matches("0x"))?
+                .try_into()
+                .map_err(|_| anyhow!("instance {s} is not 32 bytes"))?;
+            bytes.reverse();
+            Option::from(Fr::from_repr(bytes)).ok_or_else(|| anyhow!("instance {s} is not in Fr"))
+        })
+        .collect()
+}
+
+fn setup_params(degree: u32, ptau: Option<&Path>, srs: Option<&Path>) -> Result<ParamsKZG<Bn256>> {
+    let params = match (ptau, srs) {
+        (Some(ptau), _) => read_ptau(ptau, degree)?,
+        (None, Some(srs)) => read_params(srs, degree)?,
+        (None, None) => {
+            warn!("no powers of tau given, generating an insecure setup");
+            ParamsKZG::<Bn256>::setup(degree, rand::thread_rng())
+        }
+    };
+    Ok(params)
+}
+
+// the given degree, or the smallest one fitting the wrapper circuit of `proof`
+fn wrapper_degree(degree: Option<u32>, proof: &Tuple, public_inputs: PublicInputs) -> Result<u32> {
+    if let Some(degree) = degree {
+        return Ok(degree);
+    }
+    let size = verifier_circuit_size(proof, public_inputs.into())?;
+    info!(
+        "the wrapper circuit uses {} rows, {} advice and {} fixed cells, degree {}",
+        size.rows, size.advice_cells, size.fixed_cells, size.k
+    );
+    Ok(size.k)
+}
+
+fn run(command: Command) -> Result<bool> {
+    match command {
+        Command::Setup {
+            plonky2,
+            degree,
+            ptau,
+            srs,
+            keys,
+            public_inputs,
+        } => {
+            // fails on missing outputs before the setup, which takes minutes
+            let (vk_path, pk_path) = (keys.vk()?, keys.pk()?);
+            let proof = plonky2.read()?;
+            let degree = wrapper_degree(degree, &proof, public_inputs)?;
+            let params = setup_params(degree, ptau.as_deref(), srs.as_deref())?;
+            let pk = generate_proving_key(&params, proof, public_inputs.into())?;
+            write_params(&params, &keys.params)?;
+            write_verifying_key(pk.get_vk(), vk_path)?;
+            write_proving_key(&pk, pk_path)?;
+        }
+        Command::Prove {
+            plonky2,
+            keys,
+            out,
+            instances,
+            public_inputs,
+        } => {
+            let pk = public_inputs.read_proving_key(keys.pk()?)?;
+            let params = read_params(&keys.params, pk.get_vk().get_domain().k())?;
+            let proof = plonky2.read()?;
+            write_instances(
+                &instances,
+                &wrapper_instances(&proof, public_inputs.into())?,
+            )?;
+            write(
+                &out,
+                prove_wrapper(&pk, &params, proof, public_inputs.into())?,
+            )?;
+        }
+        Command::Verify {
+            keys,
+            proof,
+            instances,
+            public_inputs,
+        } => {
+            let vk = public_inputs.read_verifying_key(keys.vk()?)?;
+            let params = read_params(&keys.params, vk.get_domain().k())?;
+            let valid = verify_wrapper(&vk, &params, &read(&proof)?, &read_instances(&instances)?)?;
+            println!("{}", if valid { "valid" } else { "invalid" });
+            return Ok(valid);
+        }
+        Command::Mock {
+            plonky2,
+            degree,
+            public_inputs,
+        } => {
+            let proof = plonky2.read()?;
+            let degree = wrapper_degree(degree, &proof, public_inputs)?;
+            match mock_prove_wrapper(degree, proof, public_inputs.into()) {
+                Ok(_) => println!("all constraints are satisfied"),
+                Err(VerifierError::ConstraintsNotSatisfied(failures)) => {
+                    for failure in failures.iter() {
+                        println!("{failure}");
+                    }
+                    println!("{} constraints are not satisfied", failures.len());
+                    return Ok(false);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Command::GenSolidity {
+            keys,
+            common_data,
+            out_dir,
+            public_inputs,
+        } => {
+            let vk = public_inputs.read_verifying_key(keys.vk()?)?;
+            let params = read_params(&keys.params, vk.get_domain().k())?;
+            let num_public_inputs = read_common_data(&common_data)?.num_public_inputs;
+            let num_instances =
+                PublicInputsMode::from(public_inputs).num_instances(num_public_inputs);
+            let generator = SolidityGenerator::new(&params, &vk, Bdfg21, num_instances);
+            let (verifier_solidity, vk_solidity) = generator.render_separately()?;
+            fs::create_dir_all(&out_dir)?;
+            write(&out_dir.join("Halo2Verifier.sol"), verifier_solidity)?;
+            write(&out_dir.join("Halo2VerifyingKey.sol"), vk_solidity)?;
+        }
+        Command::Calldata {
+            proof,
+            instances,
+            vk_address,
+        } => {
+            let vk_address = vk_address
+                .map(|address| -> Result<[u8; 20]> {
+                    hex::decode(address.trim_start_matches("0x"))?
+                        .try_into()
+                        .map_err(|_| anyhow!("address {address} is not 20 bytes"))
+                })
+                .transpose()?;
+            let calldata =
+                encode_calldata(vk_address, &read(&proof)?, &read_instances(&instances)?);
+            println!("0x{}", hex::encode(calldata));
+        }
+    }
+    Ok(true)
+}
+
+fn main() -> Result<ExitCode> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let cli = Cli::parse();
+    Ok(if run(cli.command)? {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
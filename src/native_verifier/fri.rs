@@ -0,0 +1,300 @@
+use halo2_proofs::halo2curves::ff::PrimeField;
+use itertools::Itertools;
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        goldilocks_field::GoldilocksField,
+        types::{Field, PrimeField64},
+    },
+    hash::hash_types::HashOut,
+    plonk::config::Hasher,
+    util::{reverse_bits, reverse_index_bits_in_place},
+};
+
+use crate::{
+    chip::plonk::gates::reduce_native,
+    types::{
+        common_data::CommonData,
+        proof::{FriProofValues, FriQueryRoundValues, MerkleProofValues},
+        MerkleCapValues,
+    },
+};
+
+use super::{to_extension, Ext, FailedCheck};
+
+/// The FRI challenges of a proof, drawn like `TranscriptChip::fri_challenges`.
+#[derive(Clone, Debug)]
+pub struct FriChallenges<const D: usize>
+where
+    GoldilocksField: Extendable<D>,
+{
+    /// Scaling factor to combine polynomials.
+    pub fri_alpha: Ext<D>,
+    /// Betas used in the FRI commit phase reductions.
+    pub fri_betas: Vec<Ext<D>>,
+    pub fri_pow_response: GoldilocksField,
+    /// Indices at which the oracle is queried in FRI.
+    pub fri_query_indices: Vec<GoldilocksField>,
+}
+
+/// Checks that `leaf` is at `leaf_index` of the tree below `cap[cap_index]`, like
+/// `MerkleProofChip::verify_merkle_proof_to_cap_with_cap_index`.
+fn verify_merkle_proof_to_cap<F, H>(
+    leaf: &[GoldilocksField],
+    leaf_index: usize,
+    cap_index: usize,
+    cap: &MerkleCapValues<F>,
+    proof: &MerkleProofValues<F>,
+) -> bool
+where
+    F: PrimeField,
+    H: Hasher<GoldilocksField, Hash = HashOut<GoldilocksField>>,
+{
+    let mut state = H::hash_or_noop(leaf);
+    for (i, sibling) in proof.siblings.iter().enumerate() {
+        let sibling = HashOut {
+            elements: sibling.elements,
+        };
+        state = if (leaf_index >> i) & 1 == 1 {
+            H::two_to_one(sibling, state)
+        } else {
+            H::two_to_one(state, sibling)
+        };
+    }
+    state.elements == cap.0[cap_index].elements
+}
+
+/// Computes `P'(x^arity)` from the evaluations of `P` on the coset of `x`, like
+/// `FriVerifierChip::next_eval`.
+fn next_eval<const D: usize>(
+    x_index_within_coset: usize,
+    x: GoldilocksField,
+    evals: &[Ext<D>],
+    arity_bits: usize,
+    beta: Ext<D>,
+) -> Ext<D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    let arity = 1 << arity_bits;
+    let g = GoldilocksField::MULTIPLICATIVE_GROUP_GENERATOR
+        .exp_u64(GoldilocksField::NEG_ONE.to_canonical_u64() / (arity as u64));
+    let g_inv = g.inverse();
+
+    // The evaluation vector needs to be reordered first.
+    let mut evals = evals.to_vec();
+    reverse_index_bits_in_place(&mut evals);
+
+    let start = g_inv.exp_u64(reverse_bits(x_index_within_coset, arity_bits) as u64);
+    let coset_start = start * x;
+
+    // The answer is gotten by interpolating {(x*g^i, P(x*g^i))} and evaluating at beta.
+    let points = g
+        .powers()
+        .map(|g_power| Ext::<D>::from(coset_start * g_power))
+        .zip(evals)
+        .collect_vec();
+    if arity_bits == 1 {
+        // a1 + (beta - a0) * (b1 - a1) / (b0 - a0)
+        let (a0, a1) = points[0];
+        let (b0, b1) = points[1];
+        return a1 + (beta - a0) * (b1 - a1) / (b0 - a0);
+    }
+
+    // For larger arities, we use the barycentric formula:
+    // P'(beta) = (beta^n - s^n) / (n * s^n) * \sum_i x_i * P(x_i) / (beta - x_i)
+    let sum = points
+        .iter()
+        .map(|&(x_i, eval)| x_i * eval / (beta - x_i))
+        .sum::<Ext<D>>();
+    let coset_start_pow = coset_start.exp_power_of_2(arity_bits);
+    let vanishing = beta.exp_power_of_2(arity_bits) - Ext::<D>::from(coset_start_pow);
+    vanishing * sum / Ext::<D>::from(coset_start_pow * GoldilocksField::from_canonical_usize(arity))
+}
+
+/// Combines the initial polynomials opened at `x`, like
+/// `FriVerifierChip::batch_initial_polynomials`.
+fn batch_initial_polynomials<F: PrimeField, const D: usize>(
+    common_data: &CommonData<F, D>,
+    points: [Ext<D>; 2],
+    fri_alpha: Ext<D>,
+    x: GoldilocksField,
+    round_proof: &FriQueryRoundValues<F, D>,
+    reduced_openings: &[Ext<D>],
+) -> Ext<D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    let x = Ext::<D>::from(x);
+    let batches = [
+        common_data.fri_all_polys(),
+        common_data.fri_next_batch_polys(),
+    ];
+    let mut sum = Ext::<D>::ZERO;
+    for ((polynomials, point), reduced_opening) in batches.iter().zip(points).zip(reduced_openings)
+    {
+        let evals = polynomials
+            .iter()
+            .map(|p| {
+                Ext::<D>::from(
+                    round_proof.initial_trees_proof.evals_proofs[p.oracle_index].0
+                        [p.polynomial_index],
+                )
+            })
+            .collect_vec();
+        let numerator = reduce_native(fri_alpha, &evals) - *reduced_opening;
+        sum = fri_alpha.exp_u64(evals.len() as u64) * sum + numerator / (x - point);
+    }
+    sum
+}
+
+fn check_consistency<F, H, const D: usize>(
+    query: usize,
+    initial_merkle_caps: &[&MerkleCapValues<F>],
+    common_data: &CommonData<F, D>,
+    points: [Ext<D>; 2],
+    fri_challenges: &FriChallenges<D>,
+    fri_proof: &FriProofValues<F, D>,
+    round_proof: &FriQueryRoundValues<F, D>,
+    reduced_openings: &[Ext<D>],
+) -> Result<(), FailedCheck>
+where
+    F: PrimeField,
+    H: Hasher<GoldilocksField, Hash = HashOut<GoldilocksField>>,
+    GoldilocksField: Extendable<D>,
+{
+    let fri_params = &common_data.fri_params;
+    let lde_bits = fri_params.lde_bits();
+
+    // `x_index` is the index of point selected from initial domain
+    let mut x_index =
+        fri_challenges.fri_query_indices[query].to_canonical_u64() as usize & ((1 << lde_bits) - 1);
+    let cap_index = x_index >> (lde_bits - fri_params.config.cap_height);
+
+    // verify evaluation proofs for initial polynomials at `x_index` point
+    for (oracle, ((evals, merkle_proof), cap)) in round_proof
+        .initial_trees_proof
+        .evals_proofs
+        .iter()
+        .zip(initial_merkle_caps)
+        .enumerate()
+    {
+        if !verify_merkle_proof_to_cap::<F, H>(evals, x_index, cap_index, cap, merkle_proof) {
+            return Err(FailedCheck::InitialMerkleProof { query, oracle });
+        }
+    }
+
+    // `omega` is the root of unity for initial domain in FRI
+    let omega = GoldilocksField::MULTIPLICATIVE_GROUP_GENERATOR
+        .exp_u64(GoldilocksField::NEG_ONE.to_canonical_u64() / (1 << lde_bits));
+    let mut x = GoldilocksField::MULTIPLICATIVE_GROUP_GENERATOR
+        * omega.exp_u64(reverse_bits(x_index, lde_bits) as u64);
+
+    let mut prev_eval = batch_initial_polynomials(
+        common_data,
+        points,
+        fri_challenges.fri_alpha,
+        x,
+        round_proof,
+        reduced_openings,
+    );
+
+    for (step, &arity_bits) in fri_params.reduction_arity_bits.iter().enumerate() {
+        let query_step = &round_proof.steps[step];
+        let evals = query_step.evals.iter().map(to_extension).collect_vec();
+
+        // Split x_index into the index of the coset x is in, and the index of x within that coset.
+        let coset_index = x_index >> arity_bits;
+        let x_index_within_coset = x_index & ((1 << arity_bits) - 1);
+
+        // check the consistency of `prev_eval` and `next_eval`
+        if evals[x_index_within_coset] != prev_eval {
+            return Err(FailedCheck::FriConsistency { query, step });
+        }
+
+        prev_eval = next_eval(
+            x_index_within_coset,
+            x,
+            &evals,
+            arity_bits,
+            fri_challenges.fri_betas[step],
+        );
+
+        let leaf = evals
+            .iter()
+            .flat_map(|eval| eval.to_basefield_array())
+            .collect_vec();
+        if !verify_merkle_proof_to_cap::<F, H>(
+            &leaf,
+            coset_index,
+            cap_index,
+            &fri_proof.commit_phase_merkle_cap_values[step],
+            &query_step.merkle_proof,
+        ) {
+            return Err(FailedCheck::CommitPhaseMerkleProof { query, step });
+        }
+
+        // Update the point x to x^arity.
+        x = x.exp_power_of_2(arity_bits);
+        x_index = coset_index;
+    }
+
+    // Final check of FRI. After all the reductions, we check that the final polynomial is equal
+    // to the one sent by the prover.
+    let final_poly_coeffs = fri_proof
+        .final_poly
+        .0
+        .iter()
+        .map(to_extension)
+        .collect_vec();
+    if reduce_native(Ext::<D>::from(x), &final_poly_coeffs) != prev_eval {
+        return Err(FailedCheck::FinalPolynomial { query });
+    }
+    Ok(())
+}
+
+/// Verifies the FRI proof of the openings at `points`, like `FriVerifierChip::verify_fri_proof`.
+/// The FRI domain is the coset of `MULTIPLICATIVE_GROUP_GENERATOR`.
+pub fn verify_fri_proof<F, H, const D: usize>(
+    initial_merkle_caps: &[&MerkleCapValues<F>],
+    fri_challenges: &FriChallenges<D>,
+    points: [Ext<D>; 2],
+    fri_openings: &[Vec<Ext<D>>; 2],
+    fri_proof: &FriProofValues<F, D>,
+    common_data: &CommonData<F, D>,
+) -> Result<(), FailedCheck>
+where
+    F: PrimeField,
+    H: Hasher<GoldilocksField, Hash = HashOut<GoldilocksField>>,
+    GoldilocksField: Extendable<D>,
+{
+    // verify proof of work
+    let proof_of_work_bits = common_data.fri_params.config.proof_of_work_bits;
+    if fri_challenges
+        .fri_pow_response
+        .to_canonical_u64()
+        .leading_zeros()
+        < proof_of_work_bits
+    {
+        return Err(FailedCheck::ProofOfWork);
+    }
+
+    // this value is the same across all queries
+    let reduced_openings = fri_openings
+        .iter()
+        .map(|values| reduce_native(fri_challenges.fri_alpha, values))
+        .collect_vec();
+    for (query, round_proof) in fri_proof.query_round_proofs.iter().enumerate() {
+        check_consistency::<F, H, D>(
+            query,
+            initial_merkle_caps,
+            common_data,
+            points,
+            fri_challenges,
+            fri_proof,
+            round_proof,
+            &reduced_openings,
+        )?;
+    }
+    Ok(())
+}
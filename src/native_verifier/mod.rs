@@ -0,0 +1,520 @@
+//! Verifies plonky2 proofs natively, with the algorithm constrained by `PlonkVerifierChip` and
+//! `FriVerifierChip`. It runs in milliseconds, so it is a cheap check that the verifier circuit of
+//! a proof will be satisfied, and it tells which check a bad proof fails.
+//!
+//! The gates are evaluated by `CustomGateConstrainer::eval_unfiltered_native`, which mirrors the
+//! constraints of each constrainer, except for `PoseidonGateConstrainer`: it evaluates plonky2's
+//! `PoseidonGate`, so only the mock prover catches a bug of the Poseidon constrainer. Circuits
+//! with a gate that has no native evaluation are rejected with `VerifierError::Gates`.
+
+mod fri;
+mod vanishing_poly;
+
+use std::fmt;
+
+use halo2_proofs::halo2curves::ff::PrimeField;
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        goldilocks_field::GoldilocksField,
+        types::{Field, PrimeField64},
+    },
+    hash::{hash_types::HashOut, poseidon::PoseidonHash},
+    iop::challenger::Challenger,
+    plonk::config::Hasher,
+};
+
+use crate::{
+    bn254_poseidon::plonky2_config::Bn254PoseidonHash,
+    chip::plonk::{gates::reduce_native, lookup::NUM_COINS_LOOKUP},
+    error::VerifierError,
+    types::{
+        assigned::SALT_SIZE, common_data::CommonData, proof::ProofValues,
+        verification_key::VerificationKeyValues, ExtensionFieldValue, MerkleCapValues,
+    },
+};
+
+pub use self::{
+    fri::{verify_fri_proof, FriChallenges},
+    vanishing_poly::eval_vanishing_poly,
+};
+
+/// The extension of degree `D` of Goldilocks, in which the openings are evaluated.
+pub type Ext<const D: usize> = <GoldilocksField as Extendable<D>>::Extension;
+
+/// A check of the verifier that the proof failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FailedCheck {
+    /// The vanishing polynomial of the given challenge round does not match the quotient
+    /// polynomials at `zeta`.
+    QuotientPolynomial { challenge: usize },
+    /// The FRI proof of work response does not start with enough zero bits.
+    ProofOfWork,
+    /// The evaluations of the given oracle in the given query round do not open to its cap.
+    InitialMerkleProof { query: usize, oracle: usize },
+    /// The evaluations of the given reduction step in the given query round do not open to its
+    /// commit phase cap.
+    CommitPhaseMerkleProof { query: usize, step: usize },
+    /// The evaluations of the given reduction step in the given query round do not contain the
+    /// evaluation folded in the previous step.
+    FriConsistency { query: usize, step: usize },
+    /// The final polynomial does not match the evaluation folded in the given query round.
+    FinalPolynomial { query: usize },
+}
+
+impl fmt::Display for FailedCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QuotientPolynomial { challenge } => write!(
+                f,
+                "vanishing polynomial of challenge {challenge} does not match the quotient polynomials"
+            ),
+            Self::ProofOfWork => write!(f, "FRI proof of work is invalid"),
+            Self::InitialMerkleProof { query, oracle } => write!(
+                f,
+                "Merkle proof of oracle {oracle} in FRI query {query} is invalid"
+            ),
+            Self::CommitPhaseMerkleProof { query, step } => write!(
+                f,
+                "Merkle proof of reduction step {step} in FRI query {query} is invalid"
+            ),
+            Self::FriConsistency { query, step } => write!(
+                f,
+                "evaluations of reduction step {step} in FRI query {query} are inconsistent"
+            ),
+            Self::FinalPolynomial { query } => write!(
+                f,
+                "final polynomial does not match the evaluation of FRI query {query}"
+            ),
+        }
+    }
+}
+
+/// The challenges of a proof, drawn like `PlonkVerifierChip::get_challenges`.
+#[derive(Clone, Debug)]
+pub struct ProofChallenges<const D: usize>
+where
+    GoldilocksField: Extendable<D>,
+{
+    pub plonk_betas: Vec<GoldilocksField>,
+    pub plonk_gammas: Vec<GoldilocksField>,
+    /// The lookup challenges, empty if the circuit has no lookups.
+    pub plonk_deltas: Vec<GoldilocksField>,
+    pub plonk_alphas: Vec<GoldilocksField>,
+    pub plonk_zeta: Ext<D>,
+    pub fri_challenges: FriChallenges<D>,
+}
+
+pub(crate) fn to_extension<F: PrimeField, const D: usize>(
+    value: &ExtensionFieldValue<F, D>,
+) -> Ext<D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    Ext::<D>::from_basefield_array(value.elements)
+}
+
+fn to_extensions<F: PrimeField, const D: usize>(values: &[ExtensionFieldValue<F, D>]) -> Vec<Ext<D>>
+where
+    GoldilocksField: Extendable<D>,
+{
+    values.iter().map(to_extension).collect()
+}
+
+fn observe_cap<F: PrimeField, H: Hasher<GoldilocksField>>(
+    challenger: &mut Challenger<GoldilocksField, H>,
+    cap: &MerkleCapValues<F>,
+) {
+    for hash in cap.0.iter() {
+        challenger.observe_elements(&hash.elements);
+    }
+}
+
+fn observe_extensions<F: PrimeField, H: Hasher<GoldilocksField>, const D: usize>(
+    challenger: &mut Challenger<GoldilocksField, H>,
+    values: &[ExtensionFieldValue<F, D>],
+) {
+    for value in values {
+        challenger.observe_elements(&value.elements);
+    }
+}
+
+/// Draws the challenges of `proof` from a challenger over the permutation of `H`.
+pub fn get_challenges<F: PrimeField, H: Hasher<GoldilocksField>, const D: usize>(
+    proof: &ProofValues<F, D>,
+    public_inputs_hash: &HashOut<GoldilocksField>,
+    vk: &VerificationKeyValues<F>,
+    common_data: &CommonData<F, D>,
+) -> ProofChallenges<D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    let num_challenges = common_data.config.num_challenges;
+    let mut challenger = Challenger::<GoldilocksField, H>::new();
+    challenger.observe_elements(&vk.circuit_digest.elements);
+    challenger.observe_elements(&public_inputs_hash.elements);
+
+    observe_cap(&mut challenger, &proof.wires_cap);
+    let plonk_betas = challenger.get_n_challenges(num_challenges);
+    let plonk_gammas = challenger.get_n_challenges(num_challenges);
+
+    // With lookups, the betas and gammas are reused as the first lookup challenges.
+    let plonk_deltas = if common_data.has_lookup() {
+        let additional =
+            challenger.get_n_challenges(NUM_COINS_LOOKUP * num_challenges - 2 * num_challenges);
+        [plonk_betas.clone(), plonk_gammas.clone(), additional].concat()
+    } else {
+        vec![]
+    };
+
+    observe_cap(&mut challenger, &proof.plonk_zs_partial_products_cap);
+    let plonk_alphas = challenger.get_n_challenges(num_challenges);
+
+    observe_cap(&mut challenger, &proof.quotient_polys_cap);
+    let plonk_zeta = challenger.get_extension_challenge::<D>();
+
+    let openings = &proof.openings;
+    for values in [
+        &openings.constants,
+        &openings.plonk_sigmas,
+        &openings.wires,
+        &openings.plonk_zs,
+        &openings.partial_products,
+        &openings.quotient_polys,
+        &openings.lookup_zs,
+        &openings.plonk_zs_next,
+        &openings.lookup_zs_next,
+    ] {
+        observe_extensions(&mut challenger, values);
+    }
+
+    let fri_proof = &proof.opening_proof;
+    let fri_alpha = challenger.get_extension_challenge::<D>();
+    let fri_betas = fri_proof
+        .commit_phase_merkle_cap_values
+        .iter()
+        .map(|cap| {
+            observe_cap(&mut challenger, cap);
+            challenger.get_extension_challenge::<D>()
+        })
+        .collect();
+    observe_extensions(&mut challenger, &fri_proof.final_poly.0);
+    challenger.observe_element(fri_proof.pow_witness);
+    let fri_pow_response = challenger.get_challenge();
+    let fri_query_indices =
+        challenger.get_n_challenges(common_data.config.fri_config.num_query_rounds);
+
+    ProofChallenges {
+        plonk_betas,
+        plonk_gammas,
+        plonk_deltas,
+        plonk_alphas,
+        plonk_zeta,
+        fri_challenges: FriChallenges {
+            fri_alpha,
+            fri_betas,
+            fri_pow_response,
+            fri_query_indices,
+        },
+    }
+}
+
+/// Checks that the openings, caps and FRI query rounds of `proof` have the lengths expected by
+/// `common_data`, so that the verifier never reads out of bounds. Also runs on the decoding of
+/// `VerifierInput`, before the verifier circuit indexes them.
+pub(crate) fn check_proof_shape<F: PrimeField, const D: usize>(
+    proof: &ProofValues<F, D>,
+    public_inputs: &[GoldilocksField],
+    vk: &VerificationKeyValues<F>,
+    common_data: &CommonData<F, D>,
+) -> Result<(), VerifierError> {
+    let config = &common_data.config;
+    let fri_params = &common_data.fri_params;
+    let openings = &proof.openings;
+    let fri_proof = &proof.opening_proof;
+    let cap_len = 1 << fri_params.config.cap_height;
+    let lde_bits = fri_params.lde_bits();
+    let total_arity_bits = fri_params.reduction_arity_bits.iter().sum::<usize>();
+    let mut expected_lengths = vec![
+        (
+            "public inputs".to_string(),
+            public_inputs.len(),
+            common_data.num_public_inputs,
+        ),
+        (
+            "constants openings".to_string(),
+            openings.constants.len(),
+            common_data.num_constants,
+        ),
+        (
+            "sigmas openings".to_string(),
+            openings.plonk_sigmas.len(),
+            config.num_routed_wires,
+        ),
+        (
+            "wires openings".to_string(),
+            openings.wires.len(),
+            config.num_wires,
+        ),
+        (
+            "zs openings".to_string(),
+            openings.plonk_zs.len(),
+            config.num_challenges,
+        ),
+        (
+            "next zs openings".to_string(),
+            openings.plonk_zs_next.len(),
+            config.num_challenges,
+        ),
+        (
+            "partial products openings".to_string(),
+            openings.partial_products.len(),
+            config.num_challenges * common_data.num_partial_products,
+        ),
+        (
+            "quotient polys openings".to_string(),
+            openings.quotient_polys.len(),
+            common_data.num_quotient_polys(),
+        ),
+        (
+            "lookup zs openings".to_string(),
+            openings.lookup_zs.len(),
+            common_data.num_all_lookup_polys(),
+        ),
+        (
+            "next lookup zs openings".to_string(),
+            openings.lookup_zs_next.len(),
+            common_data.num_all_lookup_polys(),
+        ),
+        (
+            "constants sigmas cap hashes".to_string(),
+            vk.constants_sigmas_cap.0.len(),
+            cap_len,
+        ),
+        (
+            "wires cap hashes".to_string(),
+            proof.wires_cap.0.len(),
+            cap_len,
+        ),
+        (
+            "zs partial products cap hashes".to_string(),
+            proof.plonk_zs_partial_products_cap.0.len(),
+            cap_len,
+        ),
+        (
+            "quotient polys cap hashes".to_string(),
+            proof.quotient_polys_cap.0.len(),
+            cap_len,
+        ),
+        (
+            "commit phase caps".to_string(),
+            fri_proof.commit_phase_merkle_cap_values.len(),
+            fri_params.reduction_arity_bits.len(),
+        ),
+        (
+            "query rounds".to_string(),
+            fri_proof.query_round_proofs.len(),
+            fri_params.config.num_query_rounds,
+        ),
+        (
+            "final polynomial coefficients".to_string(),
+            fri_proof.final_poly.0.len(),
+            1 << fri_params.degree_bits.saturating_sub(total_arity_bits),
+        ),
+    ];
+    for (i, cap) in fri_proof.commit_phase_merkle_cap_values.iter().enumerate() {
+        expected_lengths.push((
+            format!("hashes in commit phase cap {i}"),
+            cap.0.len(),
+            cap_len,
+        ));
+    }
+    for (query, round) in fri_proof.query_round_proofs.iter().enumerate() {
+        let oracles = common_data.fri_oracles();
+        expected_lengths.push((
+            format!("initial trees in query {query}"),
+            round.initial_trees_proof.evals_proofs.len(),
+            oracles.len(),
+        ));
+        for (oracle, ((evals, merkle_proof), info)) in round
+            .initial_trees_proof
+            .evals_proofs
+            .iter()
+            .zip(oracles.iter())
+            .enumerate()
+        {
+            let salt_size = if fri_params.hiding && info.blinding {
+                SALT_SIZE
+            } else {
+                0
+            };
+            expected_lengths.push((
+                format!("evaluations of oracle {oracle} in query {query}"),
+                evals.len(),
+                info.num_polys + salt_size,
+            ));
+            expected_lengths.push((
+                format!("siblings of oracle {oracle} in query {query}"),
+                merkle_proof.siblings.len(),
+                lde_bits - fri_params.config.cap_height,
+            ));
+        }
+        expected_lengths.push((
+            format!("reduction steps in query {query}"),
+            round.steps.len(),
+            fri_params.reduction_arity_bits.len(),
+        ));
+        let mut step_bits = lde_bits;
+        for (step, (query_step, &arity_bits)) in round
+            .steps
+            .iter()
+            .zip(fri_params.reduction_arity_bits.iter())
+            .enumerate()
+        {
+            step_bits = step_bits.saturating_sub(arity_bits);
+            expected_lengths.push((
+                format!("evaluations of reduction step {step} in query {query}"),
+                query_step.evals.len(),
+                1 << arity_bits,
+            ));
+            expected_lengths.push((
+                format!("siblings of reduction step {step} in query {query}"),
+                query_step.merkle_proof.siblings.len(),
+                step_bits.saturating_sub(fri_params.config.cap_height),
+            ));
+        }
+    }
+    for (name, len, expected) in expected_lengths {
+        if len != expected {
+            return Err(VerifierError::InvalidProof(format!(
+                "expected {expected} {name}, got {len}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Verifies `proof` against the given challenges, like
+/// `PlonkVerifierChip::verify_proof_with_challenges`.
+pub fn verify_proof_with_challenges<F, H, const D: usize>(
+    proof: &ProofValues<F, D>,
+    public_inputs_hash: &HashOut<GoldilocksField>,
+    challenges: &ProofChallenges<D>,
+    vk: &VerificationKeyValues<F>,
+    common_data: &CommonData<F, D>,
+) -> Result<(), VerifierError>
+where
+    F: PrimeField,
+    H: Hasher<GoldilocksField, Hash = HashOut<GoldilocksField>>,
+    GoldilocksField: Extendable<D>,
+{
+    let openings = &proof.openings;
+    let zeta = challenges.plonk_zeta;
+    let zeta_pow_deg = zeta.exp_power_of_2(common_data.degree_bits());
+    let vanishing_poly_zeta = eval_vanishing_poly(
+        common_data,
+        zeta,
+        zeta_pow_deg,
+        &to_extensions(&openings.constants),
+        &to_extensions(&openings.wires),
+        public_inputs_hash,
+        &to_extensions(&openings.plonk_zs),
+        &to_extensions(&openings.plonk_zs_next),
+        &to_extensions(&openings.lookup_zs),
+        &to_extensions(&openings.lookup_zs_next),
+        &to_extensions(&openings.partial_products),
+        &to_extensions(&openings.plonk_sigmas),
+        &challenges.plonk_betas,
+        &challenges.plonk_gammas,
+        &challenges.plonk_alphas,
+        &challenges.plonk_deltas,
+    )?;
+    let quotient_polys_zeta = to_extensions(&openings.quotient_polys);
+    let z_h_zeta = zeta_pow_deg - Ext::<D>::ONE;
+    for (i, chunk) in quotient_polys_zeta
+        .chunks(common_data.quotient_degree_factor)
+        .enumerate()
+    {
+        if vanishing_poly_zeta[i] != z_h_zeta * reduce_native(zeta_pow_deg, chunk) {
+            return Err(VerifierError::Rejected(FailedCheck::QuotientPolynomial {
+                challenge: i,
+            }));
+        }
+    }
+
+    let merkle_caps = [
+        &vk.constants_sigmas_cap,
+        &proof.wires_cap,
+        &proof.plonk_zs_partial_products_cap,
+        &proof.quotient_polys_cap,
+    ];
+    let g = GoldilocksField::MULTIPLICATIVE_GROUP_GENERATOR
+        .exp_u64(GoldilocksField::NEG_ONE.to_canonical_u64() / (1 << common_data.degree_bits()));
+    let zeta_next = zeta.scalar_mul(g);
+    // The openings at `zeta` and at `g * zeta`, in the order of `FriInstanceInfo`.
+    let fri_openings = [
+        [
+            openings.constants.as_slice(),
+            openings.plonk_sigmas.as_slice(),
+            openings.wires.as_slice(),
+            openings.plonk_zs.as_slice(),
+            openings.partial_products.as_slice(),
+            openings.quotient_polys.as_slice(),
+            openings.lookup_zs.as_slice(),
+        ]
+        .concat(),
+        [
+            openings.plonk_zs_next.as_slice(),
+            openings.lookup_zs_next.as_slice(),
+        ]
+        .concat(),
+    ]
+    .map(|values| to_extensions(&values));
+    verify_fri_proof::<F, H, D>(
+        &merkle_caps,
+        &challenges.fri_challenges,
+        [zeta, zeta_next],
+        &fri_openings,
+        &proof.opening_proof,
+        common_data,
+    )
+    .map_err(VerifierError::Rejected)
+}
+
+/// Verifies natively a proof of `Bn254PoseidonGoldilocksConfig`, returning
+/// `VerifierError::Rejected` with the failed check if it is invalid.
+pub fn verify_proof<F: PrimeField, const D: usize>(
+    proof: &ProofValues<F, D>,
+    public_inputs: &[GoldilocksField],
+    vk: &VerificationKeyValues<F>,
+    common_data: &CommonData<F, D>,
+) -> Result<(), VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+{
+    verify_proof_with_hasher::<F, Bn254PoseidonHash, D>(proof, public_inputs, vk, common_data)
+}
+
+/// Verifies natively a proof whose Merkle trees and challenger use the hasher `H`.
+pub fn verify_proof_with_hasher<F, H, const D: usize>(
+    proof: &ProofValues<F, D>,
+    public_inputs: &[GoldilocksField],
+    vk: &VerificationKeyValues<F>,
+    common_data: &CommonData<F, D>,
+) -> Result<(), VerifierError>
+where
+    F: PrimeField,
+    H: Hasher<GoldilocksField, Hash = HashOut<GoldilocksField>>,
+    GoldilocksField: Extendable<D>,
+{
+    check_proof_shape(proof, public_inputs, vk, common_data)?;
+    let public_inputs_hash = PoseidonHash::hash_no_pad(public_inputs);
+    let challenges = get_challenges::<F, H, D>(proof, &public_inputs_hash, vk, common_data);
+    verify_proof_with_challenges::<F, H, D>(
+        proof,
+        &public_inputs_hash,
+        &challenges,
+        vk,
+        common_data,
+    )
+}
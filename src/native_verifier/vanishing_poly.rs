@@ -0,0 +1,360 @@
+use core::iter;
+
+use halo2_proofs::halo2curves::ff::PrimeField;
+use itertools::Itertools;
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField, types::Field},
+    hash::hash_types::HashOut,
+    plonk::vars::EvaluationVars,
+};
+
+use crate::{
+    chip::plonk::{
+        gates::{
+            lookup::LookupGateConstrainer, lookup_table::LookupTableGateConstrainer, reduce_native,
+            registry::GateRegistryError,
+        },
+        lookup::{LookupChallenges, LookupSelectors, NUM_COINS_LOOKUP},
+    },
+    types::common_data::CommonData,
+};
+
+use super::Ext;
+
+/// Evaluates the vanishing polynomial at `x` for each of the `alphas`, like
+/// `PlonkVerifierChip::eval_vanishing_poly`.
+pub fn eval_vanishing_poly<F: PrimeField, const D: usize>(
+    common_data: &CommonData<F, D>,
+    x: Ext<D>,
+    x_pow_deg: Ext<D>,
+    local_constants: &[Ext<D>],
+    local_wires: &[Ext<D>],
+    public_inputs_hash: &HashOut<GoldilocksField>,
+    local_zs: &[Ext<D>],
+    next_zs: &[Ext<D>],
+    local_lookup_zs: &[Ext<D>],
+    next_lookup_zs: &[Ext<D>],
+    partial_products: &[Ext<D>],
+    s_sigmas: &[Ext<D>],
+    betas: &[GoldilocksField],
+    gammas: &[GoldilocksField],
+    alphas: &[GoldilocksField],
+    deltas: &[GoldilocksField],
+) -> Result<Vec<Ext<D>>, GateRegistryError>
+where
+    GoldilocksField: Extendable<D>,
+{
+    let max_degree = common_data.quotient_degree_factor;
+    let num_prods = common_data.num_partial_products;
+
+    let constraint_terms = eval_gate_constraints(
+        common_data,
+        local_constants,
+        local_wires,
+        public_inputs_hash,
+    )?;
+
+    let num_selectors = common_data.selectors_info.num_selectors();
+    let lookup_selectors =
+        &local_constants[num_selectors..num_selectors + common_data.num_lookup_selectors];
+
+    // The L_0(x) (Z(x) - 1) vanishing terms.
+    let mut vanishing_z_1_terms = Vec::new();
+    // The terms checking the lookup constraints, if any.
+    let mut vanishing_all_lookup_terms = Vec::new();
+    // The terms checking the partial products.
+    let mut vanishing_partial_products_terms = Vec::new();
+
+    // L_0(x) = (x^n - 1) / (n * (x - 1))
+    let n = Ext::<D>::from_canonical_usize(common_data.degree());
+    let l_0_x = (x_pow_deg - Ext::<D>::ONE) / (n * (x - Ext::<D>::ONE));
+
+    let s_ids = common_data.k_is[..common_data.config.num_routed_wires]
+        .iter()
+        .map(|&k| x * Ext::<D>::from(k))
+        .collect_vec();
+
+    for i in 0..common_data.config.num_challenges {
+        let z_x = local_zs[i];
+        let z_gx = next_zs[i];
+
+        vanishing_z_1_terms.push(l_0_x * z_x - l_0_x);
+
+        if common_data.has_lookup() {
+            let lookup_polys_range =
+                common_data.num_lookup_polys * i..common_data.num_lookup_polys * (i + 1);
+            vanishing_all_lookup_terms.extend(check_lookup_constraints(
+                common_data,
+                local_wires,
+                &local_lookup_zs[lookup_polys_range.clone()],
+                &next_lookup_zs[lookup_polys_range],
+                lookup_selectors,
+                &deltas[NUM_COINS_LOOKUP * i..NUM_COINS_LOOKUP * (i + 1)],
+            ));
+        }
+
+        let beta = Ext::<D>::from(betas[i]);
+        let gamma = Ext::<D>::from(gammas[i]);
+        let numerator_values = (0..common_data.config.num_routed_wires)
+            .map(|j| beta * s_ids[j] + local_wires[j] + gamma)
+            .collect_vec();
+        let denominator_values = (0..common_data.config.num_routed_wires)
+            .map(|j| beta * s_sigmas[j] + local_wires[j] + gamma)
+            .collect_vec();
+
+        // The partial products considered for this iteration of `i`.
+        let current_partial_products = &partial_products[i * num_prods..(i + 1) * num_prods];
+        vanishing_partial_products_terms.extend(check_partial_products(
+            &numerator_values,
+            &denominator_values,
+            current_partial_products,
+            z_x,
+            z_gx,
+            max_degree,
+        ));
+    }
+
+    let vanishing_terms = [
+        vanishing_z_1_terms,
+        vanishing_partial_products_terms,
+        vanishing_all_lookup_terms,
+        constraint_terms,
+    ]
+    .concat();
+
+    Ok(alphas
+        .iter()
+        .map(|&alpha| reduce_native(Ext::<D>::from(alpha), &vanishing_terms))
+        .collect())
+}
+
+fn eval_gate_constraints<F: PrimeField, const D: usize>(
+    common_data: &CommonData<F, D>,
+    local_constants: &[Ext<D>],
+    local_wires: &[Ext<D>],
+    public_inputs_hash: &HashOut<GoldilocksField>,
+) -> Result<Vec<Ext<D>>, GateRegistryError>
+where
+    GoldilocksField: Extendable<D>,
+{
+    let vars = EvaluationVars {
+        local_constants,
+        local_wires,
+        public_inputs_hash,
+    };
+    let mut all_gate_constraints = vec![Ext::<D>::ZERO; common_data.num_gate_constraints];
+    for (i, gate) in common_data.gates.iter().enumerate() {
+        let selector_index = common_data.selectors_info.selector_indices[i];
+        gate.0.eval_filtered_native(
+            vars,
+            i,
+            selector_index,
+            common_data.selectors_info.groups[selector_index].clone(),
+            common_data.selectors_info.num_selectors(),
+            common_data.num_lookup_selectors,
+            &mut all_gate_constraints,
+        )?;
+    }
+    Ok(all_gate_constraints)
+}
+
+// \prod(g_i'(x))\phi_1(x) - \prod(f_i'(x))Z(x)
+// ..
+// \prod(g_i'(x))Z(gx) - \prod(f_i'(x))\phi_s(x)
+fn check_partial_products<const D: usize>(
+    numerators: &[Ext<D>],
+    denominators: &[Ext<D>],
+    partials: &[Ext<D>],
+    z_x: Ext<D>,
+    z_gx: Ext<D>,
+    max_degree: usize,
+) -> Vec<Ext<D>>
+where
+    GoldilocksField: Extendable<D>,
+{
+    let product_accs = iter::once(z_x)
+        .chain(partials.iter().copied())
+        .chain(iter::once(z_gx));
+    numerators
+        .chunks(max_degree)
+        .zip_eq(denominators.chunks(max_degree))
+        .zip_eq(product_accs.tuple_windows())
+        .map(|((nume_chunk, denom_chunk), (prev_acc, next_acc))| {
+            let nume_product = nume_chunk.iter().copied().product::<Ext<D>>();
+            let denom_product = denom_chunk.iter().copied().product::<Ext<D>>();
+            prev_acc * nume_product - next_acc * denom_product
+        })
+        .collect()
+}
+
+/// Evaluates the lookup constraints of one challenge round, like
+/// `PlonkVerifierChip::check_lookup_constraints`.
+fn check_lookup_constraints<F: PrimeField, const D: usize>(
+    common_data: &CommonData<F, D>,
+    local_wires: &[Ext<D>],
+    local_lookup_zs: &[Ext<D>],
+    next_lookup_zs: &[Ext<D>],
+    lookup_selectors: &[Ext<D>],
+    deltas: &[GoldilocksField],
+) -> Vec<Ext<D>>
+where
+    GoldilocksField: Extendable<D>,
+{
+    let num_lu_slots = LookupGateConstrainer::num_slots(&common_data.config);
+    let num_lut_slots = LookupTableGateConstrainer::num_slots(&common_data.config);
+    let lu_degree = common_data.quotient_degree_factor - 1;
+    let num_sldc_polys = local_lookup_zs.len() - 1;
+    let lut_degree = num_lut_slots.div_ceil(num_sldc_polys);
+
+    let mut constraints = Vec::new();
+
+    // RE is the first polynomial stored.
+    let z_re = local_lookup_zs[0];
+    let next_z_re = next_lookup_zs[0];
+
+    // Partial Sums and LDCs are both stored in the remaining SLDC polynomials.
+    let z_x_lookup_sldcs = &local_lookup_zs[1..num_sldc_polys + 1];
+    let z_gx_lookup_sldcs = &next_lookup_zs[1..num_sldc_polys + 1];
+
+    let challenge_a = Ext::<D>::from(deltas[LookupChallenges::ChallengeA as usize]);
+    let challenge_b = Ext::<D>::from(deltas[LookupChallenges::ChallengeB as usize]);
+    let challenge_alpha = Ext::<D>::from(deltas[LookupChallenges::ChallengeAlpha as usize]);
+    let challenge_delta = Ext::<D>::from(deltas[LookupChallenges::ChallengeDelta as usize]);
+
+    // `alpha - (input + a * output)` for the looked pairs, i.e. the pairs of the lookup table.
+    let looked_denominators = (0..num_lut_slots)
+        .map(|s| {
+            challenge_alpha
+                - (challenge_a * local_wires[LookupTableGateConstrainer::wire_ith_looked_out(s)]
+                    + local_wires[LookupTableGateConstrainer::wire_ith_looked_inp(s)])
+        })
+        .collect_vec();
+
+    // `alpha - (input + a * output)` for the looking pairs.
+    let looking_denominators = (0..num_lu_slots)
+        .map(|s| {
+            challenge_alpha
+                - (challenge_a * local_wires[LookupGateConstrainer::wire_ith_looking_out(s)]
+                    + local_wires[LookupGateConstrainer::wire_ith_looking_inp(s)])
+        })
+        .collect_vec();
+
+    // `input + b * output` for the pairs of the lookup table, used to check the RE polynomial.
+    let lookup_combos = (0..num_lut_slots)
+        .map(|s| {
+            challenge_b * local_wires[LookupTableGateConstrainer::wire_ith_looked_out(s)]
+                + local_wires[LookupTableGateConstrainer::wire_ith_looked_inp(s)]
+        })
+        .collect_vec();
+
+    // Check last LDC constraint.
+    constraints.push(
+        lookup_selectors[LookupSelectors::LastLdc as usize] * z_x_lookup_sldcs[num_sldc_polys - 1],
+    );
+
+    // Check initial Sum constraint.
+    constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_x_lookup_sldcs[0]);
+
+    // Check initial RE constraint.
+    constraints.push(lookup_selectors[LookupSelectors::InitSre as usize] * z_re);
+
+    // Check final RE constraints for each different LUT.
+    for r in LookupSelectors::StartEnd as usize..common_data.num_lookup_selectors {
+        let lut = &common_data.luts[r - LookupSelectors::StartEnd as usize];
+        let lut_row_number = lut.len().div_ceil(num_lut_slots);
+        let lut_eval = eval_lut_poly(
+            lut,
+            deltas[LookupChallenges::ChallengeB as usize],
+            deltas[LookupChallenges::ChallengeDelta as usize],
+            num_lut_slots * lut_row_number,
+        );
+        constraints.push(lookup_selectors[r] * (z_re - Ext::<D>::from(lut_eval)));
+    }
+
+    // Check RE row transition constraint.
+    let cur_sum = lookup_combos
+        .iter()
+        .fold(next_z_re, |acc, &combo| acc * challenge_delta + combo);
+    constraints.push(lookup_selectors[LookupSelectors::TransSre as usize] * (z_re - cur_sum));
+
+    for poly in 0..num_sldc_polys {
+        let lut_range =
+            (poly * lut_degree).min(num_lut_slots)..((poly + 1) * lut_degree).min(num_lut_slots);
+        let lu_range =
+            (poly * lu_degree).min(num_lu_slots)..((poly + 1) * lu_degree).min(num_lu_slots);
+
+        let lut_prod = looked_denominators[lut_range.clone()]
+            .iter()
+            .copied()
+            .product::<Ext<D>>();
+        let lu_prod = looking_denominators[lu_range.clone()]
+            .iter()
+            .copied()
+            .product::<Ext<D>>();
+
+        // `\sum_i m_i \prod_{j \neq i} (alpha - looked_combo_j)`.
+        let sum_prod_lut = lut_range
+            .clone()
+            .map(|i| {
+                let prod_i = lut_range
+                    .clone()
+                    .filter(|&j| j != i)
+                    .map(|j| looked_denominators[j])
+                    .product::<Ext<D>>();
+                prod_i * local_wires[LookupTableGateConstrainer::wire_ith_multiplicity(i)]
+            })
+            .sum::<Ext<D>>();
+
+        // `\sum_i \prod_{j \neq i} (alpha - looking_combo_j)`.
+        let sum_prod_lu = lu_range
+            .clone()
+            .map(|i| {
+                lu_range
+                    .clone()
+                    .filter(|&j| j != i)
+                    .map(|j| looking_denominators[j])
+                    .product::<Ext<D>>()
+            })
+            .sum::<Ext<D>>();
+
+        // The previous element is the previous poly of the current row or the last poly of the
+        // next row.
+        let prev = if poly == 0 {
+            z_gx_lookup_sldcs[num_sldc_polys - 1]
+        } else {
+            z_x_lookup_sldcs[poly - 1]
+        };
+        let diff = z_x_lookup_sldcs[poly] - prev;
+
+        // Check Sum row and col transitions.
+        constraints.push(
+            lookup_selectors[LookupSelectors::TransSre as usize] * (lut_prod * diff - sum_prod_lut),
+        );
+
+        // Check LDC row and col transitions.
+        constraints.push(
+            lookup_selectors[LookupSelectors::TransLdc as usize] * (lu_prod * diff + sum_prod_lu),
+        );
+    }
+
+    constraints
+}
+
+/// Evaluates at `delta` the polynomial whose coefficients, from the highest degree down, are
+/// `input_i + b * output_i` for the pairs of `lut`, padded with zeros up to `degree`
+/// coefficients, like `PlonkVerifierChip::eval_lut_poly`.
+fn eval_lut_poly(
+    lut: &[(u16, u16)],
+    b: GoldilocksField,
+    delta: GoldilocksField,
+    degree: usize,
+) -> GoldilocksField {
+    let mut inputs_eval = GoldilocksField::ZERO;
+    let mut outputs_eval = GoldilocksField::ZERO;
+    for i in 0..degree {
+        let (input, output) = lut.get(i).copied().unwrap_or_default();
+        inputs_eval = inputs_eval * delta + GoldilocksField::from_canonical_u16(input);
+        outputs_eval = outputs_eval * delta + GoldilocksField::from_canonical_u16(output);
+    }
+    b * outputs_eval + inputs_eval
+}
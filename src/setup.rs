@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use halo2_proofs::arithmetic::g_to_lagrange;
+use halo2_proofs::halo2curves::bn256::{Bn256, Fq, Fq2, Fr, G1Affine, G2Affine};
+use halo2_proofs::halo2curves::ff::{Field, PrimeField};
+use halo2_proofs::halo2curves::serde::SerdeObject;
+use halo2_proofs::halo2curves::CurveAffine;
+use halo2_proofs::plonk::{Circuit, ProvingKey, VerifyingKey};
+use halo2_proofs::poly::commitment::Params;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2_proofs::SerdeFormat;
+
+use crate::error::VerifierError;
+
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+const PTAU_HEADER_SECTION: u32 = 1;
+const PTAU_TAU_G1_SECTION: u32 = 2;
+const PTAU_TAU_G2_SECTION: u32 = 3;
+
+/// Reads KZG parameters written by [`write_params`] and downsizes them to `2^degree` points
+pub fn read_params(path: impl AsRef<Path>, degree: u32) -> Result<ParamsKZG<Bn256>, VerifierError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let params = ParamsKZG::<Bn256>::read_custom(&mut reader, SerdeFormat::RawBytes)?;
+    downsize(params, degree)
+}
+
+/// Writes KZG parameters in the halo2 raw bytes format
+pub fn write_params(
+    params: &ParamsKZG<Bn256>,
+    path: impl AsRef<Path>,
+) -> Result<(), VerifierError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    params.write_custom(&mut writer, SerdeFormat::RawBytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads KZG parameters of `2^degree` points from a snarkjs `.ptau` file,
+/// e.g. the Hermez files of the perpetual powers of tau ceremony
+pub fn read_ptau(path: impl AsRef<Path>, degree: u32) -> Result<ParamsKZG<Bn256>, VerifierError> {
+    read_ptau_from(BufReader::new(File::open(path)?), degree)
+}
+
+/// Reads KZG parameters of `2^degree` points from a `.ptau` stream
+pub fn read_ptau_from<R: Read + Seek>(
+    mut reader: R,
+    degree: u32,
+) -> Result<ParamsKZG<Bn256>, VerifierError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != PTAU_MAGIC {
+        return Err(VerifierError::InvalidPowersOfTau(
+            "not a ptau file".to_string(),
+        ));
+    }
+    let _version = read_u32(&mut reader)?;
+    let num_sections = read_u32(&mut reader)?;
+    let mut sections = HashMap::new();
+    for _ in 0..num_sections {
+        let section_type = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        let offset = reader.stream_position()?;
+        sections.insert(section_type, (offset, size));
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+    let seek_section = |reader: &mut R, section_type: u32| {
+        let (offset, size) = *sections.get(&section_type).ok_or_else(|| {
+            VerifierError::InvalidPowersOfTau(format!("missing section {section_type}"))
+        })?;
+        reader.seek(SeekFrom::Start(offset))?;
+        Ok::<_, VerifierError>(size)
+    };
+
+    seek_section(&mut reader, PTAU_HEADER_SECTION)?;
+    let n8 = read_u32(&mut reader)?;
+    if n8 != 32 {
+        return Err(VerifierError::InvalidPowersOfTau(format!(
+            "unsupported field element size {n8}"
+        )));
+    }
+    let mut q = [0u8; 32];
+    reader.read_exact(&mut q)?;
+    if q != fq_modulus() {
+        return Err(VerifierError::InvalidPowersOfTau(
+            "the ceremony is not over BN254".to_string(),
+        ));
+    }
+    let power = read_u32(&mut reader)?;
+    if power < degree {
+        return Err(VerifierError::SetupTooSmall {
+            available: power,
+            required: degree,
+        });
+    }
+
+    let n = 1usize << degree;
+    let size = seek_section(&mut reader, PTAU_TAU_G1_SECTION)?;
+    if size < 64 * n as u64 {
+        return Err(VerifierError::InvalidPowersOfTau(
+            "truncated tau G1 section".to_string(),
+        ));
+    }
+    let g = (0..n)
+        .map(|_| read_g1(&mut reader))
+        .collect::<Result<Vec<_>, _>>()?;
+    let size = seek_section(&mut reader, PTAU_TAU_G2_SECTION)?;
+    if size < 2 * 128 {
+        return Err(VerifierError::InvalidPowersOfTau(
+            "truncated tau G2 section".to_string(),
+        ));
+    }
+    let g2 = read_g2(&mut reader)?;
+    let s_g2 = read_g2(&mut reader)?;
+
+    let g_lagrange: Vec<G1Affine> = g_to_lagrange(g.iter().map(|p| p.into()).collect(), degree);
+    // ParamsKZG has no public constructor, so the points go through its own serialization.
+    let mut bytes = degree.to_le_bytes().to_vec();
+    for p in g.iter().chain(g_lagrange.iter()) {
+        p.write_raw(&mut bytes)?;
+    }
+    g2.write_raw(&mut bytes)?;
+    s_g2.write_raw(&mut bytes)?;
+    let params =
+        ParamsKZG::<Bn256>::read_custom(&mut bytes.as_slice(), SerdeFormat::RawBytesUnchecked)?;
+    Ok(params)
+}
+
+/// Reads a proving key of the circuit `C` written by [`write_proving_key`]
+pub fn read_proving_key<C: Circuit<Fr>>(
+    path: impl AsRef<Path>,
+) -> Result<ProvingKey<G1Affine>, VerifierError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    Ok(ProvingKey::read::<_, C>(
+        &mut reader,
+        SerdeFormat::RawBytes,
+    )?)
+}
+
+pub fn write_proving_key(
+    pk: &ProvingKey<G1Affine>,
+    path: impl AsRef<Path>,
+) -> Result<(), VerifierError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    pk.write(&mut writer, SerdeFormat::RawBytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a verifying key of the circuit `C` written by [`write_verifying_key`]
+pub fn read_verifying_key<C: Circuit<Fr>>(
+    path: impl AsRef<Path>,
+) -> Result<VerifyingKey<G1Affine>, VerifierError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    Ok(VerifyingKey::read::<_, C>(
+        &mut reader,
+        SerdeFormat::RawBytes,
+    )?)
+}
+
+pub fn write_verifying_key(
+    vk: &VerifyingKey<G1Affine>,
+    path: impl AsRef<Path>,
+) -> Result<(), VerifierError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    vk.write(&mut writer, SerdeFormat::RawBytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn downsize(mut params: ParamsKZG<Bn256>, degree: u32) -> Result<ParamsKZG<Bn256>, VerifierError> {
+    if params.k() < degree {
+        return Err(VerifierError::SetupTooSmall {
+            available: params.k(),
+            required: degree,
+        });
+    }
+    if params.k() > degree {
+        params.downsize(degree);
+    }
+    Ok(params)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Little endian bytes of the base field modulus
+fn fq_modulus() -> [u8; 32] {
+    let mut q = (-Fq::ONE).to_repr();
+    for byte in q.iter_mut() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            break;
+        }
+    }
+    q
+}
+
+/// `.ptau` files store base field elements in little endian Montgomery form
+fn read_fq(reader: &mut impl Read) -> Result<Fq, VerifierError> {
+    let mut repr = [0u8; 32];
+    reader.read_exact(&mut repr)?;
+    Fq::from_raw_bytes(&repr)
+        .ok_or_else(|| VerifierError::InvalidPowersOfTau("non canonical field element".to_string()))
+}
+
+fn read_g1(reader: &mut impl Read) -> Result<G1Affine, VerifierError> {
+    let x = read_fq(reader)?;
+    let y = read_fq(reader)?;
+    Option::from(G1Affine::from_xy(x, y))
+        .ok_or_else(|| VerifierError::InvalidPowersOfTau("G1 point not on curve".to_string()))
+}
+
+fn read_g2(reader: &mut impl Read) -> Result<G2Affine, VerifierError> {
+    let x = Fq2 {
+        c0: read_fq(reader)?,
+        c1: read_fq(reader)?,
+    };
+    let y = Fq2 {
+        c0: read_fq(reader)?,
+        c1: read_fq(reader)?,
+    };
+    Option::from(G2Affine::from_xy(x, y))
+        .ok_or_else(|| VerifierError::InvalidPowersOfTau("G2 point not on curve".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        halo2curves::{
+            bn256::{Bn256, Fq, Fr, G1Affine, G2Affine},
+            ff::Field,
+            group::{prime::PrimeCurveAffine, Curve},
+            serde::SerdeObject,
+        },
+        plonk::{keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Fixed},
+        poly::{commitment::Params, kzg::commitment::ParamsKZG, Rotation},
+    };
+
+    use super::{
+        fq_modulus, read_params, read_proving_key, read_ptau_from, read_verifying_key,
+        write_params, write_proving_key, write_verifying_key,
+    };
+    use crate::error::VerifierError;
+
+    fn write_fq(bytes: &mut Vec<u8>, x: Fq) {
+        bytes.extend_from_slice(&x.to_raw_bytes());
+    }
+
+    fn write_section(bytes: &mut Vec<u8>, section_type: u32, content: &[u8]) {
+        bytes.extend_from_slice(&section_type.to_le_bytes());
+        bytes.extend_from_slice(&(content.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(content);
+    }
+
+    /// `.ptau` file of `2^power` powers of `tau`
+    fn ptau_bytes(tau: Fr, power: u32) -> Vec<u8> {
+        let mut header = 32u32.to_le_bytes().to_vec();
+        header.extend_from_slice(&fq_modulus());
+        header.extend_from_slice(&power.to_le_bytes());
+        header.extend_from_slice(&power.to_le_bytes());
+
+        let mut tau_g1 = vec![];
+        let mut s = Fr::ONE;
+        for _ in 0..(1 << (power + 1)) - 1 {
+            let p = (G1Affine::generator() * s).to_affine();
+            write_fq(&mut tau_g1, p.x);
+            write_fq(&mut tau_g1, p.y);
+            s *= tau;
+        }
+        let mut tau_g2 = vec![];
+        let mut s = Fr::ONE;
+        for _ in 0..1 << power {
+            let p = (G2Affine::generator() * s).to_affine();
+            for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1] {
+                write_fq(&mut tau_g2, c);
+            }
+            s *= tau;
+        }
+
+        let mut bytes = b"ptau".to_vec();
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        // sections are looked up by type, not by position
+        write_section(&mut bytes, 2, &tau_g1);
+        write_section(&mut bytes, 1, &header);
+        write_section(&mut bytes, 3, &tau_g2);
+        bytes
+    }
+
+    #[test]
+    fn test_read_ptau() {
+        let tau = Fr::from(1234567);
+        let params = read_ptau_from(Cursor::new(ptau_bytes(tau, 4)), 3).unwrap();
+        assert_eq!(params.k(), 3);
+        let mut s = Fr::ONE;
+        for p in params.get_g() {
+            assert_eq!(*p, (G1Affine::generator() * s).to_affine());
+            s *= tau;
+        }
+        assert_eq!(params.g2(), G2Affine::generator());
+        assert_eq!(params.s_g2(), (G2Affine::generator() * tau).to_affine());
+
+        let result = read_ptau_from(Cursor::new(ptau_bytes(tau, 2)), 3);
+        assert!(matches!(
+            result,
+            Err(VerifierError::SetupTooSmall {
+                available: 2,
+                required: 3
+            })
+        ));
+    }
+
+    #[derive(Clone, Default)]
+    struct TestCircuit {
+        a: Value<Fr>,
+    }
+
+    impl Circuit<Fr> for TestCircuit {
+        type Config = (Column<Advice>, Column<Fixed>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let a = meta.advice_column();
+            let q = meta.fixed_column();
+            meta.create_gate("a * a = q", |meta| {
+                let a = meta.query_advice(a, Rotation::cur());
+                let q = meta.query_fixed(q, Rotation::cur());
+                vec![a.clone() * a - q]
+            });
+            (a, q)
+        }
+
+        fn synthesize(
+            &self,
+            (a, q): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "square",
+                |mut region| {
+                    region.assign_advice(|| "a", a, 0, || self.a)?;
+                    region.assign_fixed(|| "q", q, 0, || Value::known(Fr::from(9)))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_keys_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("stark-verifier-setup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let params = ParamsKZG::<Bn256>::setup(6, rand::thread_rng());
+        write_params(&params, dir.join("params")).unwrap();
+        let params = read_params(dir.join("params"), 5).unwrap();
+        assert_eq!(params.k(), 5);
+
+        let circuit = TestCircuit {
+            a: Value::known(Fr::from(3)),
+        };
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk.clone(), &circuit).unwrap();
+        write_verifying_key(&vk, dir.join("vk")).unwrap();
+        write_proving_key(&pk, dir.join("pk")).unwrap();
+        let read_vk = read_verifying_key::<TestCircuit>(dir.join("vk")).unwrap();
+        let read_pk = read_proving_key::<TestCircuit>(dir.join("pk")).unwrap();
+        assert_eq!(read_vk.transcript_repr(), vk.transcript_repr());
+        assert_eq!(
+            read_pk.get_vk().transcript_repr(),
+            pk.get_vk().transcript_repr()
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
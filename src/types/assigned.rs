@@ -20,6 +20,8 @@ pub struct AssignedOpeningSetValues<F: PrimeField, const D: usize> {
     pub plonk_zs_next: Vec<AssignedExtensionFieldValue<F, D>>,
     pub partial_products: Vec<AssignedExtensionFieldValue<F, D>>,
     pub quotient_polys: Vec<AssignedExtensionFieldValue<F, D>>,
+    pub lookup_zs: Vec<AssignedExtensionFieldValue<F, D>>,
+    pub lookup_zs_next: Vec<AssignedExtensionFieldValue<F, D>>,
 }
 
 impl<F: PrimeField, const D: usize> AssignedOpeningSetValues<F, D> {
@@ -32,11 +34,16 @@ impl<F: PrimeField, const D: usize> AssignedOpeningSetValues<F, D> {
                 self.plonk_zs.as_slice(),
                 self.partial_products.as_slice(),
                 self.quotient_polys.as_slice(),
+                self.lookup_zs.as_slice(),
             ]
             .concat(),
         };
         let zeta_next_batch = AssignedFriOpeningBatch {
-            values: self.plonk_zs_next.clone(),
+            values: [
+                self.plonk_zs_next.as_slice(),
+                self.lookup_zs_next.as_slice(),
+            ]
+            .concat(),
         };
         AssignedFriOpenings {
             batches: vec![zeta_batch, zeta_next_batch],
@@ -49,6 +56,9 @@ pub struct AssignedMerkleProofValues<F: PrimeField> {
     pub siblings: Vec<AssignedHashValues<F>>,
 }
 
+/// Number of random elements appended to the leaves of blinded oracles in zero-knowledge proofs.
+pub const SALT_SIZE: usize = 4;
+
 #[derive(Clone)]
 pub struct AssignedFriInitialTreeProofValues<F: PrimeField> {
     pub evals_proofs: Vec<(Vec<AssignedValue<F>>, AssignedMerkleProofValues<F>)>,
@@ -66,7 +76,7 @@ impl<F: PrimeField> AssignedFriInitialTreeProofValues<F> {
 
     fn unsalted_evals(&self, oracle_index: usize, salted: bool) -> &[AssignedValue<F>] {
         let evals = &self.evals_proofs[oracle_index].0;
-        let salt_size = if salted { 4 } else { 0 };
+        let salt_size = if salted { SALT_SIZE } else { 0 };
         &evals[..evals.len() - salt_size]
     }
 }
@@ -136,7 +146,70 @@ pub struct AssignedFriOpeningBatch<F: PrimeField, const D: usize> {
 pub struct AssignedProofChallenges<F: PrimeField, const D: usize> {
     pub plonk_betas: Vec<AssignedValue<F>>,
     pub plonk_gammas: Vec<AssignedValue<F>>,
+    /// Lookup challenges, `NUM_COINS_LOOKUP` per challenge round. Empty without lookups.
+    pub plonk_deltas: Vec<AssignedValue<F>>,
     pub plonk_alphas: Vec<AssignedValue<F>>,
     pub plonk_zeta: AssignedExtensionFieldValue<F, D>,
     pub fri_challenges: AssignedFriChallenges<F, D>,
 }
+
+pub struct AssignedStarkOpeningSetValues<F: PrimeField, const D: usize> {
+    pub local_values: Vec<AssignedExtensionFieldValue<F, D>>,
+    pub next_values: Vec<AssignedExtensionFieldValue<F, D>>,
+    pub permutation_zs: Vec<AssignedExtensionFieldValue<F, D>>,
+    pub permutation_zs_next: Vec<AssignedExtensionFieldValue<F, D>>,
+    pub quotient_polys: Vec<AssignedExtensionFieldValue<F, D>>,
+}
+
+impl<F: PrimeField, const D: usize> AssignedStarkOpeningSetValues<F, D> {
+    pub(crate) fn to_fri_openings(&self) -> AssignedFriOpenings<F, D> {
+        let zeta_batch = AssignedFriOpeningBatch {
+            values: [
+                self.local_values.as_slice(),
+                self.permutation_zs.as_slice(),
+                self.quotient_polys.as_slice(),
+            ]
+            .concat(),
+        };
+        let zeta_next_batch = AssignedFriOpeningBatch {
+            values: [
+                self.next_values.as_slice(),
+                self.permutation_zs_next.as_slice(),
+            ]
+            .concat(),
+        };
+        AssignedFriOpenings {
+            batches: vec![zeta_batch, zeta_next_batch],
+        }
+    }
+}
+
+pub struct AssignedStarkProofValues<F: PrimeField, const D: usize> {
+    pub trace_cap: AssignedMerkleCapValues<F>,
+    pub permutation_zs_cap: Option<AssignedMerkleCapValues<F>>,
+    pub quotient_polys_cap: AssignedMerkleCapValues<F>,
+
+    pub openings: AssignedStarkOpeningSetValues<F, D>,
+    pub opening_proof: AssignedFriProofValues<F, D>,
+}
+
+/// Challenges of a grand product argument, combining columns as `gamma + sum_i beta^i c_i`.
+#[derive(Clone)]
+pub struct AssignedGrandProductChallenge<F: PrimeField> {
+    pub beta: AssignedValue<F>,
+    pub gamma: AssignedValue<F>,
+}
+
+/// One challenge per challenge round.
+#[derive(Clone)]
+pub struct AssignedGrandProductChallengeSet<F: PrimeField> {
+    pub challenges: Vec<AssignedGrandProductChallenge<F>>,
+}
+
+pub struct AssignedStarkProofChallenges<F: PrimeField, const D: usize> {
+    /// Challenges of the permutation argument, `None` if the AIR has no permutation pairs.
+    pub permutation_challenge_sets: Option<Vec<AssignedGrandProductChallengeSet<F>>>,
+    pub stark_alphas: Vec<AssignedValue<F>>,
+    pub stark_zeta: AssignedExtensionFieldValue<F, D>,
+    pub fri_challenges: AssignedFriChallenges<F, D>,
+}
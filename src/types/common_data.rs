@@ -1,12 +1,26 @@
 use std::ops::{Range, RangeFrom};
 
-use crate::{chip::plonk::gates::CustomGateRef, types::fri::FriOracleInfo};
+use crate::{
+    chip::{
+        goldilocks_extension_chip::check_extension_degree,
+        plonk::{
+            gates::{registry::CustomGateRegistry, CustomGateRef},
+            lookup::LookupSelectors,
+        },
+    },
+    error::VerifierError,
+    types::fri::FriOracleInfo,
+};
 
 use super::{fri::FriPolynomialInfo, to_goldilocks};
 use halo2_proofs::halo2curves::ff::PrimeField;
-use plonky2::{field::goldilocks_field::GoldilocksField, plonk::circuit_data::CommonCircuitData};
+use plonky2::{
+    field::{extension::Extendable, goldilocks_field::GoldilocksField},
+    plonk::circuit_data::CommonCircuitData,
+};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct FriConfig {
     /// `rate = 2^{-rate_bits}`.
     pub rate_bits: usize,
@@ -20,7 +34,7 @@ pub struct FriConfig {
     pub num_query_rounds: usize,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CircuitConfig {
     pub num_wires: usize,
     pub num_routed_wires: usize,
@@ -39,7 +53,7 @@ pub struct CircuitConfig {
     pub fri_config: FriConfig,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct FriParams {
     pub config: FriConfig,
     pub hiding: bool,
@@ -53,7 +67,7 @@ impl FriParams {
     }
 }
 
-#[derive(Clone, Default, Debug)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct SelectorsInfo {
     pub selector_indices: Vec<usize>,
     pub groups: Vec<Range<usize>>,
@@ -65,14 +79,16 @@ impl SelectorsInfo {
     }
 }
 
-#[derive(Clone, Default)]
-pub struct CommonData<F: PrimeField> {
+/// Gates are encoded by their `GateDescriptor`, see the `Serialize` impl of `CustomGateRef`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CommonData<F: PrimeField, const D: usize> {
     pub config: CircuitConfig,
 
     pub fri_params: FriParams,
 
     /// The types of gates used in this circuit, along with their prefixes.
-    pub gates: Vec<CustomGateRef<F>>,
+    pub gates: Vec<CustomGateRef<F, D>>,
 
     /// Information on the circuit's selector polynomials.
     pub selectors_info: SelectorsInfo,
@@ -93,6 +109,15 @@ pub struct CommonData<F: PrimeField> {
 
     /// The number of partial products needed to compute the `Z` polynomials.
     pub num_partial_products: usize,
+
+    /// The number of lookup polynomials per challenge, zero if the circuit has no lookups.
+    pub num_lookup_polys: usize,
+
+    /// The number of lookup selectors, stored in the constants after the gate selectors.
+    pub num_lookup_selectors: usize,
+
+    /// The `(input, output)` pairs of each lookup table.
+    pub luts: Vec<Vec<(u16, u16)>>,
 }
 
 /// Holds the Merkle tree index and blinding flag of a set of polynomials used in FRI.
@@ -121,7 +146,7 @@ impl PlonkOracle {
     };
 }
 
-impl<F: PrimeField> CommonData<F> {
+impl<F: PrimeField, const D: usize> CommonData<F, D> {
     pub const fn degree_bits(&self) -> usize {
         self.fri_params.degree_bits
     }
@@ -170,6 +195,20 @@ impl<F: PrimeField> CommonData<F> {
         self.config.num_challenges * (1 + self.num_partial_products)
     }
 
+    /// Range of the lookup polynomials in the `zs_partial_products_commitment`.
+    pub fn lookup_range(&self) -> Range<usize> {
+        self.num_zs_partial_products_polys()
+            ..self.num_zs_partial_products_polys() + self.num_all_lookup_polys()
+    }
+
+    pub fn num_all_lookup_polys(&self) -> usize {
+        self.config.num_challenges * self.num_lookup_polys
+    }
+
+    pub fn has_lookup(&self) -> bool {
+        self.num_lookup_polys != 0
+    }
+
     fn fri_zs_partial_products_polys(&self) -> Vec<FriPolynomialInfo> {
         FriPolynomialInfo::from_range(
             PlonkOracle::ZS_PARTIAL_PRODUCTS.index,
@@ -181,6 +220,15 @@ impl<F: PrimeField> CommonData<F> {
         FriPolynomialInfo::from_range(PlonkOracle::ZS_PARTIAL_PRODUCTS.index, self.zs_range())
     }
 
+    fn fri_lookup_polys(&self) -> Vec<FriPolynomialInfo> {
+        FriPolynomialInfo::from_range(PlonkOracle::ZS_PARTIAL_PRODUCTS.index, self.lookup_range())
+    }
+
+    /// The polynomials opened at `g * zeta`: the `Z`s and the lookup polynomials.
+    pub fn fri_next_batch_polys(&self) -> Vec<FriPolynomialInfo> {
+        [self.fri_zs_polys(), self.fri_lookup_polys()].concat()
+    }
+
     pub(crate) fn num_quotient_polys(&self) -> usize {
         self.config.num_challenges * self.quotient_degree_factor
     }
@@ -195,6 +243,7 @@ impl<F: PrimeField> CommonData<F> {
             self.fri_wire_polys(),
             self.fri_zs_partial_products_polys(),
             self.fri_quotient_polys(),
+            self.fri_lookup_polys(),
         ]
         .concat()
     }
@@ -210,7 +259,7 @@ impl<F: PrimeField> CommonData<F> {
                 blinding: PlonkOracle::WIRES.blinding,
             },
             FriOracleInfo {
-                num_polys: self.num_zs_partial_products_polys(),
+                num_polys: self.num_zs_partial_products_polys() + self.num_all_lookup_polys(),
                 blinding: PlonkOracle::ZS_PARTIAL_PRODUCTS.blinding,
             },
             FriOracleInfo {
@@ -221,9 +270,73 @@ impl<F: PrimeField> CommonData<F> {
     }
 }
 
-impl<F: PrimeField> From<CommonCircuitData<GoldilocksField, 2>> for CommonData<F> {
-    fn from(value: CommonCircuitData<GoldilocksField, 2>) -> Self {
-        Self {
+/// The largest FRI reduction arity, in bits, supported by `FriVerifierChip`.
+pub const MAX_REDUCTION_ARITY_BITS: usize = 4;
+
+/// Checks that every FRI reduction arity is supported by `FriVerifierChip`.
+pub(crate) fn check_reduction_arity_bits(
+    reduction_arity_bits: &[usize],
+) -> Result<(), VerifierError> {
+    if let Some((round, &arity_bits)) = reduction_arity_bits
+        .iter()
+        .enumerate()
+        .find(|(_, &arity_bits)| arity_bits == 0 || arity_bits > MAX_REDUCTION_ARITY_BITS)
+    {
+        return Err(VerifierError::UnsupportedReductionArity { round, arity_bits });
+    }
+    Ok(())
+}
+
+impl<F: PrimeField, const D: usize> CommonData<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    /// Checks that everything but the gates is supported by the verifier circuit. Runs on the
+    /// conversion from plonky2's circuit data and on the decoding of `VerifierInput`.
+    pub fn check(&self) -> Result<(), VerifierError> {
+        check_extension_degree::<D>()?;
+        if self.config.num_challenges == 0 {
+            return Err(VerifierError::InvalidNumChallenges(
+                self.config.num_challenges,
+            ));
+        }
+        if self.config.zero_knowledge != self.fri_params.hiding {
+            return Err(VerifierError::InvalidCommonData(
+                "FRI hiding does not match zero knowledge".to_string(),
+            ));
+        }
+        check_reduction_arity_bits(&self.fri_params.reduction_arity_bits)?;
+        if self.quotient_degree_factor == 0 {
+            return Err(VerifierError::InvalidCommonData(
+                "quotient degree factor is zero".to_string(),
+            ));
+        }
+        if self.selectors_info.selector_indices.len() != self.gates.len() {
+            return Err(VerifierError::InvalidCommonData(format!(
+                "{} selector indices for {} gates",
+                self.selectors_info.selector_indices.len(),
+                self.gates.len()
+            )));
+        }
+        if self.num_lookup_polys != 0
+            && self.num_lookup_selectors != LookupSelectors::StartEnd as usize + self.luts.len()
+        {
+            return Err(VerifierError::InvalidCommonData(format!(
+                "{} lookup selectors for {} lookup tables",
+                self.num_lookup_selectors,
+                self.luts.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Converts plonky2's circuit data, building the gate constrainers with `registry`.
+    pub fn from_circuit_data_with_registry(
+        value: CommonCircuitData<GoldilocksField, D>,
+        registry: &CustomGateRegistry<F, D>,
+    ) -> Result<Self, VerifierError> {
+        let gates = registry.build_all(&value.gates)?;
+        let common_data = Self {
             config: CircuitConfig {
                 num_wires: value.config.num_wires,
                 num_routed_wires: value.config.num_routed_wires,
@@ -240,11 +353,7 @@ impl<F: PrimeField> From<CommonCircuitData<GoldilocksField, 2>> for CommonData<F
                     num_query_rounds: value.config.fri_config.num_query_rounds,
                 },
             },
-            gates: value
-                .gates
-                .iter()
-                .map(|gate| CustomGateRef::from(gate))
-                .collect(),
+            gates,
             fri_params: FriParams {
                 config: FriConfig {
                     rate_bits: value.config.fri_config.rate_bits,
@@ -266,6 +375,73 @@ impl<F: PrimeField> From<CommonCircuitData<GoldilocksField, 2>> for CommonData<F
             num_public_inputs: value.num_public_inputs,
             k_is: value.k_is.iter().map(|e| to_goldilocks(*e)).collect(),
             num_partial_products: value.num_partial_products,
-        }
+            num_lookup_polys: value.num_lookup_polys,
+            num_lookup_selectors: value.num_lookup_selectors,
+            luts: value.luts.iter().map(|lut| lut.to_vec()).collect(),
+        };
+        common_data.check()?;
+        Ok(common_data)
+    }
+}
+
+impl<F: PrimeField, const D: usize> TryFrom<CommonCircuitData<GoldilocksField, D>>
+    for CommonData<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    type Error = VerifierError;
+
+    fn try_from(value: CommonCircuitData<GoldilocksField, D>) -> Result<Self, Self::Error> {
+        Self::from_circuit_data_with_registry(value, &CustomGateRegistry::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommonData;
+    use crate::{
+        bn254_poseidon::plonky2_config::{
+            standard_stark_verifier_config, Bn254PoseidonGoldilocksConfig,
+        },
+        error::VerifierError,
+    };
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use plonky2::{
+        field::goldilocks_field::GoldilocksField, plonk::circuit_builder::CircuitBuilder,
+    };
+
+    #[test]
+    fn test_try_from_common_circuit_data() {
+        let mut builder =
+            CircuitBuilder::<GoldilocksField, 2>::new(standard_stark_verifier_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        let data = builder.build::<Bn254PoseidonGoldilocksConfig>();
+        assert!(CommonData::<Fr, 2>::try_from(data.common.clone()).is_ok());
+
+        let mut common = data.common.clone();
+        common.fri_params.reduction_arity_bits = vec![1, 4, 5];
+        assert_eq!(
+            CommonData::<Fr, 2>::try_from(common).err(),
+            Some(VerifierError::UnsupportedReductionArity {
+                round: 2,
+                arity_bits: 5
+            })
+        );
+
+        let mut common = data.common.clone();
+        common.fri_params.hiding = true;
+        assert!(matches!(
+            CommonData::<Fr, 2>::try_from(common),
+            Err(VerifierError::InvalidCommonData(_))
+        ));
+
+        let mut common = data.common;
+        common.config.num_challenges = 0;
+        assert_eq!(
+            CommonData::<Fr, 2>::try_from(common).err(),
+            Some(VerifierError::InvalidNumChallenges(0))
+        );
     }
 }
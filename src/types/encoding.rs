@@ -0,0 +1,287 @@
+//! Versioned JSON and binary encodings of the value types, so that a plonky2 proof can be wrapped
+//! on another machine than the plonky2 prover, without plonky2's own types.
+
+use halo2_proofs::halo2curves::ff::PrimeField;
+use plonky2::field::{extension::Extendable, goldilocks_field::GoldilocksField};
+use plonky2::plonk::config::GenericConfig;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    error::VerifierError, native_verifier::check_proof_shape, verifier_circuit::ProofTuple,
+};
+
+use super::{
+    common_data::CommonData, proof::ProofValues, to_goldilocks,
+    verification_key::VerificationKeyValues,
+};
+
+/// Version of the encoding, bumped whenever the encoded types change.
+pub const ENCODING_VERSION: u32 = 2;
+
+/// Prefix of the binary encoding, followed by the version.
+const MAGIC: &[u8; 4] = b"SVRF";
+
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct VersionedJson {
+    version: u32,
+    data: serde_json::Value,
+}
+
+/// Everything the verifier circuit needs to know about a plonky2 proof.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct VerifierInput<F: PrimeField, const D: usize> {
+    pub proof: ProofValues<F, D>,
+    pub public_inputs: Vec<GoldilocksField>,
+    pub vk: VerificationKeyValues<F>,
+    pub common_data: CommonData<F, D>,
+}
+
+impl<F: PrimeField, C, const D: usize> TryFrom<ProofTuple<GoldilocksField, C, D>>
+    for VerifierInput<F, D>
+where
+    GoldilocksField: Extendable<D>,
+    C: GenericConfig<D, F = GoldilocksField>,
+{
+    type Error = VerifierError;
+
+    fn try_from(value: ProofTuple<GoldilocksField, C, D>) -> Result<Self, Self::Error> {
+        let (proof_with_public_inputs, vd, cd) = value;
+        Ok(Self {
+            common_data: CommonData::try_from(cd)?,
+            proof: ProofValues::from(proof_with_public_inputs.proof),
+            public_inputs: proof_with_public_inputs
+                .public_inputs
+                .into_iter()
+                .map(to_goldilocks)
+                .collect(),
+            vk: VerificationKeyValues::from(vd),
+        })
+    }
+}
+
+/// Checks run on the decoded values, for the invariants their conversions from plonky2 check.
+pub trait Validate {
+    fn validate(&self) -> Result<(), VerifierError>;
+}
+
+/// Checks the common data, then the shapes of the proof and of the verification key against it.
+impl<F: PrimeField, const D: usize> Validate for VerifierInput<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    fn validate(&self) -> Result<(), VerifierError> {
+        self.common_data.validate()?;
+        check_proof_shape(
+            &self.proof,
+            &self.public_inputs,
+            &self.vk,
+            &self.common_data,
+        )
+    }
+}
+
+impl<F: PrimeField, const D: usize> Validate for CommonData<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    fn validate(&self) -> Result<(), VerifierError> {
+        self.check()
+    }
+}
+
+/// The shape of a verification key depends on the common data, so it is only checked when decoded
+/// as part of a `VerifierInput`.
+impl<F: PrimeField> Validate for VerificationKeyValues<F> {
+    fn validate(&self) -> Result<(), VerifierError> {
+        Ok(())
+    }
+}
+
+/// The shape of a proof depends on the common data, so it is only checked when decoded as part of
+/// a `VerifierInput`.
+impl<F: PrimeField, const D: usize> Validate for ProofValues<F, D> {
+    fn validate(&self) -> Result<(), VerifierError> {
+        Ok(())
+    }
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> Result<String, VerifierError> {
+    serde_json::to_string(&Versioned {
+        version: ENCODING_VERSION,
+        data: value,
+    })
+    .map_err(|e| VerifierError::Encoding(e.to_string()))
+}
+
+pub fn from_json<T: DeserializeOwned + Validate>(json: &str) -> Result<T, VerifierError> {
+    let versioned: VersionedJson =
+        serde_json::from_str(json).map_err(|e| VerifierError::Encoding(e.to_string()))?;
+    check_version(versioned.version)?;
+    let value: T = serde_json::from_value(versioned.data)
+        .map_err(|e| VerifierError::Encoding(e.to_string()))?;
+    value.validate()?;
+    Ok(value)
+}
+
+pub fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, VerifierError> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&ENCODING_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, value)
+        .map_err(|e| VerifierError::Encoding(e.to_string()))?;
+    Ok(bytes)
+}
+
+pub fn from_bytes<T: DeserializeOwned + Validate>(bytes: &[u8]) -> Result<T, VerifierError> {
+    if bytes.len() < 8 || &bytes[..4] != MAGIC {
+        return Err(VerifierError::Encoding("missing header".to_string()));
+    }
+    check_version(u32::from_le_bytes(bytes[4..8].try_into().unwrap()))?;
+    let value: T =
+        bincode::deserialize(&bytes[8..]).map_err(|e| VerifierError::Encoding(e.to_string()))?;
+    value.validate()?;
+    Ok(value)
+}
+
+fn check_version(version: u32) -> Result<(), VerifierError> {
+    if version != ENCODING_VERSION {
+        return Err(VerifierError::UnsupportedEncodingVersion(version));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_bytes, from_json, to_bytes, to_json, VerifierInput, ENCODING_VERSION};
+    use crate::{
+        bn254_poseidon::plonky2_config::{
+            standard_stark_verifier_config, Bn254PoseidonGoldilocksConfig,
+        },
+        error::VerifierError,
+        types::verification_key::VerificationKeyValues,
+    };
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use plonky2::{
+        field::{goldilocks_field::GoldilocksField, types::Field},
+        iop::witness::{PartialWitness, WitnessWrite},
+        plonk::circuit_builder::CircuitBuilder,
+    };
+
+    fn verifier_input() -> VerifierInput<Fr, 2> {
+        let mut builder =
+            CircuitBuilder::<GoldilocksField, 2>::new(standard_stark_verifier_config());
+        let x = builder.add_virtual_public_input();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        let data = builder.build::<Bn254PoseidonGoldilocksConfig>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, GoldilocksField::from_canonical_u64(3));
+        let proof = data.prove(pw).unwrap();
+        VerifierInput::try_from((proof, data.verifier_only, data.common)).unwrap()
+    }
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let input = verifier_input();
+
+        let json = to_json(&input).unwrap();
+        let decoded: VerifierInput<Fr, 2> = from_json(&json).unwrap();
+        assert_eq!(to_json(&decoded).unwrap(), json);
+        assert_eq!(decoded.public_inputs, input.public_inputs);
+        assert_eq!(
+            decoded.common_data.gates.len(),
+            input.common_data.gates.len()
+        );
+        for (decoded, gate) in decoded
+            .common_data
+            .gates
+            .iter()
+            .zip(&input.common_data.gates)
+        {
+            assert_eq!(decoded.0.descriptor(), gate.0.descriptor());
+        }
+
+        let bytes = to_bytes(&input).unwrap();
+        let decoded: VerifierInput<Fr, 2> = from_bytes(&bytes).unwrap();
+        assert_eq!(to_bytes(&decoded).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_decoding_checks_common_data() {
+        let mut input = verifier_input();
+        input.common_data.fri_params.reduction_arity_bits = vec![1, 4, 5];
+        let expected = Some(VerifierError::UnsupportedReductionArity {
+            round: 2,
+            arity_bits: 5,
+        });
+        assert_eq!(
+            from_json::<VerifierInput<Fr, 2>>(&to_json(&input).unwrap()).err(),
+            expected
+        );
+        assert_eq!(
+            from_bytes::<VerifierInput<Fr, 2>>(&to_bytes(&input).unwrap()).err(),
+            expected
+        );
+
+        input.common_data.config.num_challenges = 0;
+        assert_eq!(
+            from_json::<VerifierInput<Fr, 2>>(&to_json(&input).unwrap()).err(),
+            Some(VerifierError::InvalidNumChallenges(0))
+        );
+    }
+
+    #[test]
+    fn test_decoding_checks_proof_shape() {
+        let mut input = verifier_input();
+        input.proof.openings.wires.pop();
+        assert!(matches!(
+            from_json::<VerifierInput<Fr, 2>>(&to_json(&input).unwrap()),
+            Err(VerifierError::InvalidProof(_))
+        ));
+
+        let mut input = verifier_input();
+        input.vk.constants_sigmas_cap.0.pop();
+        assert!(matches!(
+            from_bytes::<VerifierInput<Fr, 2>>(&to_bytes(&input).unwrap()),
+            Err(VerifierError::InvalidProof(_))
+        ));
+
+        let mut input = verifier_input();
+        input.proof.opening_proof.query_round_proofs[0].steps[0]
+            .evals
+            .pop();
+        assert!(matches!(
+            from_json::<VerifierInput<Fr, 2>>(&to_json(&input).unwrap()),
+            Err(VerifierError::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_encoding_version() {
+        let vk = verifier_input().vk;
+        let json = to_json(&vk).unwrap().replacen(
+            &format!("\"version\":{ENCODING_VERSION}"),
+            "\"version\":0",
+            1,
+        );
+        assert_eq!(
+            from_json::<VerificationKeyValues<Fr>>(&json).err(),
+            Some(VerifierError::UnsupportedEncodingVersion(0))
+        );
+
+        let mut bytes = to_bytes(&vk).unwrap();
+        bytes[4] = ENCODING_VERSION as u8 + 1;
+        assert_eq!(
+            from_bytes::<VerificationKeyValues<Fr>>(&bytes).err(),
+            Some(VerifierError::UnsupportedEncodingVersion(
+                ENCODING_VERSION + 1
+            ))
+        );
+    }
+}
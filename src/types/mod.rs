@@ -5,32 +5,59 @@ use halo2_proofs::circuit::Value;
 use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::plonk::Error;
 use halo2wrong_maingate::AssignedValue;
-use plonky2::field::extension::Extendable;
+use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
 use plonky2::{
     field::goldilocks_field::GoldilocksField,
     hash::{hash_types::HashOut, merkle_tree::MerkleCap},
+    plonk::config::{GenericHashOut, Hasher},
 };
+use serde::{Deserialize, Serialize};
 
 use self::assigned::{AssignedExtensionFieldValue, AssignedHashValues, AssignedMerkleCapValues};
 
-use super::bn254_poseidon::plonky2_config::Bn254PoseidonHash;
 use super::chip::goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig};
 use super::chip::native_chip::utils::goldilocks_to_fe;
 
 pub mod assigned;
 pub mod common_data;
+pub mod encoding;
 pub mod fri;
 pub mod proof;
+pub mod stark;
 pub mod verification_key;
 
+/// Serde for `[T; N]` with a const generic `N`, which serde does not support.
+mod array_serde {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize, const N: usize>(
+        value: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(value.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[T; N], D::Error> {
+        let value = Vec::<T>::deserialize(deserializer)?;
+        let len = value.len();
+        value
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(len, &format!("{N} elements").as_str()))
+    }
+}
+
 pub fn to_goldilocks(e: GoldilocksField) -> GoldilocksField {
     GoldilocksField::from_canonical_u64(e.0)
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct HashValues<F: PrimeField> {
     pub elements: [GoldilocksField; 4],
+    #[serde(skip)]
     _marker: PhantomData<F>,
 }
 
@@ -70,10 +97,13 @@ impl<F: PrimeField> HashValues<F> {
     }
 }
 
-impl<F: PrimeField> From<HashOut<GoldilocksField>> for HashValues<F> {
-    fn from(value: HashOut<GoldilocksField>) -> Self {
+impl<F: PrimeField> HashValues<F> {
+    /// Converts the hash of any plonky2 `Hasher` through the 4 elements the challenger observes.
+    pub fn from_hash<H: GenericHashOut<GoldilocksField>>(value: H) -> Self {
         let mut elements = [GoldilocksField::ZERO; 4];
-        for (to, from) in elements.iter_mut().zip(value.elements.iter()) {
+        let value = value.to_vec();
+        assert_eq!(value.len(), elements.len(), "hashes must have 4 elements");
+        for (to, from) in elements.iter_mut().zip(value.iter()) {
             *to = to_goldilocks(*from);
         }
         HashValues {
@@ -83,7 +113,14 @@ impl<F: PrimeField> From<HashOut<GoldilocksField>> for HashValues<F> {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+impl<F: PrimeField> From<HashOut<GoldilocksField>> for HashValues<F> {
+    fn from(value: HashOut<GoldilocksField>) -> Self {
+        HashValues::from_hash(value)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct MerkleCapValues<F: PrimeField>(pub Vec<HashValues<F>>);
 
 impl<F: PrimeField> MerkleCapValues<F> {
@@ -114,17 +151,22 @@ impl<F: PrimeField> MerkleCapValues<F> {
     }
 }
 
-impl<F: PrimeField> From<MerkleCap<GoldilocksField, Bn254PoseidonHash>> for MerkleCapValues<F> {
-    fn from(value: MerkleCap<GoldilocksField, Bn254PoseidonHash>) -> Self {
-        let cap_values = value.0.iter().map(|h| HashValues::from(*h)).collect();
+impl<F: PrimeField, H: Hasher<GoldilocksField>> From<MerkleCap<GoldilocksField, H>>
+    for MerkleCapValues<F>
+{
+    fn from(value: MerkleCap<GoldilocksField, H>) -> Self {
+        let cap_values = value.0.iter().map(|h| HashValues::from_hash(*h)).collect();
         MerkleCapValues(cap_values)
     }
 }
 
 /// Contains a extension field value
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct ExtensionFieldValue<F: PrimeField, const D: usize> {
+    #[serde(with = "array_serde")]
     pub elements: [GoldilocksField; D],
+    #[serde(skip)]
     _marker: PhantomData<F>,
 }
 
@@ -155,8 +197,8 @@ impl<F: PrimeField, const D: usize> ExtensionFieldValue<F, D> {
     }
 }
 
-impl<F: PrimeField> From<[GoldilocksField; 2]> for ExtensionFieldValue<F, 2> {
-    fn from(value: [GoldilocksField; 2]) -> Self {
+impl<F: PrimeField, const D: usize> From<[GoldilocksField; D]> for ExtensionFieldValue<F, D> {
+    fn from(value: [GoldilocksField; D]) -> Self {
         let mut elements = vec![];
         for from in value.iter() {
             elements.push(to_goldilocks(*from));
@@ -168,11 +210,14 @@ impl<F: PrimeField> From<[GoldilocksField; 2]> for ExtensionFieldValue<F, 2> {
     }
 }
 
-pub fn to_extension_field_values<F: PrimeField>(
-    extension_fields: Vec<<GoldilocksField as Extendable<2>>::Extension>,
-) -> Vec<ExtensionFieldValue<F, 2>> {
+pub fn to_extension_field_values<F: PrimeField, const D: usize>(
+    extension_fields: Vec<<GoldilocksField as Extendable<D>>::Extension>,
+) -> Vec<ExtensionFieldValue<F, D>>
+where
+    GoldilocksField: Extendable<D>,
+{
     extension_fields
         .iter()
-        .map(|e| ExtensionFieldValue::from(e.0))
+        .map(|e| ExtensionFieldValue::from(e.to_basefield_array()))
         .collect()
 }
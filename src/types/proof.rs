@@ -1,4 +1,3 @@
-use crate::bn254_poseidon::plonky2_config::{Bn254PoseidonGoldilocksConfig, Bn254PoseidonHash};
 use crate::chip::goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig};
 use crate::chip::native_chip::utils::goldilocks_to_fe;
 
@@ -17,18 +16,21 @@ use halo2_proofs::halo2curves::ff::PrimeField;
 use halo2_proofs::plonk::Error;
 use halo2wrong_maingate::AssignedValue;
 use itertools::Itertools;
-use plonky2::field::extension::quadratic::QuadraticExtension;
+use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::polynomial::PolynomialCoeffs;
 use plonky2::field::types::Field;
 use plonky2::fri::proof::{FriProof, FriQueryRound};
 use plonky2::hash::merkle_proofs::MerkleProof;
+use plonky2::plonk::config::{GenericConfig, Hasher};
 use plonky2::plonk::proof::{OpeningSet, Proof};
 use plonky2::{
     field::goldilocks_field::GoldilocksField,
     fri::proof::{FriInitialTreeProof, FriQueryStep},
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct OpeningSetValues<F: PrimeField, const D: usize> {
     pub constants: Vec<ExtensionFieldValue<F, D>>,
     pub plonk_sigmas: Vec<ExtensionFieldValue<F, D>>,
@@ -37,10 +39,15 @@ pub struct OpeningSetValues<F: PrimeField, const D: usize> {
     pub plonk_zs_next: Vec<ExtensionFieldValue<F, D>>,
     pub partial_products: Vec<ExtensionFieldValue<F, D>>,
     pub quotient_polys: Vec<ExtensionFieldValue<F, D>>,
+    pub lookup_zs: Vec<ExtensionFieldValue<F, D>>,
+    pub lookup_zs_next: Vec<ExtensionFieldValue<F, D>>,
 }
 
-impl<F: PrimeField> From<OpeningSet<GoldilocksField, 2>> for OpeningSetValues<F, 2> {
-    fn from(value: OpeningSet<GoldilocksField, 2>) -> Self {
+impl<F: PrimeField, const D: usize> From<OpeningSet<GoldilocksField, D>> for OpeningSetValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    fn from(value: OpeningSet<GoldilocksField, D>) -> Self {
         Self {
             constants: to_extension_field_values(value.constants),
             plonk_sigmas: to_extension_field_values(value.plonk_sigmas),
@@ -49,6 +56,8 @@ impl<F: PrimeField> From<OpeningSet<GoldilocksField, 2>> for OpeningSetValues<F,
             plonk_zs_next: to_extension_field_values(value.plonk_zs_next),
             partial_products: to_extension_field_values(value.partial_products),
             quotient_polys: to_extension_field_values(value.quotient_polys),
+            lookup_zs: to_extension_field_values(value.lookup_zs),
+            lookup_zs_next: to_extension_field_values(value.lookup_zs_next),
         }
     }
 }
@@ -94,6 +103,16 @@ impl<F: PrimeField, const D: usize> OpeningSetValues<F, D> {
             .iter()
             .map(|q| ExtensionFieldValue::assign(config, ctx, q))
             .collect::<Result<Vec<AssignedExtensionFieldValue<F, D>>, Error>>()?;
+        let lookup_zs = opening_set_values
+            .lookup_zs
+            .iter()
+            .map(|z| ExtensionFieldValue::assign(config, ctx, z))
+            .collect::<Result<Vec<AssignedExtensionFieldValue<F, D>>, Error>>()?;
+        let lookup_zs_next = opening_set_values
+            .lookup_zs_next
+            .iter()
+            .map(|z_next| ExtensionFieldValue::assign(config, ctx, z_next))
+            .collect::<Result<Vec<AssignedExtensionFieldValue<F, D>>, Error>>()?;
         Ok(AssignedOpeningSetValues {
             constants,
             plonk_sigmas,
@@ -102,11 +121,14 @@ impl<F: PrimeField, const D: usize> OpeningSetValues<F, D> {
             plonk_zs_next,
             partial_products,
             quotient_polys,
+            lookup_zs,
+            lookup_zs_next,
         })
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct MerkleProofValues<F: PrimeField> {
     pub siblings: Vec<HashValues<F>>,
 }
@@ -126,26 +148,29 @@ impl<F: PrimeField> MerkleProofValues<F> {
     }
 }
 
-impl<F: PrimeField> From<MerkleProof<GoldilocksField, Bn254PoseidonHash>> for MerkleProofValues<F> {
-    fn from(value: MerkleProof<GoldilocksField, Bn254PoseidonHash>) -> Self {
+impl<F: PrimeField, H: Hasher<GoldilocksField>> From<MerkleProof<GoldilocksField, H>>
+    for MerkleProofValues<F>
+{
+    fn from(value: MerkleProof<GoldilocksField, H>) -> Self {
         let siblings = value
             .siblings
             .iter()
-            .map(|value| HashValues::from(*value))
+            .map(|value| HashValues::from_hash(*value))
             .collect();
         MerkleProofValues { siblings }
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct FriInitialTreeProofValues<F: PrimeField> {
     pub evals_proofs: Vec<(Vec<GoldilocksField>, MerkleProofValues<F>)>,
 }
 
-impl<F: PrimeField> From<FriInitialTreeProof<GoldilocksField, Bn254PoseidonHash>>
+impl<F: PrimeField, H: Hasher<GoldilocksField>> From<FriInitialTreeProof<GoldilocksField, H>>
     for FriInitialTreeProofValues<F>
 {
-    fn from(value: FriInitialTreeProof<GoldilocksField, Bn254PoseidonHash>) -> Self {
+    fn from(value: FriInitialTreeProof<GoldilocksField, H>) -> Self {
         let evals_proofs = value
             .evals_proofs
             .iter()
@@ -162,7 +187,8 @@ impl<F: PrimeField> From<FriInitialTreeProof<GoldilocksField, Bn254PoseidonHash>
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct FriQueryStepValues<F: PrimeField, const D: usize> {
     pub evals: Vec<ExtensionFieldValue<F, D>>,
     pub merkle_proof: MerkleProofValues<F>,
@@ -194,14 +220,16 @@ impl<F: PrimeField, const D: usize> FriQueryStepValues<F, D> {
     }
 }
 
-impl<F: PrimeField> From<FriQueryStep<GoldilocksField, Bn254PoseidonHash, 2>>
-    for FriQueryStepValues<F, 2>
+impl<F: PrimeField, H: Hasher<GoldilocksField>, const D: usize>
+    From<FriQueryStep<GoldilocksField, H, D>> for FriQueryStepValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
 {
-    fn from(value: FriQueryStep<GoldilocksField, Bn254PoseidonHash, 2>) -> Self {
+    fn from(value: FriQueryStep<GoldilocksField, H, D>) -> Self {
         let evals_values = value
             .evals
             .iter()
-            .map(|e| ExtensionFieldValue::from(e.0))
+            .map(|e| ExtensionFieldValue::from(e.to_basefield_array()))
             .collect();
         let merkle_proof_values = MerkleProofValues::from(value.merkle_proof.clone());
         FriQueryStepValues {
@@ -211,16 +239,19 @@ impl<F: PrimeField> From<FriQueryStep<GoldilocksField, Bn254PoseidonHash, 2>>
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct FriQueryRoundValues<F: PrimeField, const D: usize> {
     pub initial_trees_proof: FriInitialTreeProofValues<F>,
     pub steps: Vec<FriQueryStepValues<F, D>>,
 }
 
-impl<F: PrimeField> From<FriQueryRound<GoldilocksField, Bn254PoseidonHash, 2>>
-    for FriQueryRoundValues<F, 2>
+impl<F: PrimeField, H: Hasher<GoldilocksField>, const D: usize>
+    From<FriQueryRound<GoldilocksField, H, D>> for FriQueryRoundValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
 {
-    fn from(value: FriQueryRound<GoldilocksField, Bn254PoseidonHash, 2>) -> Self {
+    fn from(value: FriQueryRound<GoldilocksField, H, D>) -> Self {
         Self {
             initial_trees_proof: FriInitialTreeProofValues::from(value.initial_trees_proof),
             steps: value
@@ -277,20 +308,24 @@ impl<F: PrimeField, const D: usize> FriQueryRoundValues<F, D> {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct PolynomialCoeffsExtValues<F: PrimeField, const D: usize>(
     pub Vec<ExtensionFieldValue<F, D>>,
 );
 
-impl<F: PrimeField> From<PolynomialCoeffs<QuadraticExtension<GoldilocksField>>>
-    for PolynomialCoeffsExtValues<F, 2>
+impl<F: PrimeField, const D: usize>
+    From<PolynomialCoeffs<<GoldilocksField as Extendable<D>>::Extension>>
+    for PolynomialCoeffsExtValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
 {
-    fn from(value: PolynomialCoeffs<QuadraticExtension<GoldilocksField>>) -> Self {
+    fn from(value: PolynomialCoeffs<<GoldilocksField as Extendable<D>>::Extension>) -> Self {
         Self(
             value
                 .coeffs
                 .iter()
-                .map(|coeff| ExtensionFieldValue::from(coeff.0))
+                .map(|coeff| ExtensionFieldValue::from(coeff.to_basefield_array()))
                 .collect_vec(),
         )
     }
@@ -312,7 +347,8 @@ impl<F: PrimeField, const D: usize> PolynomialCoeffsExtValues<F, D> {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct FriProofValues<F: PrimeField, const D: usize> {
     pub commit_phase_merkle_cap_values: Vec<MerkleCapValues<F>>,
     pub query_round_proofs: Vec<FriQueryRoundValues<F, D>>,
@@ -320,8 +356,12 @@ pub struct FriProofValues<F: PrimeField, const D: usize> {
     pub pow_witness: GoldilocksField,
 }
 
-impl<F: PrimeField> From<FriProof<GoldilocksField, Bn254PoseidonHash, 2>> for FriProofValues<F, 2> {
-    fn from(value: FriProof<GoldilocksField, Bn254PoseidonHash, 2>) -> Self {
+impl<F: PrimeField, H: Hasher<GoldilocksField>, const D: usize>
+    From<FriProof<GoldilocksField, H, D>> for FriProofValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    fn from(value: FriProof<GoldilocksField, H, D>) -> Self {
         Self {
             commit_phase_merkle_cap_values: value
                 .commit_phase_merkle_caps
@@ -374,7 +414,8 @@ impl<F: PrimeField, const D: usize> FriProofValues<F, D> {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct ProofValues<F: PrimeField, const D: usize> {
     pub wires_cap: MerkleCapValues<F>,
     pub plonk_zs_partial_products_cap: MerkleCapValues<F>,
@@ -384,10 +425,12 @@ pub struct ProofValues<F: PrimeField, const D: usize> {
     pub opening_proof: FriProofValues<F, D>,
 }
 
-impl<F: PrimeField> From<Proof<GoldilocksField, Bn254PoseidonGoldilocksConfig, 2>>
-    for ProofValues<F, 2>
+impl<F: PrimeField, C, const D: usize> From<Proof<GoldilocksField, C, D>> for ProofValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
+    C: GenericConfig<D, F = GoldilocksField>,
 {
-    fn from(value: Proof<GoldilocksField, Bn254PoseidonGoldilocksConfig, 2>) -> Self {
+    fn from(value: Proof<GoldilocksField, C, D>) -> Self {
         Self {
             wires_cap: MerkleCapValues::from(value.wires_cap),
             plonk_zs_partial_products_cap: MerkleCapValues::from(
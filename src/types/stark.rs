@@ -0,0 +1,176 @@
+use crate::chip::goldilocks_chip::GoldilocksChipConfig;
+use crate::chip::goldilocks_extension_chip::check_extension_degree;
+use crate::context::RegionCtx;
+use crate::error::VerifierError;
+use halo2_proofs::halo2curves::ff::PrimeField;
+use halo2_proofs::plonk::Error;
+use plonky2::field::extension::Extendable;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::config::GenericConfig;
+use serde::{Deserialize, Serialize};
+use starky::proof::{StarkOpeningSet, StarkProof};
+
+use super::assigned::{
+    AssignedExtensionFieldValue, AssignedStarkOpeningSetValues, AssignedStarkProofValues,
+};
+use super::common_data::{check_reduction_arity_bits, FriConfig, FriParams};
+use super::proof::FriProofValues;
+use super::{to_extension_field_values, ExtensionFieldValue, MerkleCapValues};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StarkConfig {
+    pub security_bits: usize,
+    /// The number of challenge points to generate, for IOPs that have soundness errors of (roughly)
+    /// `degree / |F|`.
+    pub num_challenges: usize,
+    pub fri_config: FriConfig,
+}
+
+/// Everything but the AIR that `StarkVerifierChip` needs to verify a Starky proof.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StarkCommonData<const D: usize> {
+    pub config: StarkConfig,
+    pub fri_params: FriParams,
+}
+
+impl<const D: usize> StarkCommonData<D> {
+    /// Converts starky's config for a trace of `2^degree_bits` rows.
+    pub fn new(
+        config: &starky::config::StarkConfig,
+        degree_bits: usize,
+    ) -> Result<Self, VerifierError> {
+        check_extension_degree::<D>()?;
+        if config.num_challenges == 0 {
+            return Err(VerifierError::InvalidNumChallenges(config.num_challenges));
+        }
+        let fri_params = config.fri_params(degree_bits);
+        check_reduction_arity_bits(&fri_params.reduction_arity_bits)?;
+        let fri_config = FriConfig {
+            rate_bits: config.fri_config.rate_bits,
+            cap_height: config.fri_config.cap_height,
+            proof_of_work_bits: config.fri_config.proof_of_work_bits,
+            num_query_rounds: config.fri_config.num_query_rounds,
+        };
+        Ok(Self {
+            config: StarkConfig {
+                security_bits: config.security_bits,
+                num_challenges: config.num_challenges,
+                fri_config: fri_config.clone(),
+            },
+            fri_params: FriParams {
+                config: fri_config,
+                hiding: fri_params.hiding,
+                degree_bits: fri_params.degree_bits,
+                reduction_arity_bits: fri_params.reduction_arity_bits,
+            },
+        })
+    }
+
+    pub const fn degree_bits(&self) -> usize {
+        self.fri_params.degree_bits
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct StarkOpeningSetValues<F: PrimeField, const D: usize> {
+    pub local_values: Vec<ExtensionFieldValue<F, D>>,
+    pub next_values: Vec<ExtensionFieldValue<F, D>>,
+    /// Openings of the permutation `Z`s, empty if the AIR has no permutation argument.
+    pub permutation_zs: Vec<ExtensionFieldValue<F, D>>,
+    pub permutation_zs_next: Vec<ExtensionFieldValue<F, D>>,
+    pub quotient_polys: Vec<ExtensionFieldValue<F, D>>,
+}
+
+impl<F: PrimeField, const D: usize> From<StarkOpeningSet<GoldilocksField, D>>
+    for StarkOpeningSetValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
+{
+    fn from(value: StarkOpeningSet<GoldilocksField, D>) -> Self {
+        Self {
+            local_values: to_extension_field_values(value.local_values),
+            next_values: to_extension_field_values(value.next_values),
+            permutation_zs: to_extension_field_values(value.permutation_zs.unwrap_or_default()),
+            permutation_zs_next: to_extension_field_values(
+                value.permutation_zs_next.unwrap_or_default(),
+            ),
+            quotient_polys: to_extension_field_values(value.quotient_polys),
+        }
+    }
+}
+
+impl<F: PrimeField, const D: usize> StarkOpeningSetValues<F, D> {
+    pub fn assign(
+        config: &GoldilocksChipConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        opening_set_values: &Self,
+    ) -> Result<AssignedStarkOpeningSetValues<F, D>, Error> {
+        let mut assign_all = |values: &[ExtensionFieldValue<F, D>]| {
+            values
+                .iter()
+                .map(|v| ExtensionFieldValue::assign(config, ctx, v))
+                .collect::<Result<Vec<AssignedExtensionFieldValue<F, D>>, Error>>()
+        };
+        Ok(AssignedStarkOpeningSetValues {
+            local_values: assign_all(&opening_set_values.local_values)?,
+            next_values: assign_all(&opening_set_values.next_values)?,
+            permutation_zs: assign_all(&opening_set_values.permutation_zs)?,
+            permutation_zs_next: assign_all(&opening_set_values.permutation_zs_next)?,
+            quotient_polys: assign_all(&opening_set_values.quotient_polys)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct StarkProofValues<F: PrimeField, const D: usize> {
+    pub trace_cap: MerkleCapValues<F>,
+    pub permutation_zs_cap: Option<MerkleCapValues<F>>,
+    pub quotient_polys_cap: MerkleCapValues<F>,
+
+    pub openings: StarkOpeningSetValues<F, D>,
+    pub opening_proof: FriProofValues<F, D>,
+}
+
+impl<F: PrimeField, C, const D: usize> From<StarkProof<GoldilocksField, C, D>>
+    for StarkProofValues<F, D>
+where
+    GoldilocksField: Extendable<D>,
+    C: GenericConfig<D, F = GoldilocksField>,
+{
+    fn from(value: StarkProof<GoldilocksField, C, D>) -> Self {
+        Self {
+            trace_cap: MerkleCapValues::from(value.trace_cap),
+            permutation_zs_cap: value.permutation_zs_cap.map(MerkleCapValues::from),
+            quotient_polys_cap: MerkleCapValues::from(value.quotient_polys_cap),
+            openings: StarkOpeningSetValues::from(value.openings),
+            opening_proof: FriProofValues::from(value.opening_proof),
+        }
+    }
+}
+
+impl<F: PrimeField, const D: usize> StarkProofValues<F, D> {
+    pub fn assign(
+        config: &GoldilocksChipConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        proof: &Self,
+    ) -> Result<AssignedStarkProofValues<F, D>, Error> {
+        let trace_cap = MerkleCapValues::assign(config, ctx, &proof.trace_cap)?;
+        let permutation_zs_cap = proof
+            .permutation_zs_cap
+            .as_ref()
+            .map(|cap| MerkleCapValues::assign(config, ctx, cap))
+            .transpose()?;
+        let quotient_polys_cap = MerkleCapValues::assign(config, ctx, &proof.quotient_polys_cap)?;
+        let openings = StarkOpeningSetValues::assign(config, ctx, &proof.openings)?;
+        let opening_proof = FriProofValues::assign(config, ctx, &proof.opening_proof)?;
+        Ok(AssignedStarkProofValues {
+            trace_cap,
+            permutation_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+}
@@ -1,23 +1,52 @@
 use crate::{
-    bn254_poseidon::plonky2_config::Bn254PoseidonGoldilocksConfig,
+    bn254_poseidon::plonky2_config::Bn254PoseidonHash,
     types::{HashValues, MerkleCapValues},
 };
 use halo2_proofs::halo2curves::ff::PrimeField;
-use plonky2::plonk::circuit_data::VerifierOnlyCircuitData;
+use plonky2::{
+    field::goldilocks_field::GoldilocksField,
+    plonk::{
+        circuit_data::VerifierOnlyCircuitData,
+        config::{GenericConfig, Hasher},
+    },
+};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct VerificationKeyValues<F: PrimeField> {
     pub constants_sigmas_cap: MerkleCapValues<F>,
     pub circuit_digest: HashValues<F>,
 }
 
-impl<F: PrimeField> From<VerifierOnlyCircuitData<Bn254PoseidonGoldilocksConfig, 2>>
+impl<F: PrimeField, C, const D: usize> From<VerifierOnlyCircuitData<C, D>>
     for VerificationKeyValues<F>
+where
+    C: GenericConfig<D, F = GoldilocksField>,
 {
-    fn from(value: VerifierOnlyCircuitData<Bn254PoseidonGoldilocksConfig, 2>) -> Self {
+    fn from(value: VerifierOnlyCircuitData<C, D>) -> Self {
         VerificationKeyValues {
             constants_sigmas_cap: MerkleCapValues::from(value.constants_sigmas_cap),
-            circuit_digest: HashValues::from(value.circuit_digest),
+            circuit_digest: HashValues::from_hash(value.circuit_digest),
         }
     }
 }
+
+impl<F: PrimeField> VerificationKeyValues<F> {
+    /// `constants_sigmas_cap` followed by `circuit_digest`.
+    pub fn to_vec(&self) -> Vec<GoldilocksField> {
+        self.constants_sigmas_cap
+            .0
+            .iter()
+            .chain(std::iter::once(&self.circuit_digest))
+            .flat_map(|hash| hash.elements)
+            .collect()
+    }
+
+    /// BN254 Poseidon hash of the whole verification key, whatever the config of the proof.
+    /// Circuits taking the verification key as a witness expose it so that the verifier can check
+    /// which plonky2 circuit the proof is for.
+    pub fn digest(&self) -> HashValues<F> {
+        HashValues::from(Bn254PoseidonHash::hash_no_pad(&self.to_vec()))
+    }
+}
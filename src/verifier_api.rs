@@ -1,128 +1,887 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use super::bn254_poseidon::plonky2_config::Bn254PoseidonGoldilocksConfig;
-use super::types::{
-    common_data::CommonData, proof::ProofValues, verification_key::VerificationKeyValues,
+use super::bn254_poseidon::plonky2_config::{
+    Bn254Poseidon2GoldilocksConfig, Bn254PoseidonGoldilocksConfig,
 };
-use super::verifier_circuit::{ProofTuple, Verifier};
-use crate::chip::native_chip::test_utils::create_proof_checked;
+use super::circuit_size::{measure_circuit, CircuitSize};
+use super::diagnostics::{explain_failures, RowAnnotations};
+use super::error::VerifierError;
+use super::evm::{compile_solidity, Evm};
+use super::native_verifier;
+use super::types::encoding::VerifierInput;
+use super::verifier_circuit::{
+    KeccakCommitmentVerifier, MultiVerifier, ProofTuple, PublicInputsMode, StarkVerifier, Verifier,
+};
+use crate::chip::hasher_chip::{HasherChip, HasherInstructions};
+use crate::chip::keccak_hasher_chip::{KeccakHasherChip, WithKeccakChip};
 use crate::chip::native_chip::utils::goldilocks_to_fe;
-use colored::Colorize;
+use crate::chip::poseidon2_hasher_chip::Poseidon2HasherChip;
+use crate::chip::public_inputs_hasher_chip::PublicInputsHasherChip;
+use crate::chip::stark::{check_stark, StarkConstrainer};
+use crate::types::stark::{StarkCommonData, StarkProofValues};
 use halo2_proofs::dev::MockProver;
-use halo2_proofs::halo2curves::bn256::{Bn256, Fr};
-use halo2_proofs::plonk::{keygen_pk, keygen_vk};
+use halo2_proofs::halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2_proofs::plonk::{
+    create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Error, ProvingKey, VerifyingKey,
+};
 use halo2_proofs::poly::kzg::commitment::ParamsKZG;
-use halo2_solidity_verifier::compile_solidity;
+use halo2_proofs::poly::kzg::multiopen::{ProverSHPLONK, VerifierSHPLONK};
+use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+use halo2_proofs::transcript::TranscriptWriterBuffer;
 use halo2_solidity_verifier::encode_calldata;
 use halo2_solidity_verifier::BatchOpenScheme::Bdfg21;
-use halo2_solidity_verifier::Evm;
+use halo2_solidity_verifier::Keccak256Transcript;
 use halo2_solidity_verifier::SolidityGenerator;
-use plonky2::field::goldilocks_field::GoldilocksField;
-
-fn report_elapsed(now: Instant) {
-    println!(
-        "{}",
-        format!("Took {} milliseconds", now.elapsed().as_millis())
-            .blue()
-            .bold()
-    );
+use log::info;
+use plonky2::field::{extension::Extendable, goldilocks_field::GoldilocksField};
+use plonky2::plonk::config::{GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+use starky::config::StarkConfig;
+use starky::proof::StarkProofWithPublicInputs;
+
+/// Time spent in each step of [`verify_inside_snark`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub native_verification: Duration,
+    pub mock_proving: Duration,
+    pub keygen: Duration,
+    pub solidity_generation: Duration,
+    pub proving: Duration,
+    pub evm_verification: Duration,
 }
 
-/// Public API for generating Halo2 proof for Plonky2 verifier circuit
-/// feed Plonky2 proof, `VerifierOnlyCircuitData`, `CommonCircuitData`
-/// This runs only mock prover for constraint check
-pub fn verify_inside_snark_mock(
-    degree: u32,
-    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, 2>,
-) {
-    let (proof_with_public_inputs, vd, cd) = proof;
-    // proof_with_public_inputs -> ProofValues type
-    let proof = ProofValues::<Fr, 2>::from(proof_with_public_inputs.proof);
-    let instances = proof_with_public_inputs
+/// Result of proving a Plonky2 verifier circuit and verifying the SNARK proof on the EVM
+#[derive(Clone, Debug)]
+pub struct SnarkOutput {
+    /// SNARK proof bytes
+    pub proof: Vec<u8>,
+    /// public instances of the Halo2 circuit
+    pub instances: Vec<Fr>,
+    /// calldata sent to the Solidity verifier
+    pub calldata: Vec<u8>,
+    /// gas used by the Solidity verifier
+    pub gas_used: u64,
+    /// Solidity source of the verifier contract
+    pub verifier_solidity: String,
+    /// Solidity source of the verifying key contract
+    pub vk_solidity: String,
+    pub timings: Timings,
+}
+
+/// Builds the verifier circuit of a Plonky2 proof
+pub fn verifier_from_proof_tuple<const D: usize>(
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+) -> Result<Verifier<D>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    verifier_from_input(VerifierInput::try_from(proof)?)
+}
+
+/// Builds the verifier circuit of a Plonky2 proof decoded with `types::encoding`
+pub fn verifier_from_input<const D: usize>(
+    input: VerifierInput<Fr, D>,
+) -> Result<Verifier<D>, VerifierError> {
+    verifier_with_hasher(input)
+}
+
+/// Builds the verifier circuit of a Plonky2 proof exposing the Keccak-256 of its public inputs,
+/// see `PublicInputsMode::Keccak`
+pub fn keccak_commitment_verifier_from_proof_tuple<const D: usize>(
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+) -> Result<KeccakCommitmentVerifier<D>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    verifier_with_hasher(VerifierInput::try_from(proof)?)?
+        .with_public_inputs_mode(PublicInputsMode::Keccak)
+}
+
+/// Builds the verifier circuit of a Plonky2 proof of `Bn254Poseidon2GoldilocksConfig`
+pub fn poseidon2_verifier_from_proof_tuple<const D: usize>(
+    proof: ProofTuple<GoldilocksField, Bn254Poseidon2GoldilocksConfig, D>,
+) -> Result<Verifier<D, Poseidon2HasherChip<Fr>>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254Poseidon2GoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    verifier_with_hasher(VerifierInput::try_from(proof)?)
+}
+
+/// Builds the verifier circuit of a Plonky2 proof of `KeccakGoldilocksConfig`
+pub fn keccak_verifier_from_proof_tuple(
+    proof: ProofTuple<GoldilocksField, KeccakGoldilocksConfig, 2>,
+) -> Result<Verifier<2, KeccakHasherChip<Fr>>, VerifierError> {
+    verifier_with_hasher(VerifierInput::try_from(proof)?)
+}
+
+/// Builds the verifier circuit of a Plonky2 proof of `PoseidonGoldilocksConfig`
+pub fn poseidon_verifier_from_proof_tuple(
+    proof: ProofTuple<GoldilocksField, PoseidonGoldilocksConfig, 2>,
+) -> Result<Verifier<2, PublicInputsHasherChip<Fr>>, VerifierError> {
+    verifier_with_hasher(VerifierInput::try_from(proof)?)
+}
+
+/// Builds the verifier circuit of a Plonky2 proof decoded with `types::encoding`, whose Merkle
+/// trees and challenger use the hasher constrained by `H`
+pub fn verifier_with_hasher<const D: usize, H: HasherInstructions<Fr>>(
+    input: VerifierInput<Fr, D>,
+) -> Result<Verifier<D, H>, VerifierError> {
+    let instances = input
         .public_inputs
         .iter()
         .map(|e| goldilocks_to_fe(*e))
         .collect::<Vec<Fr>>();
-    // let instances = vec![];
-    let vk = VerificationKeyValues::from(vd.clone());
-    let common_data = CommonData::from(cd);
-    let verifier_circuit = Verifier::new(proof, instances.clone(), vk, common_data);
-    let prover = MockProver::run(degree, &verifier_circuit, vec![instances.clone()]).unwrap();
-    prover.assert_satisfied();
+    Verifier::new(input.proof, instances, input.vk, input.common_data)
+}
+
+/// An operation on the Halo2 circuit verifying a Plonky2 proof, whose hasher depends on the
+/// `PublicInputsMode`, see [`with_wrapper_circuit`]
+pub trait WrapperCircuitFn<const D: usize> {
+    type Output;
+
+    fn call<H: HasherInstructions<Fr>>(
+        self,
+        circuit: Verifier<D, H>,
+    ) -> Result<Self::Output, VerifierError>;
+}
+
+/// Builds the Halo2 circuit verifying `proof` with its public inputs exposed as given by
+/// `public_inputs_mode`, and calls `f` on it
+/// `PublicInputsMode::Keccak` needs the Keccak chip, so its circuit is a
+/// `KeccakCommitmentVerifier`, whose keys differ from the ones of the other modes
+pub fn with_wrapper_circuit<const D: usize, T: WrapperCircuitFn<D>>(
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    public_inputs_mode: PublicInputsMode,
+    f: T,
+) -> Result<T::Output, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    let input = VerifierInput::try_from(proof)?;
+    match public_inputs_mode {
+        PublicInputsMode::Keccak => {
+            let circuit: KeccakCommitmentVerifier<D> =
+                verifier_with_hasher(input)?.with_public_inputs_mode(public_inputs_mode)?;
+            f.call(circuit)
+        }
+        _ => f.call(verifier_from_input(input)?.with_public_inputs_mode(public_inputs_mode)?),
+    }
+}
+
+/// Verifies a Plonky2 proof natively, with the checks constrained by the verifier circuit
+/// This takes milliseconds and returns `VerifierError::Rejected` with the failed check, so it is
+/// worth running before proving
+pub fn verify_natively<const D: usize>(
+    proof: &ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+) -> Result<(), VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    verify_input_natively(&VerifierInput::try_from(proof.clone())?)
+}
+
+/// Verifies natively a Plonky2 proof decoded with `types::encoding`
+pub fn verify_input_natively<const D: usize>(
+    input: &VerifierInput<Fr, D>,
+) -> Result<(), VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+{
+    native_verifier::verify_proof(
+        &input.proof,
+        &input.public_inputs,
+        &input.vk,
+        &input.common_data,
+    )
+}
+
+/// Runs the mock prover on `circuit` and returns the first failure, if any
+pub fn mock_prove<C: Circuit<Fr>>(
+    degree: u32,
+    circuit: &C,
+    instances: Vec<Fr>,
+) -> Result<(), VerifierError> {
+    let prover = MockProver::run(degree, circuit, vec![instances])
+        .map_err(|e| VerifierError::Halo2(e.to_string()))?;
+    prover.verify().map_err(|failures| {
+        VerifierError::ConstraintsNotSatisfied(failures.iter().map(|f| f.to_string()).collect())
+    })
+}
+
+/// Runs the mock prover on `circuit` like [`mock_prove`], describing the failures by the
+/// namespaces of their rows, e.g. "FRI query 7, reduction step 2: folded eval mismatch"
+/// `annotations` reads the namespaces recorded by the synthesis, e.g. `Verifier::row_annotations`
+pub fn mock_prove_with_diagnostics<C: Circuit<Fr>>(
+    degree: u32,
+    circuit: &C,
+    instances: Vec<Fr>,
+    annotations: impl FnOnce(&C) -> RowAnnotations,
+) -> Result<(), VerifierError> {
+    let prover = MockProver::run(degree, circuit, vec![instances])
+        .map_err(|e| VerifierError::Halo2(e.to_string()))?;
+    prover.verify().map_err(|failures| {
+        VerifierError::ConstraintsNotSatisfied(explain_failures(&failures, &annotations(circuit)))
+    })
 }
 
 /// Public API for generating Halo2 proof for Plonky2 verifier circuit
 /// feed Plonky2 proof, `VerifierOnlyCircuitData`, `CommonCircuitData`
-/// This runs real prover and generates valid SNARK proof, generates EVM verifier and runs the verifier
-pub fn verify_inside_snark(
+/// This runs only mock prover for constraint check and returns the public instances
+pub fn verify_inside_snark_mock<const D: usize>(
+    degree: u32,
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+) -> Result<Vec<Fr>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    mock_prove_wrapper(degree, proof, PublicInputsMode::Raw)
+}
+
+/// Public API for checking the constraints of the Halo2 circuit verifying `proof`, with its
+/// public inputs exposed as given by `public_inputs_mode`
+/// This runs only mock prover and returns the public instances
+pub fn mock_prove_wrapper<const D: usize>(
+    degree: u32,
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<Vec<Fr>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    struct MockProveFn(u32);
+
+    impl<const D: usize> WrapperCircuitFn<D> for MockProveFn {
+        type Output = Vec<Fr>;
+
+        fn call<H: HasherInstructions<Fr>>(
+            self,
+            circuit: Verifier<D, H>,
+        ) -> Result<Vec<Fr>, VerifierError> {
+            let instances = circuit.instances();
+            mock_prove_with_diagnostics(
+                self.0,
+                &circuit,
+                instances.clone(),
+                Verifier::row_annotations,
+            )?;
+            Ok(instances)
+        }
+    }
+
+    with_wrapper_circuit(proof, public_inputs_mode, MockProveFn(degree))
+}
+
+/// Public API for generating Halo2 proof for Plonky2 verifier circuit of a `Bn254Poseidon2GoldilocksConfig` proof
+/// This runs only mock prover for constraint check and returns the public instances
+pub fn verify_poseidon2_inside_snark_mock<const D: usize>(
+    degree: u32,
+    proof: ProofTuple<GoldilocksField, Bn254Poseidon2GoldilocksConfig, D>,
+) -> Result<Vec<Fr>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254Poseidon2GoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    let verifier_circuit = poseidon2_verifier_from_proof_tuple(proof)?;
+    let instances = verifier_circuit.instances();
+    mock_prove_with_diagnostics(
+        degree,
+        &verifier_circuit,
+        instances.clone(),
+        Verifier::row_annotations,
+    )?;
+    Ok(instances)
+}
+
+/// Public API for generating Halo2 proof for Plonky2 verifier circuit of a `KeccakGoldilocksConfig` proof
+/// The Keccak lookup table needs `degree` of at least 13
+/// This runs only mock prover for constraint check and returns the public instances
+pub fn verify_keccak_inside_snark_mock(
     degree: u32,
-    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, 2>,
-) {
-    let (proof_with_public_inputs, vd, cd) = proof;
-    let proof = ProofValues::<Fr, 2>::from(proof_with_public_inputs.proof);
-    let instances = proof_with_public_inputs
+    proof: ProofTuple<GoldilocksField, KeccakGoldilocksConfig, 2>,
+) -> Result<Vec<Fr>, VerifierError> {
+    let verifier_circuit = keccak_verifier_from_proof_tuple(proof)?;
+    let instances = verifier_circuit.instances();
+    mock_prove_with_diagnostics(
+        degree,
+        &verifier_circuit,
+        instances.clone(),
+        Verifier::row_annotations,
+    )?;
+    Ok(instances)
+}
+
+/// Public API for generating Halo2 proof for Plonky2 verifier circuit of a `PoseidonGoldilocksConfig` proof
+/// The Goldilocks Poseidon permutation is constrained with the arithmetic of `GoldilocksChip`,
+/// so no BN254 Poseidon layer is needed on top of the proof
+/// This runs only mock prover for constraint check and returns the public instances
+pub fn verify_poseidon_inside_snark_mock(
+    degree: u32,
+    proof: ProofTuple<GoldilocksField, PoseidonGoldilocksConfig, 2>,
+) -> Result<Vec<Fr>, VerifierError> {
+    let verifier_circuit = poseidon_verifier_from_proof_tuple(proof)?;
+    let instances = verifier_circuit.instances();
+    mock_prove_with_diagnostics(
+        degree,
+        &verifier_circuit,
+        instances.clone(),
+        Verifier::row_annotations,
+    )?;
+    Ok(instances)
+}
+
+/// Public API for verifying several Plonky2 proofs in one Halo2 circuit
+/// feed Plonky2 proofs, `VerifierOnlyCircuitData`s, `CommonCircuitData`s, possibly of different circuits
+/// This runs only mock prover for constraint check and returns the public instances
+pub fn verify_multiple_inside_snark_mock<const D: usize>(
+    degree: u32,
+    proofs: Vec<ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<Vec<Fr>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    let inputs = proofs
+        .into_iter()
+        .map(VerifierInput::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    match public_inputs_mode {
+        PublicInputsMode::Keccak => mock_prove_multiple::<D, WithKeccakChip<HasherChip<Fr>>>(
+            degree,
+            inputs,
+            public_inputs_mode,
+        ),
+        _ => mock_prove_multiple::<D, HasherChip<Fr>>(degree, inputs, public_inputs_mode),
+    }
+}
+
+fn mock_prove_multiple<const D: usize, H: HasherInstructions<Fr>>(
+    degree: u32,
+    inputs: Vec<VerifierInput<Fr, D>>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<Vec<Fr>, VerifierError> {
+    let verifiers = inputs
+        .into_iter()
+        .map(verifier_with_hasher::<D, H>)
+        .collect::<Result<_, _>>()?;
+    let circuit = MultiVerifier::new(verifiers, public_inputs_mode)?;
+    let instances = circuit.instances();
+    mock_prove_with_diagnostics(
+        degree,
+        &circuit,
+        instances.clone(),
+        MultiVerifier::row_annotations,
+    )?;
+    Ok(instances)
+}
+
+/// Checks that the openings and caps of `proof` match the AIR `stark`
+fn check_stark_proof_shape<S: StarkConstrainer<Fr, D>, const D: usize>(
+    stark: &S,
+    proof: &StarkProofValues<Fr, D>,
+    num_challenges: usize,
+) -> Result<(), VerifierError> {
+    let openings = &proof.openings;
+    let num_permutation_zs = if stark.uses_permutation_args() {
+        stark.num_permutation_batches(num_challenges)
+    } else {
+        0
+    };
+    let expected_lengths = [
+        ("local values", openings.local_values.len(), stark.columns()),
+        ("next values", openings.next_values.len(), stark.columns()),
+        (
+            "permutation zs",
+            openings.permutation_zs.len(),
+            num_permutation_zs,
+        ),
+        (
+            "next permutation zs",
+            openings.permutation_zs_next.len(),
+            num_permutation_zs,
+        ),
+        (
+            "quotient polys",
+            openings.quotient_polys.len(),
+            stark.num_quotient_polys(num_challenges),
+        ),
+    ];
+    for (name, len, expected) in expected_lengths {
+        if len != expected {
+            return Err(VerifierError::InvalidProof(format!(
+                "expected {expected} {name} openings, got {len}"
+            )));
+        }
+    }
+    if proof.permutation_zs_cap.is_some() != stark.uses_permutation_args() {
+        return Err(VerifierError::InvalidProof(
+            "permutation zs cap does not match the permutation pairs of the stark".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the verifier circuit of a Starky proof of the AIR `stark`
+/// AIRs with cross-table lookups or auxiliary columns are rejected, see `chip::stark::check_stark`
+pub fn stark_verifier_from_proof<S: StarkConstrainer<Fr, D> + Clone, const D: usize>(
+    stark: S,
+    proof: StarkProofWithPublicInputs<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    config: &StarkConfig,
+) -> Result<StarkVerifier<S, D>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    check_stark(&stark)?;
+    let degree_bits = proof.proof.recover_degree_bits(config);
+    let common_data = StarkCommonData::new(config, degree_bits)?;
+    let public_inputs = proof
         .public_inputs
         .iter()
         .map(|e| goldilocks_to_fe(*e))
         .collect::<Vec<Fr>>();
-    let vk = VerificationKeyValues::from(vd.clone());
-    let common_data = CommonData::from(cd);
+    let proof = StarkProofValues::from(proof.proof);
+    check_stark_proof_shape(&stark, &proof, config.num_challenges)?;
+    StarkVerifier::new(stark, proof, public_inputs, common_data)
+}
+
+/// Public API for verifying a Starky proof inside a Halo2 circuit
+/// feed the AIR, its Starky proof and the `StarkConfig` it was proven with
+/// This runs only mock prover for constraint check and returns the public instances
+pub fn verify_stark_inside_snark_mock<S: StarkConstrainer<Fr, D> + Clone, const D: usize>(
+    degree: u32,
+    stark: S,
+    proof: StarkProofWithPublicInputs<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    config: &StarkConfig,
+) -> Result<Vec<Fr>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    let circuit = stark_verifier_from_proof(stark, proof, config)?;
+    let instances = circuit.instances();
+    mock_prove(degree, &circuit, instances.clone())?;
+    Ok(instances)
+}
+
+/// Public API for generating Halo2 proof for Plonky2 verifier circuit
+/// feed Plonky2 proof, `VerifierOnlyCircuitData`, `CommonCircuitData`
+/// This runs real prover and generates valid SNARK proof, generates EVM verifier and runs the verifier
+/// The smallest `degree` fitting the circuit is given by [`verifier_circuit_size`]
+pub fn verify_inside_snark<const D: usize>(
+    degree: u32,
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+) -> Result<SnarkOutput, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    let input = VerifierInput::try_from(proof)?;
+    let mut timings = Timings::default();
+
+    // runs the native verifier, which fails much faster than the provers on invalid proofs
+    let now = Instant::now();
+    verify_input_natively(&input)?;
+    timings.native_verification = now.elapsed();
+    info!(
+        "Native verifier passes, took {:?}",
+        timings.native_verification
+    );
+
+    let circuit = verifier_from_input(input)?;
+    let instances = circuit.instances();
+
     // runs mock prover
-    let circuit = Verifier::new(proof, instances.clone(), vk, common_data);
-    let mock_prover = MockProver::run(degree, &circuit, vec![instances.clone()]).unwrap();
-    mock_prover.assert_satisfied();
-    println!("{}", "Mock prover passes".white().bold());
+    let now = Instant::now();
+    mock_prove_with_diagnostics(
+        degree,
+        &circuit,
+        instances.clone(),
+        Verifier::row_annotations,
+    )?;
+    timings.mock_proving = now.elapsed();
+    info!("Mock prover passes, took {:?}", timings.mock_proving);
+
+    // generates a throwaway setup, see `verify_inside_snark_with_keys` for persistent ones
+    let now = Instant::now();
+    let params = ParamsKZG::<Bn256>::setup(degree, rand::thread_rng());
+    let pk = keygen(&params, &circuit)?;
+    timings.keygen = now.elapsed();
+    info!("Keys generated, took {:?}", timings.keygen);
+
+    let mut output = prove_and_verify_on_evm(&params, &pk, circuit)?;
+    output.timings.native_verification = timings.native_verification;
+    output.timings.mock_proving = timings.mock_proving;
+    output.timings.keygen = timings.keygen;
+    Ok(output)
+}
+
+/// Measures the Halo2 circuit verifying `proof` with `circuit_size::measure_circuit`
+/// The synthesis runs in full, so it takes about as long as the one of the key generation. The `k` of the result is the smallest `degree` accepted by the provers. Like the proving key,
+/// it only depends on the Plonky2 circuit and on `public_inputs_mode`
+pub fn verifier_circuit_size<const D: usize>(
+    proof: &ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<CircuitSize, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    struct MeasureFn;
+
+    impl<const D: usize> WrapperCircuitFn<D> for MeasureFn {
+        type Output = CircuitSize;
+
+        fn call<H: HasherInstructions<Fr>>(
+            self,
+            circuit: Verifier<D, H>,
+        ) -> Result<CircuitSize, VerifierError> {
+            measure_circuit(&circuit, circuit.instances().len())
+        }
+    }
+
+    let now = Instant::now();
+    let size = with_wrapper_circuit(proof.clone(), public_inputs_mode, MeasureFn)?;
+    info!("Circuit measured, took {:?}", now.elapsed());
+    Ok(size)
+}
+
+/// Generates the proving key of the Halo2 circuit verifying `proof`
+/// The key only depends on the Plonky2 circuit and on `public_inputs_mode`, so it can be stored
+/// with `setup::write_proving_key` and reused for every proof of that circuit
+pub fn generate_proving_key<const D: usize>(
+    params: &ParamsKZG<Bn256>,
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<ProvingKey<G1Affine>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    struct KeygenFn<'a>(&'a ParamsKZG<Bn256>);
+
+    impl<const D: usize> WrapperCircuitFn<D> for KeygenFn<'_> {
+        type Output = ProvingKey<G1Affine>;
+
+        fn call<H: HasherInstructions<Fr>>(
+            self,
+            circuit: Verifier<D, H>,
+        ) -> Result<ProvingKey<G1Affine>, VerifierError> {
+            keygen(self.0, &circuit.without_witnesses())
+        }
+    }
+
+    with_wrapper_circuit(proof, public_inputs_mode, KeygenFn(params))
+}
+
+/// Public API for generating Halo2 proof for Plonky2 verifier circuit with an existing setup
+/// `params` and `pk` are typically loaded with the functions of `setup`
+/// This runs real prover, generates EVM verifier and runs the verifier
+pub fn verify_inside_snark_with_keys<const D: usize>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<SnarkOutput, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    struct ProveAndVerifyOnEvmFn<'a>(&'a ParamsKZG<Bn256>, &'a ProvingKey<G1Affine>);
+
+    impl<const D: usize> WrapperCircuitFn<D> for ProveAndVerifyOnEvmFn<'_> {
+        type Output = SnarkOutput;
+
+        fn call<H: HasherInstructions<Fr>>(
+            self,
+            circuit: Verifier<D, H>,
+        ) -> Result<SnarkOutput, VerifierError> {
+            prove_and_verify_on_evm(self.0, self.1, circuit)
+        }
+    }
+
+    with_wrapper_circuit(proof, public_inputs_mode, ProveAndVerifyOnEvmFn(params, pk))
+}
+
+/// Public API for proving the Halo2 circuit verifying `proof`
+/// The SNARK proof uses the Keccak transcript and SHPLONK multiopen of the Solidity verifier,
+/// its public instances are given by `wrapper_instances`
+pub fn prove_wrapper<const D: usize>(
+    pk: &ProvingKey<G1Affine>,
+    params: &ParamsKZG<Bn256>,
+    proof: ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<Vec<u8>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    struct ProveFn<'a>(&'a ParamsKZG<Bn256>, &'a ProvingKey<G1Affine>);
+
+    impl<const D: usize> WrapperCircuitFn<D> for ProveFn<'_> {
+        type Output = Vec<u8>;
+
+        fn call<H: HasherInstructions<Fr>>(
+            self,
+            circuit: Verifier<D, H>,
+        ) -> Result<Vec<u8>, VerifierError> {
+            let instances = circuit.instances();
+            create_snark_proof(self.0, self.1, circuit, &instances)
+        }
+    }
+
+    // an invalid proof would only be noticed after proving
+    verify_natively(&proof)?;
+    with_wrapper_circuit(proof, public_inputs_mode, ProveFn(params, pk))
+}
+
+/// Public API for verifying a SNARK proof generated by `prove_wrapper` natively
+/// Returns `Ok(false)` if the proof is invalid, see `verify_snark_proof`
+pub fn verify_wrapper(
+    vk: &VerifyingKey<G1Affine>,
+    params: &ParamsKZG<Bn256>,
+    proof: &[u8],
+    instances: &[Fr],
+) -> Result<bool, VerifierError> {
+    verify_snark_proof(params, vk, proof, instances)
+}
+
+/// Public instances of the Halo2 circuit verifying `proof`, whose number is the one to give to
+/// the `SolidityGenerator` of its verifier
+/// They are the instances of the circuit itself, so they cannot diverge from the ones it exposes
+pub fn wrapper_instances<const D: usize>(
+    proof: &ProofTuple<GoldilocksField, Bn254PoseidonGoldilocksConfig, D>,
+    public_inputs_mode: PublicInputsMode,
+) -> Result<Vec<Fr>, VerifierError>
+where
+    GoldilocksField: Extendable<D>,
+    Bn254PoseidonGoldilocksConfig: GenericConfig<D, F = GoldilocksField>,
+{
+    struct InstancesFn;
+
+    impl<const D: usize> WrapperCircuitFn<D> for InstancesFn {
+        type Output = Vec<Fr>;
+
+        fn call<H: HasherInstructions<Fr>>(
+            self,
+            circuit: Verifier<D, H>,
+        ) -> Result<Vec<Fr>, VerifierError> {
+            Ok(circuit.instances())
+        }
+    }
+
+    with_wrapper_circuit(proof.clone(), public_inputs_mode, InstancesFn)
+}
+
+/// Generates a SNARK proof of `circuit` with Keccak transcript and SHPLONK multiopen
+pub fn create_snark_proof<C: Circuit<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    instances: &[Fr],
+) -> Result<Vec<u8>, VerifierError> {
+    let mut transcript = Keccak256Transcript::new(Vec::new());
+    create_proof::<_, ProverSHPLONK<_>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[&[instances]],
+        rand::thread_rng(),
+        &mut transcript,
+    )
+    .map_err(|e| VerifierError::Halo2(e.to_string()))?;
+    Ok(transcript.finalize())
+}
+
+/// Verifies a SNARK proof generated by `create_snark_proof`
+/// Returns `Ok(false)` if the proof fails the constraint or the opening checks, and an error on
+/// any other failure, e.g. invalid instances or a malformed proof
+pub fn verify_snark_proof(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    proof: &[u8],
+    instances: &[Fr],
+) -> Result<bool, VerifierError> {
+    let mut transcript = Keccak256Transcript::new(proof);
+    match verify_proof::<_, VerifierSHPLONK<_>, _, _, SingleStrategy<_>>(
+        params,
+        vk,
+        SingleStrategy::new(params),
+        &[&[instances]],
+        &mut transcript,
+    ) {
+        Ok(()) => Ok(true),
+        Err(Error::ConstraintSystemFailure | Error::Opening) => Ok(false),
+        Err(e) => Err(VerifierError::Halo2(e.to_string())),
+    }
+}
+
+fn keygen<C: Circuit<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    circuit: &C,
+) -> Result<ProvingKey<G1Affine>, VerifierError> {
+    let vk = keygen_vk(params, circuit).map_err(|e| VerifierError::Halo2(e.to_string()))?;
+    keygen_pk(params, vk, circuit).map_err(|e| VerifierError::Halo2(e.to_string()))
+}
+
+fn prove_and_verify_on_evm<const D: usize, H: HasherInstructions<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: Verifier<D, H>,
+) -> Result<SnarkOutput, VerifierError> {
+    let instances = circuit.instances();
+    let mut timings = Timings::default();
+
     // generates halo2 solidity verifier
-    let mut rng = rand::thread_rng();
-    let param = ParamsKZG::<Bn256>::setup(degree, &mut rng);
-    let vk = keygen_vk(&param, &circuit).unwrap();
-    let pk = keygen_pk(&param, vk.clone(), &circuit).unwrap();
-    let generator = SolidityGenerator::new(&param, &vk, Bdfg21, instances.len());
-    let (verifier_solidity, vk_solidity) = generator.render_separately().unwrap();
+    let now = Instant::now();
+    let generator = SolidityGenerator::new(params, pk.get_vk(), Bdfg21, instances.len());
+    let (verifier_solidity, vk_solidity) = generator
+        .render_separately()
+        .map_err(|e| VerifierError::SolidityGeneration(e.to_string()))?;
     let mut evm = Evm::default();
-    let verifier_creation_code = compile_solidity(&verifier_solidity);
-    let verifier_address = evm.create(verifier_creation_code);
-    let vk_creation_code = compile_solidity(&vk_solidity);
-    let vk_address = evm.create(vk_creation_code);
+    let verifier_address = evm.create(compile_solidity(&verifier_solidity)?)?;
+    let vk_address = evm.create(compile_solidity(&vk_solidity)?)?;
+    timings.solidity_generation = now.elapsed();
+    info!(
+        "Solidity verifier deployed, took {:?}",
+        timings.solidity_generation
+    );
+
     // generates SNARK proof and runs EVM verifier
-    println!("{}", "Starting finalization phase".red().bold());
     let now = Instant::now();
-    let proof = create_proof_checked(&param, &pk, circuit.clone(), &instances, &mut rng);
-    println!("{}", "SNARK proof generated successfully!".white().bold());
-    report_elapsed(now);
+    let proof = create_snark_proof(params, pk, circuit, &instances)?;
+    if !verify_snark_proof(params, pk.get_vk(), &proof, &instances)? {
+        return Err(VerifierError::Halo2(
+            "generated SNARK proof does not verify".to_string(),
+        ));
+    }
+    timings.proving = now.elapsed();
+    info!("SNARK proof generated, took {:?}", timings.proving);
+
+    let now = Instant::now();
     let calldata = encode_calldata(Some(vk_address.into()), &proof, &instances);
-    let (gas_cost, _output) = evm.call(verifier_address, calldata);
-    println!("Gas cost: {}", gas_cost);
+    let (gas_used, _output) = evm.call(verifier_address, calldata.clone())?;
+    timings.evm_verification = now.elapsed();
+    info!("EVM verification passes, gas used: {gas_used}");
+
+    Ok(SnarkOutput {
+        proof,
+        instances,
+        calldata,
+        gas_used,
+        verifier_solidity,
+        vk_solidity,
+        timings,
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{verify_inside_snark, verify_inside_snark_mock};
+    use super::{
+        generate_proving_key, keccak_commitment_verifier_from_proof_tuple, mock_prove,
+        mock_prove_with_diagnostics, prove_wrapper, verifier_circuit_size, verifier_from_input,
+        verifier_from_proof_tuple, verify_input_natively, verify_inside_snark,
+        verify_inside_snark_mock, verify_keccak_inside_snark_mock,
+        verify_multiple_inside_snark_mock, verify_natively, verify_poseidon2_inside_snark_mock,
+        verify_poseidon_inside_snark_mock, verify_stark_inside_snark_mock, verify_wrapper,
+        wrapper_instances,
+    };
     use crate::{
-        bn254_poseidon::plonky2_config::{
-            standard_inner_stark_verifier_config, standard_stark_verifier_config,
-            Bn254PoseidonGoldilocksConfig,
+        bn254_poseidon::{
+            native::encode_fe,
+            plonky2_config::{
+                standard_inner_stark_verifier_config, standard_stark_verifier_config,
+                Bn254Poseidon2GoldilocksConfig, Bn254PoseidonGoldilocksConfig,
+            },
         },
-        verifier_circuit::ProofTuple,
+        chip::{
+            goldilocks_chip::GoldilocksChipConfig,
+            native_chip::utils::goldilocks_to_fe,
+            stark::{
+                constraint_consumer::ConstraintConsumer, StarkConstrainer, StarkEvaluationVars,
+            },
+        },
+        context::RegionCtx,
+        error::VerifierError,
+        evm::{compile_solidity, Evm},
+        native_verifier::FailedCheck,
+        types::encoding::VerifierInput,
+        verifier_circuit::{ProofTuple, PublicInputsMode, VerificationKeyMode, Verifier},
+    };
+    use halo2_proofs::{
+        dev::MockProver,
+        halo2curves::{
+            bn256::{Bn256, Fr},
+            ff::PrimeField,
+        },
+        plonk::{keygen_vk, Error},
+        poly::kzg::commitment::ParamsKZG,
     };
     use plonky2::{
-        field::{goldilocks_field::GoldilocksField, types::Field},
+        field::{
+            extension::FieldExtension,
+            goldilocks_field::GoldilocksField,
+            packed::PackedField,
+            types::{Field, PrimeField64},
+        },
+        fri::{reduction_strategies::FriReductionStrategy, FriConfig},
+        gates::lookup_table::LookupTable,
         hash::{
             hashing::hash_n_to_hash_no_pad,
             poseidon::{PoseidonHash, PoseidonPermutation},
         },
         iop::witness::{PartialWitness, WitnessWrite},
-        plonk::{circuit_builder::CircuitBuilder, config::PoseidonGoldilocksConfig},
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, Hasher, KeccakGoldilocksConfig, PoseidonGoldilocksConfig},
+        },
+        util::timing::TimingTree,
     };
+    use sha3::{Digest as _, Keccak256};
+    use starky::{
+        config::StarkConfig,
+        constraint_consumer::{
+            ConstraintConsumer as StarkyConstraintConsumer, RecursiveConstraintConsumer,
+        },
+        permutation::PermutationPair,
+        proof::StarkProofWithPublicInputs,
+        prover::prove,
+        stark::Stark,
+        util::trace_rows_to_poly_values,
+        vars::{StarkEvaluationTargets, StarkEvaluationVars as StarkyEvaluationVars},
+        verifier::verify_stark_proof,
+    };
+    use std::sync::Arc;
 
     type F = GoldilocksField;
     const D: usize = 2;
 
     fn generate_proof_tuple() -> ProofTuple<F, Bn254PoseidonGoldilocksConfig, D> {
+        generate_proof_tuple_with_config(
+            standard_inner_stark_verifier_config(),
+            standard_stark_verifier_config(),
+            42,
+        )
+    }
+
+    fn generate_proof_tuple_with_config(
+        inner_config: CircuitConfig,
+        config: CircuitConfig,
+        secret: u64,
+    ) -> ProofTuple<F, Bn254PoseidonGoldilocksConfig, D> {
         let (inner_target, inner_data) = {
             let hash_const =
-                hash_n_to_hash_no_pad::<F, PoseidonPermutation<F>>(&[F::from_canonical_u64(42)]);
-            let mut builder = CircuitBuilder::<F, D>::new(standard_inner_stark_verifier_config());
+                hash_n_to_hash_no_pad::<F, PoseidonPermutation<F>>(&[F::from_canonical_u64(
+                    secret,
+                )]);
+            let mut builder = CircuitBuilder::<F, D>::new(inner_config);
             let target = builder.add_virtual_target();
             let expected_hash = builder.constant_hash(hash_const);
             let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(vec![target]);
@@ -132,7 +891,7 @@ mod tests {
             (target, data)
         };
 
-        let mut builder = CircuitBuilder::<F, D>::new(standard_stark_verifier_config());
+        let mut builder = CircuitBuilder::<F, D>::new(config);
         let proof_t = builder.add_virtual_proof_with_pis(&inner_data.common);
         let vd = builder.constant_verifier_data(&inner_data.verifier_only);
         builder.verify_proof::<PoseidonGoldilocksConfig>(&proof_t, &vd, &inner_data.common);
@@ -141,7 +900,7 @@ mod tests {
 
         let proof = {
             let mut pw = PartialWitness::new();
-            pw.set_target(inner_target, F::from_canonical_usize(42));
+            pw.set_target(inner_target, F::from_canonical_u64(secret));
             inner_data.prove(pw).unwrap()
         };
 
@@ -153,15 +912,634 @@ mod tests {
         proof
     }
 
+    /// A proof of `y = x^2 + x` for `x = 3`, with a single FRI query to keep the verifier small.
+    fn generate_mul_add_proof_tuple<C: GenericConfig<D, F = F>>() -> ProofTuple<F, C, D> {
+        let config = CircuitConfig {
+            fri_config: FriConfig {
+                rate_bits: 3,
+                cap_height: 0,
+                proof_of_work_bits: 0,
+                reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
+                num_query_rounds: 1,
+            },
+            ..CircuitConfig::standard_recursion_config()
+        };
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_public_input();
+        let y = builder.mul_add(x, x, x);
+        builder.register_public_input(y);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let proof = data.prove(pw).unwrap();
+        assert_eq!(proof.public_inputs[1], F::from_canonical_u64(12));
+        (proof, data.verifier_only, data.common)
+    }
+
     #[test]
     fn test_recursive_halo2_mock() {
         let proof = generate_proof_tuple();
-        verify_inside_snark_mock(19, proof);
+        verify_inside_snark_mock(19, proof).unwrap();
+    }
+
+    #[test]
+    fn test_recursive_halo2_mock_wrong_public_inputs() {
+        let (mut proof, vd, cd) = generate_proof_tuple();
+        proof.public_inputs[0] += F::ONE;
+        let result = verify_inside_snark_mock(19, (proof, vd, cd));
+        assert!(matches!(
+            result,
+            Err(VerifierError::ConstraintsNotSatisfied(_))
+        ));
+    }
+
+    #[test]
+    fn test_lookup_halo2_mock() {
+        let mut builder = CircuitBuilder::<F, D>::new(standard_stark_verifier_config());
+        let table: LookupTable = Arc::new((0..16u16).map(|i| (i, i * i)).collect());
+        let lut_index = builder.add_lookup_table_from_pairs(table);
+        let x = builder.add_virtual_public_input();
+        let y = builder.add_lookup_from_index(x, lut_index);
+        builder.register_public_input(y);
+        let data = builder.build::<Bn254PoseidonGoldilocksConfig>();
+        assert!(data.common.num_lookup_polys > 0);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        let proof = data.prove(pw).unwrap();
+        assert_eq!(proof.public_inputs[1], F::from_canonical_u64(9));
+        verify_inside_snark_mock(19, (proof, data.verifier_only, data.common)).unwrap();
+    }
+
+    #[test]
+    fn test_quartic_extension_halo2_mock() {
+        const D: usize = 4;
+        let mut builder = CircuitBuilder::<F, D>::new(standard_stark_verifier_config());
+        let x = builder.add_virtual_public_input();
+        let x_ext = builder.convert_to_ext(x);
+        let y_ext = builder.mul_add_extension(x_ext, x_ext, x_ext);
+        let y = builder.add_virtual_public_input();
+        let y_as_ext = builder.convert_to_ext(y);
+        builder.connect_extension(y_ext, y_as_ext);
+        let data = builder.build::<Bn254PoseidonGoldilocksConfig>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(3));
+        pw.set_target(y, F::from_canonical_u64(12));
+        let proof = data.prove(pw).unwrap();
+        verify_inside_snark_mock(19, (proof, data.verifier_only, data.common)).unwrap();
+    }
+
+    #[test]
+    fn test_keccak_halo2_mock() {
+        let (proof, vd, cd) = generate_mul_add_proof_tuple::<KeccakGoldilocksConfig>();
+        let instances = verify_keccak_inside_snark_mock(20, (proof, vd, cd)).unwrap();
+        assert_eq!(instances[1], Fr::from(12));
+    }
+
+    #[test]
+    fn test_poseidon2_halo2_mock() {
+        let (proof, vd, cd) = generate_mul_add_proof_tuple::<Bn254Poseidon2GoldilocksConfig>();
+        let instances = verify_poseidon2_inside_snark_mock(19, (proof, vd, cd)).unwrap();
+        assert_eq!(instances[1], Fr::from(12));
+    }
+
+    #[test]
+    fn test_goldilocks_poseidon_halo2_mock() {
+        let (proof, vd, cd) = generate_mul_add_proof_tuple::<PoseidonGoldilocksConfig>();
+        let instances = verify_poseidon_inside_snark_mock(20, (proof, vd, cd)).unwrap();
+        assert_eq!(instances[1], Fr::from(12));
+    }
+
+    #[test]
+    fn test_recursive_halo2_mock_high_arity() {
+        for arity_bits in [2, 3, 4] {
+            let standard_config = standard_stark_verifier_config();
+            let config = CircuitConfig {
+                fri_config: FriConfig {
+                    reduction_strategy: FriReductionStrategy::ConstantArityBits(arity_bits, 5),
+                    ..standard_config.fri_config.clone()
+                },
+                ..standard_config
+            };
+            let proof = generate_proof_tuple_with_config(
+                standard_inner_stark_verifier_config(),
+                config,
+                42,
+            );
+            assert!(proof
+                .2
+                .fri_params
+                .reduction_arity_bits
+                .iter()
+                .any(|&bits| bits == arity_bits));
+            verify_inside_snark_mock(19, proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_recursive_halo2_mock_zero_knowledge() {
+        let inner_config = CircuitConfig {
+            zero_knowledge: true,
+            ..standard_inner_stark_verifier_config()
+        };
+        let config = CircuitConfig {
+            zero_knowledge: true,
+            ..standard_stark_verifier_config()
+        };
+        let proof = generate_proof_tuple_with_config(inner_config, config, 42);
+        assert!(proof.2.fri_params.hiding);
+        verify_inside_snark_mock(20, proof).unwrap();
+    }
+
+    #[test]
+    fn test_multi_verifier_mock() {
+        let standard_config = standard_stark_verifier_config();
+        let high_arity_config = CircuitConfig {
+            fri_config: FriConfig {
+                reduction_strategy: FriReductionStrategy::ConstantArityBits(3, 5),
+                ..standard_config.fri_config.clone()
+            },
+            ..standard_config
+        };
+        let proofs = vec![
+            generate_proof_tuple(),
+            generate_proof_tuple_with_config(
+                standard_inner_stark_verifier_config(),
+                high_arity_config,
+                42,
+            ),
+        ];
+        verify_multiple_inside_snark_mock(20, proofs.clone(), PublicInputsMode::Raw).unwrap();
+        verify_multiple_inside_snark_mock(20, proofs.clone(), PublicInputsMode::Digest).unwrap();
+        verify_multiple_inside_snark_mock(20, proofs, PublicInputsMode::Keccak).unwrap();
+    }
+
+    #[test]
+    fn test_public_inputs_modes_mock() {
+        let proof = generate_proof_tuple();
+        let public_inputs = proof.0.public_inputs.clone();
+        assert_eq!(public_inputs.len(), 4);
+        for (mode, num_instances) in [
+            (PublicInputsMode::Raw, 4),
+            (PublicInputsMode::Packed, 2),
+            (PublicInputsMode::Digest, 4),
+        ] {
+            let instances = wrapper_instances(&proof, mode).unwrap();
+            assert_eq!(instances.len(), num_instances);
+            assert_eq!(mode.num_instances(public_inputs.len()), num_instances);
+            let circuit = verifier_from_proof_tuple(proof.clone())
+                .unwrap()
+                .with_public_inputs_mode(mode)
+                .unwrap();
+            assert_eq!(circuit.instances(), instances);
+            mock_prove(19, &circuit, instances.clone()).unwrap();
+
+            let mut wrong_instances = instances;
+            wrong_instances[0] += Fr::from(1);
+            assert!(matches!(
+                mock_prove(19, &circuit, wrong_instances),
+                Err(VerifierError::ConstraintsNotSatisfied(_))
+            ));
+        }
+
+        let packed = wrapper_instances(&proof, PublicInputsMode::Packed).unwrap();
+        assert_eq!(
+            packed[1],
+            encode_fe([
+                public_inputs[3],
+                GoldilocksField::ZERO,
+                GoldilocksField::ZERO
+            ])
+        );
+        let digest = wrapper_instances(&proof, PublicInputsMode::Digest).unwrap();
+        assert_eq!(
+            digest,
+            PoseidonHash::hash_no_pad(&public_inputs)
+                .elements
+                .map(goldilocks_to_fe)
+        );
+    }
+
+    #[test]
+    fn test_keccak_public_inputs_mock() {
+        let proof = generate_proof_tuple();
+        let instances = wrapper_instances(&proof, PublicInputsMode::Keccak).unwrap();
+        assert_eq!(instances.len(), 2);
+        assert_eq!(
+            PublicInputsMode::Keccak.num_instances(proof.0.public_inputs.len()),
+            2
+        );
+        assert!(instances
+            .iter()
+            .all(|x| x.to_repr().as_ref()[16..].iter().all(|&byte| byte == 0)));
+        assert!(matches!(
+            verifier_from_proof_tuple(proof.clone())
+                .unwrap()
+                .with_public_inputs_mode(PublicInputsMode::Keccak),
+            Err(VerifierError::KeccakChipNotConfigured)
+        ));
+        let circuit = keccak_commitment_verifier_from_proof_tuple(proof).unwrap();
+        assert_eq!(circuit.instances(), instances);
+        mock_prove(20, &circuit, instances.clone()).unwrap();
+
+        let mut wrong_instances = instances;
+        wrong_instances[1] += Fr::from(1);
+        assert!(matches!(
+            mock_prove(20, &circuit, wrong_instances),
+            Err(VerifierError::ConstraintsNotSatisfied(_))
+        ));
+    }
+
+    /// Hashes `uint64` public inputs like `PublicInputsMode::Keccak`, as a contract would.
+    const PUBLIC_INPUTS_COMMITMENT_SOLIDITY: &str = r#"
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract PublicInputsCommitment {
+    function commit(uint64[] calldata publicInputs) external pure returns (uint256, uint256) {
+        bytes memory packed;
+        for (uint256 i = 0; i < publicInputs.length; i++) {
+            packed = abi.encodePacked(packed, bytes8(publicInputs[i]));
+        }
+        uint256 digest = uint256(keccak256(packed));
+        return (digest >> 128, uint128(digest));
+    }
+}
+"#;
+
+    #[test]
+    fn test_keccak_public_inputs_evm() {
+        let proof = generate_proof_tuple();
+        let mut evm = Evm::default();
+        let address = evm
+            .create(compile_solidity(PUBLIC_INPUTS_COMMITMENT_SOLIDITY).unwrap())
+            .unwrap();
+
+        // ABI encoding of `commit(uint64[])`
+        let word = |x: u64| [[0; 24], x.to_be_bytes()].concat();
+        let mut calldata = Keccak256::digest(b"commit(uint64[])")[..4].to_vec();
+        calldata.extend(word(32));
+        calldata.extend(word(proof.0.public_inputs.len() as u64));
+        for public_input in proof.0.public_inputs.iter() {
+            calldata.extend(word(public_input.to_canonical_u64()));
+        }
+        let (_, output) = evm.call(address, calldata).unwrap();
+        let commitment = output
+            .chunks(32)
+            .map(|word| Fr::from_u128(u128::from_be_bytes(word[16..].try_into().unwrap())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            commitment,
+            wrapper_instances(&proof, PublicInputsMode::Keccak).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_native_verifier() {
+        let zero_knowledge_proof = generate_proof_tuple_with_config(
+            CircuitConfig {
+                zero_knowledge: true,
+                ..standard_inner_stark_verifier_config()
+            },
+            CircuitConfig {
+                zero_knowledge: true,
+                ..standard_stark_verifier_config()
+            },
+            42,
+        );
+        let lookup_proof = {
+            let mut builder = CircuitBuilder::<F, D>::new(standard_stark_verifier_config());
+            let table: LookupTable = Arc::new((0..16u16).map(|i| (i, i * i)).collect());
+            let lut_index = builder.add_lookup_table_from_pairs(table);
+            let x = builder.add_virtual_public_input();
+            let y = builder.add_lookup_from_index(x, lut_index);
+            builder.register_public_input(y);
+            let data = builder.build::<Bn254PoseidonGoldilocksConfig>();
+            let mut pw = PartialWitness::new();
+            pw.set_target(x, F::from_canonical_u64(5));
+            (data.prove(pw).unwrap(), data.verifier_only, data.common)
+        };
+        for proof in [
+            generate_proof_tuple(),
+            zero_knowledge_proof,
+            lookup_proof,
+            generate_mul_add_proof_tuple::<Bn254PoseidonGoldilocksConfig>(),
+        ] {
+            verify_natively(&proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_native_verifier_failed_checks() {
+        let input = VerifierInput::<Fr, D>::try_from(generate_proof_tuple()).unwrap();
+        verify_input_natively(&input).unwrap();
+        let rejected = |check| Err(VerifierError::Rejected(check));
+
+        let mut wrong_public_inputs = input.clone();
+        wrong_public_inputs.public_inputs[0] += F::ONE;
+        assert_eq!(
+            verify_input_natively(&wrong_public_inputs),
+            rejected(FailedCheck::QuotientPolynomial { challenge: 0 })
+        );
+
+        let mut wrong_opening = input.clone();
+        wrong_opening.proof.openings.wires[0].elements[0] += F::ONE;
+        assert_eq!(
+            verify_input_natively(&wrong_opening),
+            rejected(FailedCheck::QuotientPolynomial { challenge: 0 })
+        );
+
+        let mut wrong_pow_witness = input.clone();
+        wrong_pow_witness.proof.opening_proof.pow_witness += F::ONE;
+        assert_eq!(
+            verify_input_natively(&wrong_pow_witness),
+            rejected(FailedCheck::ProofOfWork)
+        );
+
+        let mut wrong_initial_eval = input.clone();
+        wrong_initial_eval.proof.opening_proof.query_round_proofs[0]
+            .initial_trees_proof
+            .evals_proofs[1]
+            .0[0] += F::ONE;
+        assert_eq!(
+            verify_input_natively(&wrong_initial_eval),
+            rejected(FailedCheck::InitialMerkleProof {
+                query: 0,
+                oracle: 1
+            })
+        );
+
+        let mut wrong_step_evals = input.clone();
+        for eval in wrong_step_evals.proof.opening_proof.query_round_proofs[0].steps[0]
+            .evals
+            .iter_mut()
+        {
+            eval.elements[0] += F::ONE;
+        }
+        assert_eq!(
+            verify_input_natively(&wrong_step_evals),
+            rejected(FailedCheck::FriConsistency { query: 0, step: 0 })
+        );
+
+        let mut wrong_sibling = input.clone();
+        wrong_sibling.proof.opening_proof.query_round_proofs[0].steps[0]
+            .merkle_proof
+            .siblings[0]
+            .elements[0] += F::ONE;
+        assert_eq!(
+            verify_input_natively(&wrong_sibling),
+            rejected(FailedCheck::CommitPhaseMerkleProof { query: 0, step: 0 })
+        );
+
+        let mut missing_opening = input;
+        missing_opening.proof.openings.wires.pop();
+        assert!(matches!(
+            verify_input_natively(&missing_opening),
+            Err(VerifierError::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_verifier_circuit_size() {
+        let proof = generate_proof_tuple();
+        let size = verifier_circuit_size(&proof, PublicInputsMode::Raw).unwrap();
+        assert!(size.rows + size.unusable_rows <= 1 << size.k);
+        assert!(size.rows + size.unusable_rows > 1 << (size.k - 1));
+        assert!(size.lookups > 0 && size.fixed_cells > 0 && size.copies > 0);
+
+        let circuit = verifier_from_proof_tuple(proof).unwrap();
+        assert!(size.rows >= circuit.instances().len());
+        mock_prove(size.k, &circuit, circuit.instances()).unwrap();
+        assert!(matches!(
+            mock_prove(size.k - 1, &circuit, circuit.instances()),
+            Err(VerifierError::Halo2(_))
+        ));
+    }
+
+    #[test]
+    fn test_mock_prover_diagnostics() {
+        let mut input = VerifierInput::<Fr, D>::try_from(generate_proof_tuple()).unwrap();
+        for eval in input.proof.opening_proof.query_round_proofs[0].steps[0]
+            .evals
+            .iter_mut()
+        {
+            eval.elements[0] += F::ONE;
+        }
+        let circuit = verifier_from_input(input).unwrap();
+        let instances = circuit.instances();
+        let Err(VerifierError::ConstraintsNotSatisfied(failures)) =
+            mock_prove_with_diagnostics(19, &circuit, instances, Verifier::row_annotations)
+        else {
+            panic!("the mock prover accepts the tampered proof");
+        };
+        assert!(failures
+            .iter()
+            .any(|failure| failure == "FRI query 0, reduction step 0: folded eval mismatch"));
+    }
+
+    #[test]
+    fn test_verification_key_witness() {
+        let proofs = [42, 43].map(|secret| {
+            generate_proof_tuple_with_config(
+                standard_inner_stark_verifier_config(),
+                standard_stark_verifier_config(),
+                secret,
+            )
+        });
+        assert_ne!(proofs[0].1.circuit_digest, proofs[1].1.circuit_digest);
+        let circuits = proofs.map(|proof| {
+            verifier_from_proof_tuple(proof)
+                .unwrap()
+                .with_vk_mode(VerificationKeyMode::Witness)
+        });
+        for circuit in circuits.iter() {
+            let instances = circuit.instances();
+            let prover = MockProver::run(19, circuit, vec![instances]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // Both plonky2 circuits are verified with the same Halo2 verifying key.
+        let params = ParamsKZG::<Bn256>::setup(19, rand::thread_rng());
+        let vks = circuits
+            .iter()
+            .map(|circuit| keygen_vk(&params, circuit).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vks[0].transcript_repr(), vks[1].transcript_repr());
+    }
+
+    #[test]
+    fn test_wrapper_prove_and_verify() {
+        let proof = generate_proof_tuple();
+        let instances = wrapper_instances(&proof, PublicInputsMode::Raw).unwrap();
+        let params = ParamsKZG::<Bn256>::setup(19, rand::thread_rng());
+        let pk = generate_proving_key(&params, proof.clone(), PublicInputsMode::Raw).unwrap();
+        let snark_proof = prove_wrapper(&pk, &params, proof, PublicInputsMode::Raw).unwrap();
+        assert!(verify_wrapper(pk.get_vk(), &params, &snark_proof, &instances).unwrap());
+
+        let mut wrong_instances = instances;
+        wrong_instances[0] += Fr::from(1);
+        assert!(!verify_wrapper(pk.get_vk(), &params, &snark_proof, &wrong_instances).unwrap());
     }
 
     #[test]
     fn test_recursive_halo2_proof() {
         let proof = generate_proof_tuple();
-        verify_inside_snark(19, proof);
+        let output = verify_inside_snark(19, proof).unwrap();
+        assert!(!output.proof.is_empty());
+        assert!(output.gas_used > 0);
+    }
+
+    /// Starky's example AIR: the first two columns compute a Fibonacci sequence starting from the
+    /// first two public inputs, and reach the last one. The other two columns are permutations
+    /// of one another, to exercise the permutation argument.
+    #[derive(Clone, Copy)]
+    struct FibonacciStark {
+        num_rows: usize,
+    }
+
+    impl FibonacciStark {
+        fn generate_trace(&self, x0: F, x1: F) -> Vec<[F; 4]> {
+            let mut trace_rows = (0..self.num_rows)
+                .scan([x0, x1, F::ZERO, F::ONE], |acc, _| {
+                    let tmp = *acc;
+                    acc[0] = tmp[1];
+                    acc[1] = tmp[0] + tmp[1];
+                    acc[2] = tmp[2] + F::ONE;
+                    acc[3] = tmp[3] + F::ONE;
+                    Some(tmp)
+                })
+                .collect::<Vec<_>>();
+            trace_rows[self.num_rows - 1][3] = F::ZERO;
+            trace_rows
+        }
+    }
+
+    impl Stark<F, D> for FibonacciStark {
+        const COLUMNS: usize = 4;
+        const PUBLIC_INPUTS: usize = 3;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: StarkyEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+            yield_constr: &mut StarkyConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            yield_constr.constraint_first_row(vars.local_values[0] - vars.public_inputs[0]);
+            yield_constr.constraint_first_row(vars.local_values[1] - vars.public_inputs[1]);
+            yield_constr.constraint_last_row(vars.local_values[1] - vars.public_inputs[2]);
+            yield_constr.constraint_transition(vars.next_values[0] - vars.local_values[1]);
+            yield_constr.constraint_transition(
+                vars.next_values[1] - vars.local_values[0] - vars.local_values[1],
+            );
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let x0 = builder.sub_extension(vars.local_values[0], vars.public_inputs[0]);
+            yield_constr.constraint_first_row(builder, x0);
+            let x1 = builder.sub_extension(vars.local_values[1], vars.public_inputs[1]);
+            yield_constr.constraint_first_row(builder, x1);
+            let result = builder.sub_extension(vars.local_values[1], vars.public_inputs[2]);
+            yield_constr.constraint_last_row(builder, result);
+            let next_x0 = builder.sub_extension(vars.next_values[0], vars.local_values[1]);
+            yield_constr.constraint_transition(builder, next_x0);
+            let next_x1 = builder.sub_extension(vars.next_values[1], vars.local_values[0]);
+            let next_x1 = builder.sub_extension(next_x1, vars.local_values[1]);
+            yield_constr.constraint_transition(builder, next_x1);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            2
+        }
+
+        fn permutation_pairs(&self) -> Vec<PermutationPair> {
+            vec![PermutationPair::singletons(2, 3)]
+        }
+    }
+
+    impl<Fp: PrimeField> StarkConstrainer<Fp, D> for FibonacciStark {
+        fn columns(&self) -> usize {
+            4
+        }
+
+        fn constraint_degree(&self) -> usize {
+            2
+        }
+
+        fn permutation_pairs(&self) -> Vec<PermutationPair> {
+            vec![PermutationPair::singletons(2, 3)]
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            ctx: &mut RegionCtx<'_, Fp>,
+            goldilocks_chip_config: &GoldilocksChipConfig<Fp>,
+            vars: &StarkEvaluationVars<'_, Fp, D>,
+            yield_constr: &mut ConstraintConsumer<Fp, D>,
+        ) -> Result<(), Error> {
+            let chip = self.goldilocks_extension_chip(goldilocks_chip_config);
+            let x0 = chip.sub_extension(ctx, &vars.local_values[0], &vars.public_inputs[0])?;
+            yield_constr.constraint_first_row(ctx, &x0)?;
+            let x1 = chip.sub_extension(ctx, &vars.local_values[1], &vars.public_inputs[1])?;
+            yield_constr.constraint_first_row(ctx, &x1)?;
+            let result = chip.sub_extension(ctx, &vars.local_values[1], &vars.public_inputs[2])?;
+            yield_constr.constraint_last_row(ctx, &result)?;
+            let next_x0 = chip.sub_extension(ctx, &vars.next_values[0], &vars.local_values[1])?;
+            yield_constr.constraint_transition(ctx, &next_x0)?;
+            let next_x1 = chip.sub_extension(ctx, &vars.next_values[1], &vars.local_values[0])?;
+            let next_x1 = chip.sub_extension(ctx, &next_x1, &vars.local_values[1])?;
+            yield_constr.constraint_transition(ctx, &next_x1)
+        }
+    }
+
+    fn generate_fibonacci_stark_proof(
+        stark: FibonacciStark,
+        config: &StarkConfig,
+    ) -> StarkProofWithPublicInputs<F, Bn254PoseidonGoldilocksConfig, D> {
+        let (x0, x1) = (F::ZERO, F::ONE);
+        let result = (1..stark.num_rows)
+            .fold((x0, x1), |x, _| (x.1, x.0 + x.1))
+            .1;
+        let trace = trace_rows_to_poly_values(stark.generate_trace(x0, x1));
+        let proof = prove::<F, Bn254PoseidonGoldilocksConfig, FibonacciStark, D>(
+            stark,
+            config,
+            trace,
+            [x0, x1, result],
+            &mut TimingTree::default(),
+        )
+        .unwrap();
+        verify_stark_proof(stark, proof.clone(), config).unwrap();
+        proof
+    }
+
+    #[test]
+    fn test_fibonacci_stark_halo2_mock() {
+        let config = StarkConfig::standard_fast_config();
+        let stark = FibonacciStark { num_rows: 1 << 5 };
+        let proof = generate_fibonacci_stark_proof(stark, &config);
+        let instances = verify_stark_inside_snark_mock(19, stark, proof, &config).unwrap();
+        assert_eq!(instances.len(), 3);
+    }
+
+    #[test]
+    fn test_fibonacci_stark_halo2_mock_wrong_public_inputs() {
+        let config = StarkConfig::standard_fast_config();
+        let stark = FibonacciStark { num_rows: 1 << 5 };
+        let mut proof = generate_fibonacci_stark_proof(stark, &config);
+        proof.public_inputs[2] += F::ONE;
+        let result = verify_stark_inside_snark_mock(19, stark, proof, &config);
+        assert!(matches!(
+            result,
+            Err(VerifierError::ConstraintsNotSatisfied(_))
+        ));
     }
 }
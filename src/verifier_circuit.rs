@@ -6,30 +6,57 @@ use halo2_proofs::{
 };
 use halo2wrong_maingate::{AssignedValue, MainGate, MainGateConfig, RangeChip, RangeConfig};
 use itertools::Itertools;
-use plonky2::plonk::{
-    circuit_data::{CommonCircuitData, VerifierOnlyCircuitData},
-    proof::ProofWithPublicInputs,
+use plonky2::{
+    field::{
+        goldilocks_field::GoldilocksField,
+        types::{Field, PrimeField64},
+    },
+    hash::poseidon::PoseidonHash,
+    plonk::{
+        circuit_data::{CommonCircuitData, VerifierOnlyCircuitData},
+        config::Hasher,
+        proof::ProofWithPublicInputs,
+    },
+};
+use sha3::{Digest as _, Keccak256};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
 };
-use std::marker::PhantomData;
 
 use super::{
+    bn254_poseidon::native::encode_fe,
     chip::{
         goldilocks_chip::{GoldilocksChip, GoldilocksChipConfig},
-        native_chip::all_chip::AllChipConfig,
+        goldilocks_extension_chip::check_extension_degree,
+        hasher_chip::{HasherChip, HasherInstructions},
+        keccak_hasher_chip::WithKeccakChip,
+        native_chip::{
+            all_chip::AllChipConfig,
+            utils::{fe_to_goldilocks, goldilocks_to_fe},
+        },
         plonk::plonk_verifier_chip::PlonkVerifierChip,
+        stark::{stark_verifier_chip::StarkVerifierChip, StarkConstrainer},
     },
     context::RegionCtx,
+    diagnostics::RowAnnotations,
+    error::VerifierError,
     types::{
         assigned::{
-            AssignedProofValues, AssignedProofWithPisValues, AssignedVerificationKeyValues,
+            AssignedHashValues, AssignedProofValues, AssignedProofWithPisValues,
+            AssignedVerificationKeyValues,
         },
         common_data::CommonData,
         proof::{FriProofValues, OpeningSetValues},
+        stark::{StarkCommonData, StarkProofValues},
         verification_key::VerificationKeyValues,
         HashValues, MerkleCapValues,
     },
 };
 
+const VERIFY_PROOF_REGION: &str = "Verify proof";
+const VERIFY_PROOFS_REGION: &str = "Verify proofs";
+
 pub type ProofTuple<F, C, const D: usize> = (
     ProofWithPublicInputs<F, C, D>,
     VerifierOnlyCircuitData<C, D>,
@@ -55,26 +82,97 @@ impl<F: PrimeField> MainGateWithRangeConfig<F> {
     }
 }
 
+/// How the verification key of the plonky2 circuit is assigned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VerificationKeyMode {
+    /// The verification key is fixed in the circuit, so each plonky2 circuit needs its own
+    /// Halo2 keys.
+    #[default]
+    Constant,
+    /// The verification key is a witness and its BN254 Poseidon digest is exposed after the
+    /// public inputs, so the same Halo2 keys verify every plonky2 circuit with the same
+    /// `CommonData`.
+    Witness,
+}
+
+/// Verifies a plonky2 proof whose Merkle trees and challenger use the hasher `H`.
 #[derive(Clone)]
-pub struct Verifier {
-    proof: ProofValues<Fr, 2>,
+pub struct Verifier<const D: usize, H: HasherInstructions<Fr> = HasherChip<Fr>> {
+    proof: ProofValues<Fr, D>,
     instances: Vec<Fr>,
     vk: VerificationKeyValues<Fr>,
-    common_data: CommonData<Fr>,
+    common_data: CommonData<Fr, D>,
+    vk_mode: VerificationKeyMode,
+    public_inputs_mode: PublicInputsMode,
+    // the namespaces of the rows of the last synthesis, shared by clones
+    annotations: Arc<Mutex<RowAnnotations>>,
+    _marker: PhantomData<H>,
 }
 
-impl Verifier {
+/// A `Verifier` with the Keccak chip, for `PublicInputsMode::Keccak`.
+pub type KeccakCommitmentVerifier<const D: usize> = Verifier<D, WithKeccakChip<HasherChip<Fr>>>;
+
+impl<const D: usize, H: HasherInstructions<Fr>> Verifier<D, H> {
+    /// Returns `VerifierError::UnsupportedExtensionDegree` unless `D` is 2 or 4.
     pub fn new(
-        proof: ProofValues<Fr, 2>,
+        proof: ProofValues<Fr, D>,
         instances: Vec<Fr>,
         vk: VerificationKeyValues<Fr>,
-        common_data: CommonData<Fr>,
-    ) -> Self {
-        Self {
+        common_data: CommonData<Fr, D>,
+    ) -> Result<Self, VerifierError> {
+        check_extension_degree::<D>()?;
+        Ok(Self {
             proof,
             instances,
             vk,
             common_data,
+            vk_mode: VerificationKeyMode::default(),
+            public_inputs_mode: PublicInputsMode::default(),
+            annotations: Arc::default(),
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn with_vk_mode(mut self, vk_mode: VerificationKeyMode) -> Self {
+        self.vk_mode = vk_mode;
+        self
+    }
+
+    /// Sets how the public inputs are exposed. `PublicInputsMode::Keccak` is only available when
+    /// `H` configures the Keccak chip, e.g. for `KeccakCommitmentVerifier`.
+    pub fn with_public_inputs_mode(
+        mut self,
+        public_inputs_mode: PublicInputsMode,
+    ) -> Result<Self, VerifierError> {
+        public_inputs_mode.check_hasher::<H>()?;
+        self.public_inputs_mode = public_inputs_mode;
+        Ok(self)
+    }
+
+    /// Returns the instances exposed by the circuit: the public inputs as given by the
+    /// `PublicInputsMode`, followed by the digest of the verification key when it is a witness.
+    pub fn instances(&self) -> Vec<Fr> {
+        let mut instances = self.public_inputs_mode.instances(&self.instances);
+        instances.extend(self.vk_digest_instances());
+        instances
+    }
+
+    /// Returns the namespaces of the rows assigned by the last synthesis of the circuit, to
+    /// explain the failures of the mock prover with `diagnostics::explain_failures`.
+    pub fn row_annotations(&self) -> RowAnnotations {
+        self.annotations.lock().unwrap().clone()
+    }
+
+    fn vk_digest_instances(&self) -> Vec<Fr> {
+        match self.vk_mode {
+            VerificationKeyMode::Constant => vec![],
+            VerificationKeyMode::Witness => self
+                .vk
+                .digest()
+                .elements
+                .into_iter()
+                .map(goldilocks_to_fe)
+                .collect(),
         }
     }
 
@@ -82,9 +180,9 @@ impl Verifier {
         &self,
         config: &GoldilocksChipConfig<Fr>,
         ctx: &mut RegionCtx<'_, Fr>,
-        proof: &ProofValues<Fr, 2>,
+        proof: &ProofValues<Fr, D>,
         instances: &Vec<Fr>,
-    ) -> Result<AssignedProofWithPisValues<Fr, 2>, Error> {
+    ) -> Result<AssignedProofWithPisValues<Fr, D>, Error> {
         let goldilocks_chip = GoldilocksChip::new(config);
 
         let public_inputs = instances
@@ -125,9 +223,92 @@ impl Verifier {
             circuit_digest: HashValues::assign_constant(config, ctx, &vk.circuit_digest)?,
         })
     }
+
+    /// Assigns the verification key as a witness and computes its digest. The digest is always
+    /// the BN254 Poseidon hash of `VerificationKeyValues::digest`, whatever the hasher `H` of the
+    /// proof, so that it does not depend on the plonky2 config.
+    pub fn assign_verification_key_witness(
+        &self,
+        config: &GoldilocksChipConfig<Fr>,
+        ctx: &mut RegionCtx<'_, Fr>,
+        vk: &VerificationKeyValues<Fr>,
+    ) -> Result<(AssignedVerificationKeyValues<Fr>, AssignedHashValues<Fr>), Error> {
+        let assigned_vk = AssignedVerificationKeyValues {
+            constants_sigmas_cap: MerkleCapValues::assign(config, ctx, &vk.constants_sigmas_cap)?,
+            circuit_digest: HashValues::assign(config, ctx, &vk.circuit_digest)?,
+        };
+        let inputs = assigned_vk
+            .constants_sigmas_cap
+            .0
+            .iter()
+            .chain(std::iter::once(&assigned_vk.circuit_digest))
+            .flat_map(|hash| hash.elements.clone())
+            .collect_vec();
+        let mut hasher = HasherChip::<Fr>::new(ctx, config)?;
+        let digest = hasher.hash(ctx, inputs, 4)?;
+        Ok((
+            assigned_vk,
+            AssignedHashValues {
+                elements: digest.try_into().unwrap(),
+            },
+        ))
+    }
+
+    /// Verifies the proof inside `ctx` and returns the values to expose: its assigned public
+    /// inputs and their hash, followed by the verification key digest in
+    /// `VerificationKeyMode::Witness`.
+    pub(crate) fn verify_proof(
+        &self,
+        config: &GoldilocksChipConfig<Fr>,
+        ctx: &mut RegionCtx<'_, Fr>,
+    ) -> Result<
+        (
+            Vec<AssignedValue<Fr>>,
+            AssignedHashValues<Fr>,
+            Vec<AssignedValue<Fr>>,
+        ),
+        Error,
+    > {
+        let assigned_proof_with_pis =
+            self.assign_proof_with_pis(config, ctx, &self.proof, &self.instances)?;
+        let (assigned_vk, vk_digest) = match self.vk_mode {
+            VerificationKeyMode::Constant => {
+                (self.assign_verification_key(config, ctx, &self.vk)?, vec![])
+            }
+            VerificationKeyMode::Witness => {
+                let (assigned_vk, digest) =
+                    self.assign_verification_key_witness(config, ctx, &self.vk)?;
+                (assigned_vk, digest.elements.to_vec())
+            }
+        };
+        let plonk_verifier_chip = PlonkVerifierChip::<Fr, D, H>::construct(config);
+        let public_inputs_hash = plonk_verifier_chip
+            .get_public_inputs_hash(ctx, &assigned_proof_with_pis.public_inputs)?;
+        let challenges = plonk_verifier_chip.get_challenges(
+            ctx,
+            &public_inputs_hash,
+            &assigned_vk.circuit_digest,
+            &self.common_data,
+            &assigned_proof_with_pis.proof,
+            self.common_data.config.num_challenges,
+        )?;
+        plonk_verifier_chip.verify_proof_with_challenges(
+            ctx,
+            &assigned_proof_with_pis.proof,
+            &public_inputs_hash,
+            &challenges,
+            &assigned_vk,
+            &self.common_data,
+        )?;
+        Ok((
+            assigned_proof_with_pis.public_inputs,
+            public_inputs_hash,
+            vk_digest,
+        ))
+    }
 }
 
-impl Circuit<Fr> for Verifier {
+impl<const D: usize, H: HasherInstructions<Fr>> Circuit<Fr> for Verifier<D, H> {
     type Config = GoldilocksChipConfig<Fr>;
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -137,9 +318,326 @@ impl Circuit<Fr> for Verifier {
             instances: self.instances.clone(),
             vk: self.vk.clone(),
             common_data: self.common_data.clone(),
+            vk_mode: self.vk_mode,
+            public_inputs_mode: self.public_inputs_mode,
+            annotations: self.annotations.clone(),
+            _marker: PhantomData,
         }
     }
 
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        H::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let goldilocks_chip_config = config.clone();
+        let goldilocks_chip = GoldilocksChip::new(&goldilocks_chip_config);
+        goldilocks_chip.load_table(&mut layouter)?;
+        let exposed = layouter.assign_region(
+            || VERIFY_PROOF_REGION,
+            |region| {
+                let ctx = &mut RegionCtx::new(region, 0);
+                let (public_inputs, public_inputs_hash, vk_digest) =
+                    self.verify_proof(&goldilocks_chip_config, ctx)?;
+                let public_inputs = match self.public_inputs_mode {
+                    PublicInputsMode::Raw => public_inputs,
+                    PublicInputsMode::Packed => {
+                        pack_public_inputs(&goldilocks_chip_config, ctx, &public_inputs)?
+                    }
+                    PublicInputsMode::Digest => public_inputs_hash.elements.to_vec(),
+                    PublicInputsMode::Keccak => {
+                        keccak_public_inputs(&goldilocks_chip_config, ctx, &public_inputs)?
+                    }
+                };
+                *self.annotations.lock().unwrap() = ctx.take_annotations(VERIFY_PROOF_REGION);
+                Ok([public_inputs, vk_digest].concat())
+            },
+        )?;
+        for (row, public_input) in (0..self.instances().len()).zip_eq(exposed) {
+            goldilocks_chip.arithmetic_chip().expose_public(
+                layouter.namespace(|| ""),
+                public_input,
+                row,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// How the public inputs of the verified proofs are exposed as instances.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PublicInputsMode {
+    /// Each public input is exposed as its own instance.
+    #[default]
+    Raw,
+    /// The public inputs are packed three per instance, like `ArithmeticChip::pack`, the last
+    /// instance being zero-padded.
+    Packed,
+    /// Only the plonky2 hash of the public inputs, with Goldilocks Poseidon, is exposed as 4
+    /// instances.
+    Digest,
+    /// Only the Keccak-256 of the public inputs, concatenated as 8-byte big-endian words, is
+    /// exposed as its high and low 128 bits. The hasher of the verifier must configure the Keccak
+    /// chip, e.g. `WithKeccakChip`.
+    Keccak,
+}
+
+impl PublicInputsMode {
+    // The Keccak chip is only configured by some hashers.
+    fn check_hasher<H: HasherInstructions<Fr>>(&self) -> Result<(), VerifierError> {
+        if *self == PublicInputsMode::Keccak && !H::CONFIGURES_KECCAK {
+            return Err(VerifierError::KeccakChipNotConfigured);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of instances exposing `num_public_inputs` public inputs, i.e. the
+    /// length of `instances`.
+    pub fn num_instances(&self, num_public_inputs: usize) -> usize {
+        match self {
+            PublicInputsMode::Raw => num_public_inputs,
+            PublicInputsMode::Packed => num_public_inputs.div_ceil(3),
+            PublicInputsMode::Digest => 4,
+            PublicInputsMode::Keccak => 2,
+        }
+    }
+
+    /// Maps the public inputs, as Goldilocks elements in `Fr`, to the instances exposing them.
+    pub fn instances(&self, public_inputs: &[Fr]) -> Vec<Fr> {
+        match self {
+            PublicInputsMode::Raw => public_inputs.to_vec(),
+            PublicInputsMode::Packed => public_inputs
+                .chunks(3)
+                .map(|chunk| {
+                    let mut packed = [GoldilocksField::ZERO; 3];
+                    for (p, x) in packed.iter_mut().zip(chunk) {
+                        *p = fe_to_goldilocks(*x);
+                    }
+                    encode_fe(packed)
+                })
+                .collect(),
+            PublicInputsMode::Digest => {
+                let public_inputs = public_inputs
+                    .iter()
+                    .map(|x| fe_to_goldilocks(*x))
+                    .collect_vec();
+                PoseidonHash::hash_no_pad(&public_inputs)
+                    .elements
+                    .into_iter()
+                    .map(goldilocks_to_fe)
+                    .collect()
+            }
+            PublicInputsMode::Keccak => {
+                let bytes = public_inputs
+                    .iter()
+                    .flat_map(|x| fe_to_goldilocks(*x).to_canonical_u64().to_be_bytes())
+                    .collect_vec();
+                Keccak256::digest(bytes)
+                    .chunks(16)
+                    .map(|half| Fr::from_u128(u128::from_be_bytes(half.try_into().unwrap())))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Packs the assigned public inputs three per cell, see `PublicInputsMode::Packed`.
+fn pack_public_inputs(
+    config: &GoldilocksChipConfig<Fr>,
+    ctx: &mut RegionCtx<'_, Fr>,
+    public_inputs: &[AssignedValue<Fr>],
+) -> Result<Vec<AssignedValue<Fr>>, Error> {
+    let goldilocks_chip = GoldilocksChip::new(config);
+    let arithmetic_chip = goldilocks_chip.arithmetic_chip();
+    let zero = goldilocks_chip.assign_constant(ctx, GoldilocksField::ZERO)?;
+    public_inputs
+        .chunks(3)
+        .map(|chunk| {
+            let mut packed = [zero.clone(), zero.clone(), zero.clone()];
+            packed[..chunk.len()].clone_from_slice(chunk);
+            arithmetic_chip.pack(ctx, packed)
+        })
+        .collect()
+}
+
+/// Hashes the assigned public inputs with Keccak-256, see `PublicInputsMode::Keccak`.
+fn keccak_public_inputs(
+    config: &GoldilocksChipConfig<Fr>,
+    ctx: &mut RegionCtx<'_, Fr>,
+    public_inputs: &[AssignedValue<Fr>],
+) -> Result<Vec<AssignedValue<Fr>>, Error> {
+    let keccak_chip = GoldilocksChip::new(config).all_chip().keccak_chip();
+    Ok(keccak_chip.keccak256_be_words(ctx, public_inputs)?.to_vec())
+}
+
+/// Verifies several plonky2 proofs, possibly of different circuits, in a single region.
+#[derive(Clone)]
+pub struct MultiVerifier<const D: usize, H: HasherInstructions<Fr> = HasherChip<Fr>> {
+    verifiers: Vec<Verifier<D, H>>,
+    public_inputs_mode: PublicInputsMode,
+    annotations: Arc<Mutex<RowAnnotations>>,
+}
+
+impl<const D: usize, H: HasherInstructions<Fr>> MultiVerifier<D, H> {
+    /// `PublicInputsMode::Keccak` is only available when `H` configures the Keccak chip, e.g.
+    /// `WithKeccakChip`.
+    pub fn new(
+        verifiers: Vec<Verifier<D, H>>,
+        public_inputs_mode: PublicInputsMode,
+    ) -> Result<Self, VerifierError> {
+        public_inputs_mode.check_hasher::<H>()?;
+        Ok(Self {
+            verifiers,
+            public_inputs_mode,
+            annotations: Arc::default(),
+        })
+    }
+
+    /// Returns the namespaces of the rows assigned by the last synthesis of the circuit, the
+    /// rows of each proof being under "proof i".
+    pub fn row_annotations(&self) -> RowAnnotations {
+        self.annotations.lock().unwrap().clone()
+    }
+
+    /// Returns the instances exposed by the circuit: the public inputs of all proofs
+    /// concatenated in order, packed or hashed as given by the `PublicInputsMode`, followed by
+    /// the digests of the verification keys assigned as witnesses.
+    pub fn instances(&self) -> Vec<Fr> {
+        let public_inputs = self
+            .verifiers
+            .iter()
+            .flat_map(|verifier| verifier.instances.clone())
+            .collect_vec();
+        let vk_digests = self
+            .verifiers
+            .iter()
+            .flat_map(|verifier| verifier.vk_digest_instances())
+            .collect_vec();
+        [
+            self.public_inputs_mode.instances(&public_inputs),
+            vk_digests,
+        ]
+        .concat()
+    }
+}
+
+impl<const D: usize, H: HasherInstructions<Fr>> Circuit<Fr> for MultiVerifier<D, H> {
+    type Config = GoldilocksChipConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            verifiers: self
+                .verifiers
+                .iter()
+                .map(|verifier| verifier.without_witnesses())
+                .collect(),
+            public_inputs_mode: self.public_inputs_mode,
+            annotations: self.annotations.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        H::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let goldilocks_chip_config = config.clone();
+        let goldilocks_chip = GoldilocksChip::new(&goldilocks_chip_config);
+        goldilocks_chip.load_table(&mut layouter)?;
+        let exposed = layouter.assign_region(
+            || VERIFY_PROOFS_REGION,
+            |region| {
+                let ctx = &mut RegionCtx::new(region, 0);
+                let mut public_inputs = vec![];
+                let mut vk_digests = vec![];
+                for (i, verifier) in self.verifiers.iter().enumerate() {
+                    let (pis, _, vk_digest) = ctx.namespaced(
+                        || format!("proof {i}"),
+                        |ctx| verifier.verify_proof(&goldilocks_chip_config, ctx),
+                    )?;
+                    public_inputs.extend(pis);
+                    vk_digests.extend(vk_digest);
+                }
+                let public_inputs = match self.public_inputs_mode {
+                    PublicInputsMode::Raw => public_inputs,
+                    PublicInputsMode::Packed => {
+                        pack_public_inputs(&goldilocks_chip_config, ctx, &public_inputs)?
+                    }
+                    PublicInputsMode::Digest => {
+                        let plonk_verifier_chip =
+                            PlonkVerifierChip::<Fr, D, H>::construct(&goldilocks_chip_config);
+                        let hash =
+                            plonk_verifier_chip.get_public_inputs_hash(ctx, &public_inputs)?;
+                        hash.elements.to_vec()
+                    }
+                    PublicInputsMode::Keccak => {
+                        keccak_public_inputs(&goldilocks_chip_config, ctx, &public_inputs)?
+                    }
+                };
+                *self.annotations.lock().unwrap() = ctx.take_annotations(VERIFY_PROOFS_REGION);
+                Ok([public_inputs, vk_digests].concat())
+            },
+        )?;
+        for (row, value) in exposed.into_iter().enumerate() {
+            goldilocks_chip.arithmetic_chip().expose_public(
+                layouter.namespace(|| ""),
+                value,
+                row,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Verifies a Starky proof of the AIR `stark`, exposing its public inputs as instances.
+#[derive(Clone)]
+pub struct StarkVerifier<S: StarkConstrainer<Fr, D> + Clone, const D: usize> {
+    stark: S,
+    proof: StarkProofValues<Fr, D>,
+    public_inputs: Vec<Fr>,
+    common_data: StarkCommonData<D>,
+}
+
+impl<S: StarkConstrainer<Fr, D> + Clone, const D: usize> StarkVerifier<S, D> {
+    /// Returns `VerifierError::UnsupportedExtensionDegree` unless `D` is 2 or 4.
+    pub fn new(
+        stark: S,
+        proof: StarkProofValues<Fr, D>,
+        public_inputs: Vec<Fr>,
+        common_data: StarkCommonData<D>,
+    ) -> Result<Self, VerifierError> {
+        check_extension_degree::<D>()?;
+        Ok(Self {
+            stark,
+            proof,
+            public_inputs,
+            common_data,
+        })
+    }
+
+    /// Returns the instances exposed by the circuit, the public inputs of the Starky proof.
+    pub fn instances(&self) -> Vec<Fr> {
+        self.public_inputs.clone()
+    }
+}
+
+impl<S: StarkConstrainer<Fr, D> + Clone, const D: usize> Circuit<Fr> for StarkVerifier<S, D> {
+    type Config = GoldilocksChipConfig<Fr>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
     fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
         let all_chip_config = AllChipConfig::<Fr>::configure(meta);
         GoldilocksChip::configure(&all_chip_config)
@@ -153,43 +651,23 @@ impl Circuit<Fr> for Verifier {
         let goldilocks_chip_config = config.clone();
         let goldilocks_chip = GoldilocksChip::new(&goldilocks_chip_config);
         goldilocks_chip.load_table(&mut layouter)?;
-        let assigned_proof_with_pis = layouter.assign_region(
-            || "Verify proof",
+        let public_inputs = layouter.assign_region(
+            || "Verify stark proof",
             |region| {
                 let ctx = &mut RegionCtx::new(region, 0);
-                let assigned_proof_with_pis = self.assign_proof_with_pis(
-                    &goldilocks_chip_config,
-                    ctx,
-                    &self.proof,
-                    &self.instances,
-                )?;
-                let assigned_vk =
-                    self.assign_verification_key(&goldilocks_chip_config, ctx, &self.vk)?;
-                let plonk_verifier_chip = PlonkVerifierChip::construct(&goldilocks_chip_config);
-                let public_inputs_hash = plonk_verifier_chip
-                    .get_public_inputs_hash(ctx, &assigned_proof_with_pis.public_inputs)?;
-                let challenges = plonk_verifier_chip.get_challenges(
-                    ctx,
-                    &public_inputs_hash,
-                    &assigned_vk.circuit_digest,
-                    &self.common_data,
-                    &assigned_proof_with_pis.proof,
-                    self.common_data.config.num_challenges,
-                )?;
-                plonk_verifier_chip.verify_proof_with_challenges(
-                    ctx,
-                    &assigned_proof_with_pis.proof,
-                    &public_inputs_hash,
-                    &challenges,
-                    &assigned_vk,
-                    &self.common_data,
-                )?;
-                Ok(assigned_proof_with_pis)
+                let proof = StarkProofValues::assign(&goldilocks_chip_config, ctx, &self.proof)?;
+                let public_inputs = self
+                    .public_inputs
+                    .iter()
+                    .map(|pi| goldilocks_chip.assign_value(ctx, Value::known(*pi)))
+                    .collect::<Result<Vec<AssignedValue<Fr>>, Error>>()?;
+                let stark_verifier_chip =
+                    StarkVerifierChip::construct(&goldilocks_chip_config, self.stark.clone());
+                stark_verifier_chip.verify_proof(ctx, &proof, &public_inputs, &self.common_data)?;
+                Ok(public_inputs)
             },
         )?;
-        for (row, public_input) in
-            (0..self.instances.len()).zip_eq(assigned_proof_with_pis.public_inputs)
-        {
+        for (row, public_input) in public_inputs.into_iter().enumerate() {
             goldilocks_chip.arithmetic_chip().expose_public(
                 layouter.namespace(|| ""),
                 public_input,